@@ -30,6 +30,8 @@ fn main() {
             TickTypes::RequestParameters(tick_request_parameters) => println!("{:?}", tick_request_parameters),
             TickTypes::SnapshotEnd => subscription.cancel(),
             TickTypes::Notice(notice) => println!("{:?}", notice),
+            TickTypes::News(news) => println!("{:?}", news),
+            TickTypes::MarketDataType(market_data_type) => println!("{:?}", market_data_type),
         }
     }
 }
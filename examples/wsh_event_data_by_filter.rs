@@ -22,7 +22,7 @@ fn main() {
     let filter = filter.replace("\n", "").to_string();
 
     let subscription = client
-        .wsh_event_data_by_filter(&filter, None, None)
+        .wsh_event_data_by_filter(&filter, None, None, None, None)
         .expect("request wsh event data failed");
 
     for event_data in &subscription {
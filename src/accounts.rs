@@ -9,6 +9,10 @@
 //! - Real-time PnL updates for individual positions
 //!
 
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use log::warn;
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
@@ -35,6 +39,18 @@ pub struct AccountSummary {
     pub currency: String,
 }
 
+impl AccountSummary {
+    /// Parses the value as a number. Fails for non numeric tags such as `AccountType`.
+    pub fn value_f64(&self) -> Result<f64, Error> {
+        self.value
+            .parse()
+            .map_err(|err| Error::Simple(format!("{} value {:?} is not numeric: {err}", self.tag, self.value)))
+    }
+}
+
+/// Account summary values keyed by tag. Each entry holds the value and its currency.
+pub type AccountSummaryMap = HashMap<String, (String, String)>;
+
 pub struct AccountSummaryTags {}
 
 impl AccountSummaryTags {
@@ -99,6 +115,75 @@ impl AccountSummaryTags {
         Self::DAY_TRADES_REMAINING,
         Self::LEVERAGE,
     ];
+
+    /// Returns true if `tag` is one of [AccountSummaryTags::ALL] or a `$LEDGER` tag, e.g. `$LEDGER:USD`.
+    pub fn is_known(tag: &str) -> bool {
+        Self::ALL.contains(&tag) || tag == "$LEDGER" || tag.starts_with("$LEDGER:")
+    }
+}
+
+/// Builds the tag list for [account_summary](crate::Client::account_summary), combining named tags with ledger requests.
+///
+/// All tags, including the ledger tags, require server version 67 or later, the first to support account summary requests.
+///
+/// # Examples
+///
+/// ```
+/// use ibapi::accounts::{AccountSummaryTags, AccountSummaryTagsBuilder};
+///
+/// let tags = AccountSummaryTagsBuilder::new()
+///     .tag(AccountSummaryTags::NET_LIQUIDATION)
+///     .ledger_currency("EUR")
+///     .build();
+///
+/// assert_eq!(tags, "NetLiquidation,$LEDGER:EUR");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct AccountSummaryTagsBuilder {
+    tags: Vec<String>,
+}
+
+impl AccountSummaryTagsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a named tag, e.g. [AccountSummaryTags::NET_LIQUIDATION].
+    pub fn tag(self, tag: &str) -> Self {
+        self.push(tag.to_owned())
+    }
+
+    /// Adds several named tags, e.g. [AccountSummaryTags::ALL].
+    pub fn tags(self, tags: &[&str]) -> Self {
+        tags.iter().fold(self, |builder, tag| builder.tag(tag))
+    }
+
+    /// Requests ledger values in the account's base currency (`$LEDGER`).
+    pub fn ledger(self) -> Self {
+        self.push("$LEDGER".to_owned())
+    }
+
+    /// Requests ledger values in the given currency (`$LEDGER:CURRENCY`).
+    pub fn ledger_currency(self, currency: &str) -> Self {
+        self.push(format!("$LEDGER:{}", currency.to_uppercase()))
+    }
+
+    /// Requests ledger values in every currency held (`$LEDGER:ALL`).
+    pub fn ledger_all(self) -> Self {
+        self.push("$LEDGER:ALL".to_owned())
+    }
+
+    /// Returns the accumulated tags, comma separated, in the order added.
+    pub fn build(&self) -> String {
+        self.tags.join(",")
+    }
+
+    fn push(mut self, tag: String) -> Self {
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+        self
+    }
 }
 
 #[derive(Debug)]
@@ -178,6 +263,40 @@ impl DataStream<PnLSingle> for PnLSingle {
     }
 }
 
+/// Point in time P&L of an account and its positions. See [Client::portfolio_pnl].
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PortfolioPnL {
+    /// Account the P&L is for.
+    pub account: String,
+    /// Daily P&L of the account.
+    pub daily_pnl: f64,
+    /// Total unrealized P&L of the account.
+    pub unrealized_pnl: Option<f64>,
+    /// Total realized P&L of the account.
+    pub realized_pnl: Option<f64>,
+    /// P&L of each position held in the account.
+    pub positions: Vec<PositionPnL>,
+}
+
+/// P&L of a single position within a [PortfolioPnL].
+///
+/// TWS does not send P&L for every position, e.g. for positions closed today. The P&L fields of such positions are `None`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PositionPnL {
+    /// Contract held.
+    pub contract: Contract,
+    /// Size of the position.
+    pub position: f64,
+    /// Daily P&L of the position.
+    pub daily_pnl: Option<f64>,
+    /// Unrealized P&L of the position.
+    pub unrealized_pnl: Option<f64>,
+    /// Realized P&L of the position.
+    pub realized_pnl: Option<f64>,
+    /// Market value of the position.
+    pub value: Option<f64>,
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Position {
     /// Account holding position
@@ -190,6 +309,64 @@ pub struct Position {
     pub average_cost: f64,
 }
 
+impl Position {
+    /// Cost basis of the position (position size times average cost), in the contract's currency.
+    pub fn cost_basis(&self) -> f64 {
+        self.position * self.average_cost
+    }
+}
+
+/// Sums the cost basis of `positions` converted to `base_currency`.
+///
+/// FX rates are not requested from TWS and must be supplied by the caller. `fx_rates` maps a currency code to the
+/// number of units of `base_currency` that one unit of that currency buys, e.g. `"EUR" => 1.08` when the base currency is USD.
+/// Positions already in `base_currency` need no entry.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::collections::HashMap;
+///
+/// use ibapi::accounts::{self, PositionUpdate};
+/// use ibapi::Client;
+///
+/// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+///
+/// let positions: Vec<_> = client
+///     .positions()
+///     .expect("request failed")
+///     .iter()
+///     .take_while(|update| !matches!(update, PositionUpdate::PositionEnd))
+///     .filter_map(|update| match update {
+///         PositionUpdate::Position(position) => Some(position),
+///         PositionUpdate::PositionEnd => None,
+///     })
+///     .collect();
+///
+/// let fx_rates = HashMap::from([("EUR".to_string(), 1.08)]);
+/// let total = accounts::total_cost_basis(&positions, "USD", &fx_rates).expect("missing FX rate");
+/// println!("total cost basis: {total} USD");
+/// ```
+pub fn total_cost_basis(positions: &[Position], base_currency: &str, fx_rates: &HashMap<String, f64>) -> Result<f64, Error> {
+    let mut total = 0.0;
+
+    for position in positions {
+        let currency = &position.contract.currency;
+        let rate = if currency == base_currency {
+            1.0
+        } else {
+            match fx_rates.get(currency) {
+                Some(rate) => *rate,
+                None => return Err(Error::Simple(format!("no FX rate from {currency} to {base_currency}"))),
+            }
+        };
+
+        total += position.cost_basis() * rate;
+    }
+
+    Ok(total)
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug)]
 pub enum PositionUpdate {
@@ -235,6 +412,62 @@ pub struct PositionMulti {
     pub average_cost: f64,
 }
 
+impl PositionMulti {
+    /// Cost basis of the position (position size times average cost), in the contract's currency.
+    pub fn cost_basis(&self) -> f64 {
+        self.position * self.average_cost
+    }
+}
+
+/// Net exposure of the positions held under one model. See [exposure_by_model].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelExposure {
+    /// Sum of position sizes, long positions counting positive and short positions negative.
+    pub quantity: f64,
+    /// Sum of position cost bases, in the currencies of the contracts.
+    pub cost: f64,
+}
+
+/// Groups `positions` by model code and sums the quantity and cost basis of each model.
+///
+/// Positions not held under a model are grouped under the empty model code. Costs are summed as reported, so
+/// positions in different currencies should be converted by the caller beforehand.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::accounts::{self, PositionUpdateMulti};
+/// use ibapi::Client;
+///
+/// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+///
+/// let positions: Vec<_> = client
+///     .positions_multi(Some("U1234567"), None)
+///     .expect("request failed")
+///     .iter()
+///     .take_while(|update| !matches!(update, PositionUpdateMulti::PositionEnd))
+///     .filter_map(|update| match update {
+///         PositionUpdateMulti::Position(position) => Some(position),
+///         PositionUpdateMulti::PositionEnd => None,
+///     })
+///     .collect();
+///
+/// for (model_code, exposure) in accounts::exposure_by_model(&positions) {
+///     println!("{model_code}: quantity {}, cost {}", exposure.quantity, exposure.cost);
+/// }
+/// ```
+pub fn exposure_by_model(positions: &[PositionMulti]) -> HashMap<String, ModelExposure> {
+    let mut exposures: HashMap<String, ModelExposure> = HashMap::new();
+
+    for position in positions {
+        let exposure = exposures.entry(position.model_code.clone()).or_default();
+        exposure.quantity += position.position;
+        exposure.cost += position.cost_basis();
+    }
+
+    exposures
+}
+
 impl DataStream<PositionUpdateMulti> for PositionUpdateMulti {
     const RESPONSE_MESSAGE_IDS: &[IncomingMessages] = &[IncomingMessages::PositionMulti, IncomingMessages::PositionMultiEnd];
 
@@ -410,6 +643,40 @@ pub(super) fn positions_multi<'a>(
     Ok(Subscription::new(client, subscription, ResponseContext::default()))
 }
 
+pub(super) fn positions_snapshot(client: &Client) -> Result<Vec<Position>, Error> {
+    let subscription = positions(client)?;
+
+    let mut positions = Vec::new();
+    loop {
+        match subscription.next() {
+            Some(PositionUpdate::Position(position)) => positions.push(position),
+            Some(PositionUpdate::PositionEnd) => return Ok(positions),
+            None => {
+                return Err(subscription
+                    .error()
+                    .unwrap_or_else(|| Error::Simple("positions ended before PositionEnd".into())))
+            }
+        }
+    }
+}
+
+pub(super) fn positions_multi_snapshot(client: &Client, account: Option<&str>, model_code: Option<&str>) -> Result<Vec<PositionMulti>, Error> {
+    let subscription = positions_multi(client, account, model_code)?;
+
+    let mut positions = Vec::new();
+    loop {
+        match subscription.next() {
+            Some(PositionUpdateMulti::Position(position)) => positions.push(position),
+            Some(PositionUpdateMulti::PositionEnd) => return Ok(positions),
+            None => {
+                return Err(subscription
+                    .error()
+                    .unwrap_or_else(|| Error::Simple("positions multi ended before PositionMultiEnd".into())))
+            }
+        }
+    }
+}
+
 // Determine whether an account exists under an account family and find the account family code.
 pub(super) fn family_codes(client: &Client) -> Result<Vec<FamilyCode>, Error> {
     client.check_server_version(server_versions::REQ_FAMILY_CODES, "It does not support family codes requests.")?;
@@ -463,7 +730,72 @@ pub(super) fn pnl_single<'a>(
     Ok(Subscription::new(client, subscription, ResponseContext::default()))
 }
 
+// Takes the first update of the account P&L and of the P&L of each of its positions, waiting at most timeout in total.
+// Positions without an update in time are kept with their P&L unset. Each subscription is cancelled when dropped.
+pub(super) fn portfolio_pnl(client: &Client, account: &str, timeout: Duration) -> Result<PortfolioPnL, Error> {
+    let deadline = Instant::now() + timeout;
+
+    let positions: Vec<Position> = positions_snapshot(client)?
+        .into_iter()
+        .filter(|position| position.account == account)
+        .collect();
+
+    // Subscribe to every position up front, so the updates arrive while waiting for the first ones.
+    let account_subscription = pnl(client, account, None)?;
+    let position_subscriptions = positions
+        .iter()
+        .map(|position| pnl_single(client, account, position.contract.contract_id, None))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let account_pnl = first_update(&account_subscription, deadline)?
+        .ok_or_else(|| Error::Simple(format!("no PnL received for account {account} within {timeout:?}")))?;
+
+    let mut portfolio = PortfolioPnL {
+        account: account.to_owned(),
+        daily_pnl: account_pnl.daily_pnl,
+        unrealized_pnl: account_pnl.unrealized_pnl,
+        realized_pnl: account_pnl.realized_pnl,
+        positions: Vec::with_capacity(positions.len()),
+    };
+
+    for (position, subscription) in positions.into_iter().zip(&position_subscriptions) {
+        let position_pnl = first_update(subscription, deadline)?;
+        if position_pnl.is_none() {
+            warn!("no PnL received for {} in account {account}", position.contract.symbol);
+        }
+
+        portfolio.positions.push(PositionPnL {
+            contract: position.contract,
+            position: position.position,
+            daily_pnl: position_pnl.as_ref().map(|pnl| pnl.daily_pnl),
+            unrealized_pnl: position_pnl.as_ref().map(|pnl| pnl.unrealized_pnl),
+            realized_pnl: position_pnl.as_ref().map(|pnl| pnl.realized_pnl),
+            value: position_pnl.as_ref().map(|pnl| pnl.value),
+        });
+    }
+
+    Ok(portfolio)
+}
+
+// Waits until deadline for the first update. Returns None if none arrived; errors reported by TWS are returned as Err.
+fn first_update<T: DataStream<T>>(subscription: &Subscription<T>, deadline: Instant) -> Result<Option<T>, Error> {
+    let update = subscription.next_timeout(deadline.saturating_duration_since(Instant::now()));
+    match (update, subscription.error()) {
+        (Some(update), _) => Ok(Some(update)),
+        (None, Some(error)) => Err(error),
+        (None, None) => Ok(None),
+    }
+}
+
 pub(super) fn account_summary<'a>(client: &'a Client, group: &str, tags: &[&str]) -> Result<Subscription<'a, AccountSummaries>, Error> {
+    if let Some(tag) = tags.iter().flat_map(|tag| tag.split(',')).find(|tag| !AccountSummaryTags::is_known(tag)) {
+        return Err(Error::InvalidArgument(format!("unknown account summary tag: {tag}")));
+    }
+
+    account_summary_unchecked(client, group, tags)
+}
+
+pub(super) fn account_summary_unchecked<'a>(client: &'a Client, group: &str, tags: &[&str]) -> Result<Subscription<'a, AccountSummaries>, Error> {
     client.check_server_version(server_versions::ACCOUNT_SUMMARY, "It does not support account summary requests.")?;
 
     let request_id = client.next_request_id();
@@ -473,6 +805,25 @@ pub(super) fn account_summary<'a>(client: &'a Client, group: &str, tags: &[&str]
     Ok(Subscription::new(client, subscription, ResponseContext::default()))
 }
 
+pub(super) fn account_summary_snapshot(client: &Client, group: &str, tags: &[&str]) -> Result<AccountSummaryMap, Error> {
+    let subscription = account_summary(client, group, tags)?;
+
+    let mut summary = AccountSummaryMap::new();
+    loop {
+        match subscription.next() {
+            Some(AccountSummaries::Summary(value)) => {
+                summary.insert(value.tag, (value.value, value.currency));
+            }
+            Some(AccountSummaries::End) => return Ok(summary),
+            None => {
+                return Err(subscription
+                    .error()
+                    .unwrap_or_else(|| Error::Simple("account summary ended before AccountSummaryEnd".into())))
+            }
+        }
+    }
+}
+
 pub(super) fn account_updates<'a>(client: &'a Client, account: &str) -> Result<Subscription<'a, AccountUpdate>, Error> {
     let request = encoders::encode_request_account_updates(client.server_version(), account)?;
     let subscription = client.send_shared_request(OutgoingMessages::RequestAccountData, request)?;
@@ -1,8 +1,10 @@
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use crate::accounts::AccountUpdateMulti;
+use crate::stubs::RoutedMessageBusStub;
 use crate::testdata::responses;
-use crate::{accounts::AccountSummaryTags, server_versions, stubs::MessageBusStub, Client};
+use crate::{accounts::AccountSummaryTags, accounts::AccountSummaryTagsBuilder, server_versions, stubs::MessageBusStub, Client, Error};
 
 #[test]
 fn test_pnl() {
@@ -169,3 +171,334 @@ fn test_account_updates_multi() {
     assert_eq!(request_messages[0].encode_simple(), "76|1|9000|DU1234567||1|");
     assert_eq!(request_messages[1].encode_simple(), "77|1|9000|");
 }
+
+#[test]
+fn test_total_cost_basis() {
+    use std::collections::HashMap;
+
+    use crate::accounts::{total_cost_basis, Position};
+    use crate::contracts::Contract;
+
+    let usd_position = Position {
+        account: "DU1234567".to_owned(),
+        contract: Contract::stock("AAPL"),
+        position: 10.0,
+        average_cost: 150.0,
+    };
+    let eur_position = Position {
+        account: "DU1234567".to_owned(),
+        contract: Contract {
+            currency: "EUR".to_owned(),
+            ..Contract::stock("SAP")
+        },
+        position: 20.0,
+        average_cost: 100.0,
+    };
+    let positions = vec![usd_position, eur_position];
+
+    assert_eq!(positions[1].cost_basis(), 2000.0);
+
+    let fx_rates = HashMap::from([("EUR".to_owned(), 1.1)]);
+    let total = total_cost_basis(&positions, "USD", &fx_rates).expect("conversion failed");
+    assert!((total - 3700.0).abs() < 1e-9, "total: {total}");
+
+    let result = total_cost_basis(&positions, "USD", &HashMap::new());
+    assert!(result.is_err(), "expected error for missing EUR rate");
+}
+
+#[test]
+fn test_exposure_by_model() {
+    use crate::accounts::{exposure_by_model, ModelExposure, PositionMulti};
+    use crate::contracts::Contract;
+
+    let position = |model_code: &str, symbol: &str, position: f64, average_cost: f64| PositionMulti {
+        account: "DU1234567".to_owned(),
+        model_code: model_code.to_owned(),
+        contract: Contract::stock(symbol),
+        position,
+        average_cost,
+    };
+
+    let positions = vec![
+        position("GROWTH", "AAPL", 10.0, 150.0),
+        position("GROWTH", "MSFT", 5.0, 300.0),
+        position("INCOME", "T", 100.0, 20.0),
+        position("INCOME", "VZ", -50.0, 40.0),
+    ];
+
+    assert_eq!(positions[3].cost_basis(), -2000.0);
+
+    let exposures = exposure_by_model(&positions);
+
+    assert_eq!(exposures.len(), 2, "expected one entry per model");
+    assert_eq!(
+        exposures["GROWTH"],
+        ModelExposure {
+            quantity: 15.0,
+            cost: 3000.0
+        }
+    );
+    assert_eq!(exposures["INCOME"], ModelExposure { quantity: 50.0, cost: 0.0 });
+}
+
+#[test]
+fn test_account_summary_rejects_unknown_tag() {
+    let message_bus = Arc::new(MessageBusStub::default());
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let tags = &[AccountSummaryTags::NET_LIQUIDATION, "NetLiquidaton"];
+
+    let result = client.account_summary("All", tags);
+    match result {
+        Err(Error::InvalidArgument(message)) => assert!(message.contains("NetLiquidaton"), "message should name the tag: {message}"),
+        _ => panic!("expected invalid argument for misspelled tag"),
+    }
+    assert!(client.message_bus.request_messages().is_empty(), "no request should be sent");
+
+    let _ = client
+        .account_summary_unchecked("All", tags)
+        .expect("unchecked request should pass tags through");
+    assert_eq!(
+        client.message_bus.request_messages()[0].encode_simple(),
+        "62|1|9000|All|NetLiquidation,NetLiquidaton|"
+    );
+}
+
+#[test]
+fn test_account_summary_tags_is_known() {
+    assert!(AccountSummaryTags::ALL.iter().all(|tag| AccountSummaryTags::is_known(tag)));
+    assert!(AccountSummaryTags::is_known("$LEDGER"));
+    assert!(AccountSummaryTags::is_known("$LEDGER:USD"));
+    assert!(!AccountSummaryTags::is_known("netliquidation"));
+    assert!(!AccountSummaryTags::is_known(""));
+}
+
+#[test]
+fn test_account_summary_value_f64() {
+    use crate::accounts::AccountSummary;
+
+    let summary = AccountSummary {
+        account: "DU1234567".to_owned(),
+        tag: AccountSummaryTags::NET_LIQUIDATION.to_owned(),
+        value: "1005852.73".to_owned(),
+        currency: "USD".to_owned(),
+    };
+    assert_eq!(summary.value_f64().unwrap(), 1005852.73);
+
+    let summary = AccountSummary {
+        account: "DU1234567".to_owned(),
+        tag: AccountSummaryTags::ACCOUNT_TYPE.to_owned(),
+        value: "INDIVIDUAL".to_owned(),
+        currency: "".to_owned(),
+    };
+    assert!(summary.value_f64().is_err(), "AccountType should not parse as a number");
+}
+
+#[test]
+fn test_account_summary_snapshot() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "63|1|9000|DU1234567|AccountType|INDIVIDUAL||".to_owned(),
+            "63|1|9000|DU1234567|NetLiquidation|1005852.73|USD|".to_owned(),
+            "63|1|9000|DU1234567|BuyingPower|4023410.92|USD|".to_owned(),
+            "64|1|9000|".to_owned(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let tags = &[
+        AccountSummaryTags::ACCOUNT_TYPE,
+        AccountSummaryTags::NET_LIQUIDATION,
+        AccountSummaryTags::BUYING_POWER,
+    ];
+    let summary = client.account_summary_snapshot("All", tags).expect("request account summary failed");
+
+    assert_eq!(summary.len(), 3);
+    assert_eq!(summary["AccountType"], ("INDIVIDUAL".to_owned(), "".to_owned()));
+    assert_eq!(summary["NetLiquidation"], ("1005852.73".to_owned(), "USD".to_owned()));
+    assert_eq!(summary["BuyingPower"].0.parse::<f64>().unwrap(), 4023410.92);
+}
+
+#[test]
+fn test_account_summary_tags_builder() {
+    let tags = AccountSummaryTagsBuilder::new()
+        .tag(AccountSummaryTags::NET_LIQUIDATION)
+        .tags(&[AccountSummaryTags::BUYING_POWER, AccountSummaryTags::NET_LIQUIDATION])
+        .ledger()
+        .ledger_currency("eur")
+        .build();
+    assert_eq!(tags, "NetLiquidation,BuyingPower,$LEDGER,$LEDGER:EUR");
+
+    assert_eq!(AccountSummaryTagsBuilder::new().ledger_all().build(), "$LEDGER:ALL");
+    assert_eq!(AccountSummaryTagsBuilder::new().build(), "");
+}
+
+#[test]
+fn test_account_summary_with_built_tags() {
+    let message_bus = Arc::new(MessageBusStub::default());
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let tags = AccountSummaryTagsBuilder::new()
+        .tag(AccountSummaryTags::NET_LIQUIDATION)
+        .ledger_currency("USD")
+        .build();
+
+    let _ = client.account_summary("All", &[&tags]).expect("request account summary failed");
+    assert_eq!(
+        client.message_bus.request_messages()[0].encode_simple(),
+        "62|1|9000|All|NetLiquidation,$LEDGER:USD|"
+    );
+}
+
+#[test]
+fn test_positions_snapshot() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "61|3|DU1234567|76792991|TSLA|STK||0.0|||NASDAQ|USD|TSLA|NMS|500|196.77|".to_owned(),
+            "61|3|DU1234567|265598|AAPL|STK||0.0|||NASDAQ|USD|AAPL|NMS|100|170.5|".to_owned(),
+            "61|3|DU7654321|272093|MSFT|STK||0.0|||NASDAQ|USD|MSFT|NMS|-50|410.25|".to_owned(),
+            "62|1|".to_owned(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let positions = client.positions_snapshot().expect("request positions failed");
+
+    assert_eq!(positions.len(), 3, "positions.len()");
+    assert_eq!(positions[0].contract.symbol, "TSLA");
+    assert_eq!(positions[0].position, 500.0);
+    assert_eq!(positions[1].contract.symbol, "AAPL");
+    assert_eq!(positions[2].account, "DU7654321");
+    assert_eq!(positions[2].position, -50.0);
+    assert_eq!(positions[2].average_cost, 410.25);
+
+    let request_messages = client.message_bus.request_messages();
+
+    assert_eq!(request_messages[0].encode_simple(), "61|1|");
+    assert_eq!(request_messages[1].encode_simple(), "64|1|", "subscription cancelled");
+}
+
+#[test]
+fn test_positions_multi_snapshot() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "71|1|9000|DU1234567|76792991|TSLA|STK||0.0|||NASDAQ|USD|TSLA|NMS|500|196.77|TARGET2024|".to_owned(),
+            "71|1|9000|DU1234567|265598|AAPL|STK||0.0|||NASDAQ|USD|AAPL|NMS|100|170.5|TARGET2024|".to_owned(),
+            "71|1|9000|DU1234567|272093|MSFT|STK||0.0|||NASDAQ|USD|MSFT|NMS|-50|410.25|TARGET2024|".to_owned(),
+            "72|1|9000|".to_owned(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let positions = client
+        .positions_multi_snapshot(Some("DU1234567"), Some("TARGET2024"))
+        .expect("request positions failed");
+
+    assert_eq!(positions.len(), 3, "positions.len()");
+    assert_eq!(positions[0].contract.symbol, "TSLA");
+    assert_eq!(positions[2].position, -50.0);
+    assert_eq!(positions[2].model_code, "TARGET2024");
+
+    let request_messages = client.message_bus.request_messages();
+
+    assert_eq!(request_messages[0].encode_simple(), "74|1|9000|DU1234567|TARGET2024|");
+    assert_eq!(request_messages[1].encode_simple(), "75|1|9000|", "subscription cancelled");
+}
+
+#[test]
+fn test_portfolio_pnl() {
+    let routes = [
+        (
+            "61|1|",
+            vec![
+                "61|3|DU1234567|76792991|TSLA|STK||0.0|||NASDAQ|USD|TSLA|NMS|500|196.77|",
+                "61|3|DU7654321|265598|AAPL|STK||0.0|||NASDAQ|USD|AAPL|NMS|100|170.5|",
+                "61|3|DU1234567|272093|MSFT|STK||0.0|||NASDAQ|USD|MSFT|NMS|-50|410.25|",
+                "61|3|DU1234567|4815747|NVDA|STK||0.0|||NASDAQ|USD|NVDA|NMS|0|0|",
+                "62|1|",
+            ],
+        ),
+        ("92|9000|DU1234567||", vec!["94|9000|250.5|1200.25|-30.0|"]),
+        ("94|9001|DU1234567||76792991|", vec!["95|9001|500|125.25|600.125|-15.0|100000.0|"]),
+        ("94|9002|DU1234567||272093|", vec!["95|9002|-50|-40.5|-210.75|12.5|-20512.5|"]),
+        // No update for the NVDA position, closed earlier in the day.
+    ];
+
+    let message_bus = Arc::new(RoutedMessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        routes: routes
+            .into_iter()
+            .map(|(request, responses)| (request.to_owned(), responses.into_iter().map(str::to_owned).collect()))
+            .collect(),
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let portfolio = client
+        .portfolio_pnl("DU1234567", Duration::from_millis(100))
+        .expect("request portfolio pnl failed");
+
+    assert_eq!(portfolio.account, "DU1234567");
+    assert_eq!(portfolio.daily_pnl, 250.5, "portfolio.daily_pnl");
+    assert_eq!(portfolio.unrealized_pnl, Some(1200.25), "portfolio.unrealized_pnl");
+    assert_eq!(portfolio.realized_pnl, Some(-30.0), "portfolio.realized_pnl");
+
+    let symbols: Vec<&str> = portfolio.positions.iter().map(|p| p.contract.symbol.as_str()).collect();
+    assert_eq!(symbols, ["TSLA", "MSFT", "NVDA"], "positions of other accounts are excluded");
+
+    let tsla = &portfolio.positions[0];
+    assert_eq!(tsla.position, 500.0);
+    assert_eq!(tsla.daily_pnl, Some(125.25));
+    assert_eq!(tsla.unrealized_pnl, Some(600.125));
+    assert_eq!(tsla.realized_pnl, Some(-15.0));
+    assert_eq!(tsla.value, Some(100000.0));
+
+    let msft = &portfolio.positions[1];
+    assert_eq!(msft.position, -50.0);
+    assert_eq!(msft.daily_pnl, Some(-40.5));
+    assert_eq!(msft.unrealized_pnl, Some(-210.75));
+    assert_eq!(msft.realized_pnl, Some(12.5));
+    assert_eq!(msft.value, Some(-20512.5));
+
+    let nvda = &portfolio.positions[2];
+    assert_eq!(nvda.position, 0.0);
+    assert_eq!(nvda.daily_pnl, None, "position without an update should be flagged");
+    assert_eq!(nvda.value, None);
+
+    let request_messages: Vec<String> = client.message_bus.request_messages().iter().map(|m| m.encode_simple()).collect();
+
+    assert_eq!(
+        request_messages,
+        [
+            "61|1|",
+            "64|1|",
+            "92|9000|DU1234567||",
+            "94|9001|DU1234567||76792991|",
+            "94|9002|DU1234567||272093|",
+            "94|9003|DU1234567||4815747|",
+            "95|9001|",
+            "95|9002|",
+            "95|9003|",
+            "93|9000|",
+        ]
+    );
+}
+
+#[test]
+fn test_portfolio_pnl_requires_account_pnl() {
+    let message_bus = Arc::new(RoutedMessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        routes: [("61|1|".to_owned(), vec!["62|1|".to_owned()])].into_iter().collect(),
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let result = client.portfolio_pnl("DU1234567", Duration::from_millis(10));
+    assert!(matches!(result, Err(Error::Simple(_))), "{result:?}");
+}
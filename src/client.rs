@@ -1,25 +1,34 @@
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::marker::PhantomData;
+use std::ops::Range;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use crossbeam::channel::{Select, TryRecvError};
 use log::{debug, error, warn};
 use time::{Date, OffsetDateTime};
 use time_tz::Tz;
 
-use crate::accounts::{AccountSummaries, AccountUpdate, AccountUpdateMulti, FamilyCode, PnL, PnLSingle, PositionUpdate, PositionUpdateMulti};
+use crate::accounts::{
+    AccountSummaries, AccountSummaryMap, AccountUpdate, AccountUpdateMulti, FamilyCode, PnL, PnLSingle, PortfolioPnL, Position, PositionMulti,
+    PositionUpdate, PositionUpdateMulti,
+};
 use crate::contracts::{Contract, OptionComputation, SecurityType};
 use crate::errors::Error;
 use crate::market_data::historical::{self, HistogramEntry};
-use crate::market_data::realtime::{self, Bar, BarSize, DepthMarketDataDescription, MarketDepths, MidPoint, TickTypes, WhatToShow};
+use crate::market_data::realtime::{
+    self, Bar, BarSize, DepthMarketDataDescription, MarketDataSnapshot, MarketDepths, MidPoint, TickTypes, WhatToShow,
+};
 use crate::market_data::MarketDataType;
 use crate::messages::{IncomingMessages, OutgoingMessages};
 use crate::messages::{RequestMessage, ResponseMessage};
 use crate::news::NewsArticle;
-use crate::orders::{CancelOrder, Executions, ExerciseOptions, Order, Orders, PlaceOrder};
+use crate::orders::{CancelOrder, Executions, ExerciseOptions, Order, OrderState, Orders, PlaceOrder};
 use crate::scanner::ScannerData;
-use crate::transport::{Connection, ConnectionMetadata, InternalSubscription, MessageBus, TcpMessageBus};
+use crate::transport::{Connection, ConnectionMetadata, InternalSubscription, MessageBus, ReplayMessageBus, TcpMessageBus};
 use crate::wsh::AutoFill;
 use crate::{accounts, contracts, market_data, news, orders, scanner, wsh};
 
@@ -38,9 +47,128 @@ pub struct Client {
     pub(crate) time_zone: Option<&'static Tz>,
     pub(crate) message_bus: Arc<dyn MessageBus>,
 
-    client_id: i32,             // ID of client.
-    next_request_id: AtomicI32, // Next available request_id.
-    order_id: AtomicI32,        // Next available order_id. Starts with value returned on connection.
+    client_id: i32,                                                                    // ID of client.
+    next_request_id: AtomicI32,                                                        // Next available request_id.
+    order_id: AtomicI32,                                // Next available order_id. Starts with value returned on connection.
+    lenient_decoding: AtomicBool,                       // Tolerate missing optional trailing fields when decoding orders.
+    strict_completed_orders: AtomicBool,                // End completed order streams at the first order that fails to decode.
+    matching_symbols_limit: Mutex<Option<TokenBucket>>, // Opt-in throttle for symbol searches.
+    historical_data_limit: Mutex<Option<TokenBucket>>,  // Opt-in pacing of historical data requests.
+    pub(crate) contract_details_cache: Mutex<Option<contracts::ContractDetailsCache>>, // Opt-in cache of contract details.
+    pub(crate) market_rule_cache: Mutex<HashMap<i32, contracts::MarketRule>>, // Market rules by id, fixed for the session.
+    read_only: bool,                                    // Refuse to send order requests.
+}
+
+/// Limits how often a request can be made: bursts of up to `burst` requests, refilled at one request per `interval`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    /// Number of requests that can be made back to back.
+    pub burst: u32,
+    /// Time for one more request to become available.
+    pub interval: Duration,
+}
+
+// Token bucket holding up to `burst` tokens. Each request takes a token.
+#[derive(Debug)]
+struct TokenBucket {
+    limit: RateLimit,
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            limit,
+            tokens: limit.burst as f64,
+            updated_at: now,
+        }
+    }
+
+    // Takes a token, or returns how long until one is available.
+    fn try_acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        let interval = self.limit.interval.as_secs_f64();
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        let refilled = if interval > 0.0 { elapsed / interval } else { f64::INFINITY };
+
+        self.tokens = (self.tokens + refilled).min(self.limit.burst as f64);
+        self.updated_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) * interval))
+        }
+    }
+}
+
+/// Controls how the client reconnects after the connection to TWS or Gateway is lost, and how responses are buffered.
+///
+/// The delay before reconnection attempt `n` (starting at 0) is `initial_backoff * 2^n`, capped at `max_backoff`.
+/// `jitter` randomizes each delay by up to that fraction in either direction, so clients dropped at the same time
+/// don't reconnect in lockstep.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectionConfig {
+    /// Number of reconnection attempts before giving up.
+    pub max_retries: i32,
+    /// Delay before the first reconnection attempt.
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between reconnection attempts.
+    pub max_backoff: Duration,
+    /// Fraction, between 0.0 and 1.0, by which each delay is randomized.
+    pub jitter: f64,
+    /// Bound on the responses buffered for each subscription created with a request or order ID.
+    /// `None`, the default, buffers without limit. Override for individual subscriptions with [Client::with_subscription_buffer].
+    pub subscription_buffer: Option<SubscriptionBuffer>,
+    /// Spaces historical data requests to stay within IB pacing limits. See [Client::set_historical_data_limit].
+    /// `None`, the default, sends requests as they are made.
+    pub historical_data_limit: Option<RateLimit>,
+    /// Refuses to send order requests. See [Client::connect_read_only].
+    pub read_only: bool,
+}
+
+/// Bounds the number of unread responses buffered for a subscription, so a slow consumer cannot exhaust memory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubscriptionBuffer {
+    /// Maximum number of unread responses. A capacity of 0 is treated as 1.
+    pub capacity: usize,
+    /// What happens to a response that arrives when the buffer is full.
+    pub overflow: OverflowPolicy,
+}
+
+/// What happens to a response that arrives when a subscription's buffer is full. See [SubscriptionBuffer].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Wait for the consumer to read a response. Responses for all other subscriptions are held up meanwhile.
+    Block,
+    /// Discard the oldest unread response to make room.
+    DropOldest,
+}
+
+/// State of the connection to TWS or Gateway. See [Client::connection_state].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Connected and exchanging messages.
+    Connected,
+    /// The connection was lost and the client is trying to reconnect. Requests fail until it succeeds.
+    Reconnecting,
+    /// The client gave up reconnecting or was shut down. It does not reconnect again.
+    Disconnected,
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 20,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            jitter: 0.1,
+            subscription_buffer: None,
+            historical_data_limit: None,
+            read_only: false,
+        }
+    }
 }
 
 impl Client {
@@ -64,7 +192,65 @@ impl Client {
     /// println!("next_order_id: {}", client.next_order_id());
     /// ```
     pub fn connect(address: &str, client_id: i32) -> Result<Client, Error> {
-        let connection = Connection::connect(client_id, address)?;
+        Client::connect_with_config(address, client_id, ConnectionConfig::default())
+    }
+
+    /// Establishes a read-only connection to TWS or Gateway, for monitoring accounts and market data.
+    ///
+    /// The client refuses to send requests that place, modify, cancel or exercise orders, failing them with [Error::ReadOnly].
+    /// The TWS API handshake has no read-only flag, so this is enforced by the client only. To also have TWS reject orders,
+    /// enable "Read-Only API" in the TWS or Gateway API settings.
+    ///
+    /// # Arguments
+    /// * `address`   - address of server. e.g. 127.0.0.1:4002
+    /// * `client_id` - id of client. e.g. 100
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect_read_only("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let positions = client.positions_snapshot().expect("error requesting positions");
+    /// println!("{} positions", positions.len());
+    /// ```
+    pub fn connect_read_only(address: &str, client_id: i32) -> Result<Client, Error> {
+        let config = ConnectionConfig {
+            read_only: true,
+            ..ConnectionConfig::default()
+        };
+        Client::connect_with_config(address, client_id, config)
+    }
+
+    /// Establishes connection to TWS or Gateway, using `config` to control reconnection attempts.
+    ///
+    /// # Arguments
+    /// * `address`   - address of server. e.g. 127.0.0.1:4002
+    /// * `client_id` - id of client. e.g. 100
+    /// * `config`    - reconnection settings. See [ConnectionConfig].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use ibapi::client::ConnectionConfig;
+    /// use ibapi::Client;
+    ///
+    /// let config = ConnectionConfig {
+    ///     max_retries: 5,
+    ///     initial_backoff: Duration::from_millis(500),
+    ///     ..ConnectionConfig::default()
+    /// };
+    ///
+    /// let client = Client::connect_with_config("127.0.0.1:4002", 100, config).expect("connection failed");
+    /// println!("server_version: {}", client.server_version());
+    /// ```
+    pub fn connect_with_config(address: &str, client_id: i32, config: ConnectionConfig) -> Result<Client, Error> {
+        let historical_data_limit = config.historical_data_limit;
+        let read_only = config.read_only;
+        let connection = Connection::connect(client_id, address, config)?;
         let connection_metadata = connection.connection_metadata();
 
         let message_bus = Arc::new(TcpMessageBus::new(connection)?);
@@ -72,7 +258,34 @@ impl Client {
         // Starts thread to read messages from TWS
         message_bus.process_messages(connection_metadata.server_version)?;
 
-        Client::new(connection_metadata, message_bus)
+        let mut client = Client::new(connection_metadata, message_bus)?;
+        client.set_historical_data_limit(historical_data_limit);
+        client.read_only = read_only;
+
+        Ok(client)
+    }
+
+    /// Creates a client that replays a session recorded with `IBAPI_RECORDING_DIR`, without connecting to TWS.
+    ///
+    /// Requests are answered with the responses recorded for the matching request, so decoding issues seen in the field
+    /// can be reproduced from a capture. A request that is not in the recording fails with an error.
+    ///
+    /// # Arguments
+    /// * `recording_dir` - Directory holding the recorded `NNNN-request.msg` and `NNNN-response.msg` files for one session.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::from_recording("/tmp/logs/2024-03-15-13-30").expect("could not load recording");
+    ///
+    /// let server_time = client.server_time().expect("request not in recording");
+    /// println!("server time: {server_time:?}");
+    /// ```
+    pub fn from_recording(recording_dir: impl AsRef<Path>) -> Result<Client, Error> {
+        let (message_bus, connection_metadata) = ReplayMessageBus::load(recording_dir.as_ref())?;
+        Client::new(connection_metadata, Arc::new(message_bus))
     }
 
     fn new(connection_metadata: ConnectionMetadata, message_bus: Arc<dyn MessageBus>) -> Result<Client, Error> {
@@ -84,6 +297,13 @@ impl Client {
             client_id: connection_metadata.client_id,
             next_request_id: AtomicI32::new(9000),
             order_id: AtomicI32::new(connection_metadata.next_order_id),
+            lenient_decoding: AtomicBool::new(false),
+            strict_completed_orders: AtomicBool::new(false),
+            matching_symbols_limit: Mutex::new(None),
+            historical_data_limit: Mutex::new(None),
+            contract_details_cache: Mutex::new(None),
+            market_rule_cache: Mutex::new(HashMap::new()),
+            read_only: false,
         };
 
         Ok(client)
@@ -124,11 +344,250 @@ impl Client {
         orders::next_valid_order_id(self)
     }
 
+    /// Reserves a block of `count` consecutive order IDs starting at or above `floor`.
+    ///
+    /// The client's order ID sequence is advanced past the reserved block, so [Self::next_order_id] never hands out
+    /// an ID inside it. A supervisor running several clients can allocate non-overlapping ranges by passing the end
+    /// of one client's range as the floor of the next.
+    ///
+    /// Things to keep in mind when coordinating multiple clients:
+    ///
+    /// * Each client only knows its own sequence. Start from the highest [Self::next_valid_order_id] across all clients
+    ///   so that no range falls below an ID TWS has already seen.
+    /// * After reserving, a client's [Self::next_order_id] continues from the end of its range, which may be the start of
+    ///   another client's range. Place orders using IDs from the reserved range only.
+    /// * Ranges are not persisted. Reserve again after reconnecting, using fresh values from [Self::next_valid_order_id].
+    ///
+    /// # Arguments
+    /// * `floor`  - Lowest order ID the block may start at.
+    /// * `count`  - Number of order IDs to reserve. Must be positive.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    ///
+    /// let client_a = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    /// let client_b = Client::connect("127.0.0.1:4002", 101).expect("connection failed");
+    ///
+    /// let floor = client_a.next_valid_order_id().unwrap().max(client_b.next_valid_order_id().unwrap());
+    ///
+    /// let ids_a = client_a.reserve_order_ids(floor, 100).expect("reservation failed");
+    /// let ids_b = client_b.reserve_order_ids(ids_a.end, 100).expect("reservation failed");
+    ///
+    /// println!("client a: {ids_a:?}, client b: {ids_b:?}");
+    /// ```
+    pub fn reserve_order_ids(&self, floor: i32, count: i32) -> Result<Range<i32>, Error> {
+        if count < 1 {
+            return Err(Error::InvalidArgument(format!("order ID count must be positive: {count}")));
+        }
+
+        let previous = self
+            .order_id
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| current.max(floor).checked_add(count))
+            .map_err(|current| Error::InvalidArgument(format!("cannot reserve {count} order IDs after {}", current.max(floor))))?;
+
+        let start = previous.max(floor);
+        Ok(start..start + count)
+    }
+
     /// Sets the current value of order ID.
     pub(crate) fn set_next_order_id(&self, order_id: i32) {
         self.order_id.store(order_id, Ordering::Relaxed)
     }
 
+    /// Controls how strictly open and completed orders are decoded.
+    ///
+    /// Gateway builds differ in the optional fields they send at the end of open and completed order messages.
+    /// By default the client is strict and fails to decode an order that is missing any field expected for the
+    /// server version. With lenient decoding enabled, missing optional trailing fields are left at their defaults.
+    /// Extra trailing fields sent by newer builds are ignored in either mode.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    /// client.set_lenient_decoding(true);
+    ///
+    /// for order_data in &client.completed_orders(false).expect("request failed") {
+    ///     println!("{order_data:?}")
+    /// }
+    /// ```
+    pub fn set_lenient_decoding(&self, lenient: bool) {
+        self.lenient_decoding.store(lenient, Ordering::Relaxed)
+    }
+
+    /// Returns true if orders are decoded leniently. See [Self::set_lenient_decoding].
+    pub fn lenient_decoding(&self) -> bool {
+        self.lenient_decoding.load(Ordering::Relaxed)
+    }
+
+    /// Controls how [Self::completed_orders] handles an order that fails to decode.
+    ///
+    /// By default such an order is logged and skipped: the subscription yields an [Orders::SkippedOrder] holding the decode
+    /// error in its place and continues with the remaining orders. With strict mode enabled, the subscription ends at the
+    /// malformed order and [Subscription::error] returns the decode error.
+    pub fn set_strict_completed_orders(&self, strict: bool) {
+        self.strict_completed_orders.store(strict, Ordering::Relaxed)
+    }
+
+    /// Returns true if completed order streams fail fast. See [Self::set_strict_completed_orders].
+    pub fn strict_completed_orders(&self) -> bool {
+        self.strict_completed_orders.load(Ordering::Relaxed)
+    }
+
+    /// Returns true if the client refuses to send order requests. See [Self::connect_read_only].
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Creates the subscriptions requested in `f` with `buffer` in place of [ConnectionConfig::subscription_buffer].
+    ///
+    /// Only subscriptions created with a request or order ID on the calling thread while `f` runs are affected.
+    /// Pass `None` for an unbounded buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::client::{OverflowPolicy, SubscriptionBuffer};
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::market_data::realtime::{BarSize, WhatToShow};
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// // Only the latest bars matter, so a slow consumer skips old ones instead of holding up other subscriptions.
+    /// let buffer = SubscriptionBuffer {
+    ///     capacity: 10,
+    ///     overflow: OverflowPolicy::DropOldest,
+    /// };
+    /// let subscription = client
+    ///     .with_subscription_buffer(Some(buffer), |client| {
+    ///         client.realtime_bars(&Contract::stock("AAPL"), BarSize::Sec5, WhatToShow::Trades, false)
+    ///     })
+    ///     .expect("realtime bars request failed!");
+    ///
+    /// for bar in &subscription {
+    ///     println!("bar: {bar:?}");
+    /// }
+    /// ```
+    pub fn with_subscription_buffer<'a, R>(&'a self, buffer: Option<SubscriptionBuffer>, f: impl FnOnce(&'a Client) -> R) -> R {
+        crate::transport::with_subscription_buffer(buffer, || f(self))
+    }
+
+    /// Throttles [Self::matching_symbols] requests, e.g. when searching symbols as the user types.
+    ///
+    /// Symbol searches are not throttled by default. With a limit set, searches beyond it fail
+    /// with [Error::RateLimited] instead of being sent to TWS and tripping IB pacing. Pass `None` to remove the limit.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use ibapi::client::RateLimit;
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    /// client.set_matching_symbols_limit(Some(RateLimit {
+    ///     burst: 1,
+    ///     interval: Duration::from_secs(1),
+    /// }));
+    /// ```
+    pub fn set_matching_symbols_limit(&self, limit: Option<RateLimit>) {
+        let bucket = limit.map(|limit| TokenBucket::new(limit, Instant::now()));
+        *self.matching_symbols_limit.lock().unwrap() = bucket;
+    }
+
+    // Takes a token from the symbol search limit, if one is set.
+    pub(crate) fn check_matching_symbols_limit(&self) -> Result<(), Error> {
+        match self.matching_symbols_limit.lock()?.as_mut() {
+            Some(bucket) => bucket.try_acquire(Instant::now()).map_err(Error::RateLimited),
+            None => Ok(()),
+        }
+    }
+
+    /// Spaces [Self::historical_data] requests, waiting before a request that would exceed the limit.
+    ///
+    /// IB allows about 60 historical data requests in any 10 minutes and rejects requests beyond that with a pacing
+    /// violation. With a limit set, requests wait for their turn instead, and a request that is still rejected for pacing
+    /// is retried with exponential backoff, starting at the limit's interval. Pass `None` to remove the limit.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use ibapi::client::RateLimit;
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    /// client.set_historical_data_limit(Some(RateLimit {
+    ///     burst: 6,
+    ///     interval: Duration::from_secs(10),
+    /// }));
+    /// ```
+    pub fn set_historical_data_limit(&self, limit: Option<RateLimit>) {
+        let bucket = limit.map(|limit| TokenBucket::new(limit, Instant::now()));
+        *self.historical_data_limit.lock().unwrap() = bucket;
+    }
+
+    // Waits until the historical data limit, if one is set, allows another request.
+    pub(crate) fn pace_historical_data(&self) -> Result<(), Error> {
+        loop {
+            let wait = match self.historical_data_limit.lock()?.as_mut() {
+                Some(bucket) => match bucket.try_acquire(Instant::now()) {
+                    Ok(()) => return Ok(()),
+                    Err(wait) => wait,
+                },
+                None => return Ok(()),
+            };
+
+            debug!("pacing historical data request, waiting {wait:?}");
+            std::thread::sleep(wait);
+        }
+    }
+
+    // Interval of the historical data limit, the base delay when backing off from a pacing violation.
+    pub(crate) fn historical_data_interval(&self) -> Option<Duration> {
+        self.historical_data_limit.lock().ok()?.as_ref().map(|bucket| bucket.limit.interval)
+    }
+
+    /// Caches [Self::contract_details] responses for `ttl`, so repeated lookups of the same contract are not sent to TWS.
+    ///
+    /// Responses are cached by the contract's id, symbol, security type, exchange, currency, expiry, strike and right.
+    /// A query matching several contracts is cached as a unit, and an identical query returns all of them from the cache.
+    /// Empty responses and errors are not cached. Pass `None` to disable caching and drop cached responses.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    /// client.set_contract_details_cache(Some(Duration::from_secs(3600)));
+    ///
+    /// let contract = Contract::stock("TSLA");
+    /// let details = client.contract_details(&contract).expect("request failed");
+    /// let cached = client.contract_details(&contract).expect("request failed");
+    /// assert_eq!(details, cached);
+    /// ```
+    pub fn set_contract_details_cache(&self, ttl: Option<Duration>) {
+        *self.contract_details_cache.lock().unwrap() = ttl.map(contracts::ContractDetailsCache::new);
+    }
+
+    /// Drops all cached [Self::contract_details] responses. See [Self::set_contract_details_cache].
+    pub fn clear_contract_details_cache(&self) {
+        if let Some(cache) = self.contract_details_cache.lock().unwrap().as_mut() {
+            cache.clear();
+        }
+    }
+
     pub fn server_version(&self) -> i32 {
         self.server_version
     }
@@ -138,6 +597,48 @@ impl Client {
         self.connection_time
     }
 
+    /// Returns the current state of the connection to TWS, e.g. for health checks.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::client::ConnectionState;
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// if client.connection_state() != ConnectionState::Connected {
+    ///     println!("connection to TWS is down");
+    /// }
+    /// ```
+    pub fn connection_state(&self) -> ConnectionState {
+        self.message_bus.connection_state()
+    }
+
+    /// Registers a callback to run each time the client reconnects to TWS after losing the connection.
+    ///
+    /// TWS forgets subscriptions when the connection drops. After the handshake completes on reconnection, the client
+    /// re-sends the requests behind active [Client::open_orders], [Client::all_open_orders], [Client::auto_open_orders] and
+    /// [Client::positions] subscriptions, then runs the registered callbacks. Use the callback to re-issue other requests.
+    ///
+    /// Delivery is at-least-once: ongoing subscriptions see a [ConnectionReset](Error::ConnectionReset), after which
+    /// calling `next` again continues with the re-sent data, so orders and positions already seen are delivered again.
+    ///
+    /// Callbacks run on a background thread, not the thread that reads from TWS, so they can make requests.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// client.on_reconnect(|| println!("reconnected to TWS"));
+    /// ```
+    pub fn on_reconnect(&self, callback: impl Fn() + Send + Sync + 'static) {
+        self.message_bus.add_reconnect_hook(Arc::new(callback));
+    }
+
     // === Accounts ===
 
     /// TWS's current time. TWS is synchronized with the server (not local computer) using NTP and this function will receive the current time in TWS.
@@ -179,6 +680,7 @@ impl Client {
 
     /// Subscribes to [PositionUpdateMulti] updates for account and/or model.
     /// Initially all positions are returned, and then updates are returned for any position changes in real time.
+    /// Use [accounts::exposure_by_model] to total the received positions per model.
     ///
     /// # Arguments
     /// * `account`    - If an account Id is provided, only the account’s positions belonging to the specified model will be delivered.
@@ -201,6 +703,48 @@ impl Client {
         accounts::positions_multi(self, account, model_code)
     }
 
+    /// Requests the positions held in all accessible accounts. Waits for the initial set of positions and returns them.
+    ///
+    /// The underlying subscription is cancelled once the positions are received. Use [Client::positions] to follow position changes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let positions = client.positions_snapshot().expect("error requesting positions");
+    /// for position in &positions {
+    ///     println!("{} {}: {}", position.account, position.contract.symbol, position.position);
+    /// }
+    /// ```
+    pub fn positions_snapshot(&self) -> Result<Vec<Position>, Error> {
+        accounts::positions_snapshot(self)
+    }
+
+    /// Requests the positions for account and/or model. Waits for the initial set of positions and returns them.
+    ///
+    /// The underlying subscription is cancelled once the positions are received. Use [Client::positions_multi] to follow position changes.
+    ///
+    /// # Arguments
+    /// * `account`    - If an account Id is provided, only the account’s positions belonging to the specified model will be delivered.
+    /// * `model_code` - The code of the model’s positions we are interested in.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let positions = client.positions_multi_snapshot(Some("U1234567"), None).expect("error requesting positions");
+    /// println!("{} positions", positions.len());
+    /// ```
+    pub fn positions_multi_snapshot(&self, account: Option<&str>, model_code: Option<&str>) -> Result<Vec<PositionMulti>, Error> {
+        accounts::positions_multi_snapshot(self, account, model_code)
+    }
+
     /// Creates subscription for real time daily PnL and unrealized PnL updates.
     ///
     /// # Arguments
@@ -249,11 +793,43 @@ impl Client {
         accounts::pnl_single(self, account, contract_id, model_code)
     }
 
+    /// Requests the P&L of an account together with the P&L of each position held in it.
+    ///
+    /// The positions are found with [Client::positions_snapshot], then the first update of [Client::pnl] and of
+    /// [Client::pnl_single] for each position is taken. This is a single snapshot, not a live stream: all subscriptions
+    /// are cancelled before returning. Use [Client::pnl] and [Client::pnl_single] to follow P&L changes.
+    ///
+    /// TWS does not send P&L for every position, e.g. for positions closed today. Positions without an update within
+    /// `timeout` are returned with their P&L fields set to `None`. If the account P&L does not arrive in time, an error is returned.
+    ///
+    /// # Arguments
+    /// * `account` - account for which to request the P&L
+    /// * `timeout` - how long to wait for the P&L updates in total
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let portfolio = client.portfolio_pnl("U1234567", Duration::from_secs(5)).expect("error requesting portfolio pnl");
+    /// println!("daily pnl: {}", portfolio.daily_pnl);
+    /// for position in &portfolio.positions {
+    ///     println!("{}: {:?}", position.contract.symbol, position.daily_pnl);
+    /// }
+    /// ```
+    pub fn portfolio_pnl(&self, account: &str, timeout: Duration) -> Result<PortfolioPnL, Error> {
+        accounts::portfolio_pnl(self, account, timeout)
+    }
+
     /// Requests a specific account’s summary. Subscribes to the account summary as presented in the TWS’ Account Summary tab. Data received is specified by using a specific tags value.
     ///
     /// # Arguments
     /// * `group` - Set to “All” to return account summary data for all accounts, or set to a specific Advisor Account Group name that has already been created in TWS Global Configuration.
-    /// * `tags`  - List of the desired tags.
+    /// * `tags`  - List of the desired tags. Unknown tags are rejected with [Error::InvalidArgument]; see [AccountSummaryTags::is_known](crate::accounts::AccountSummaryTags::is_known).
     ///
     /// # Examples
     ///
@@ -274,6 +850,43 @@ impl Client {
         accounts::account_summary(self, group, tags)
     }
 
+    /// Requests a specific account’s summary without validating `tags`.
+    ///
+    /// Same as [Client::account_summary], but passes tags through as is. Use for tags added to TWS after this release.
+    ///
+    /// # Arguments
+    /// * `group` - Set to “All” to return account summary data for all accounts, or set to a specific Advisor Account Group name that has already been created in TWS Global Configuration.
+    /// * `tags`  - List of the desired tags.
+    pub fn account_summary_unchecked<'a>(&'a self, group: &str, tags: &[&str]) -> Result<Subscription<'a, AccountSummaries>, Error> {
+        accounts::account_summary_unchecked(self, group, tags)
+    }
+
+    /// Requests a one time account summary. Waits for all values to arrive and returns them keyed by tag.
+    ///
+    /// Non numeric values, such as `AccountType`, are returned as strings. When `group` covers several accounts,
+    /// use [Client::account_summary] instead, since values for the same tag would overwrite one another.
+    ///
+    /// # Arguments
+    /// * `group` - Set to “All” to return account summary data for all accounts, or set to a specific Advisor Account Group name that has already been created in TWS Global Configuration.
+    /// * `tags`  - List of the desired tags.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    /// use ibapi::accounts::AccountSummaryTags;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let summary = client.account_summary_snapshot("All", &[AccountSummaryTags::NET_LIQUIDATION]).expect("error requesting account summary");
+    /// if let Some((value, currency)) = summary.get(AccountSummaryTags::NET_LIQUIDATION) {
+    ///     println!("net liquidation: {value} {currency}");
+    /// }
+    /// ```
+    pub fn account_summary_snapshot(&self, group: &str, tags: &[&str]) -> Result<AccountSummaryMap, Error> {
+        accounts::account_summary_snapshot(self, group, tags)
+    }
+
     /// Subscribes to a specific account’s information and portfolio.
     ///
     /// All account values and positions will be returned initially, and then there will only be updates when there is a change in a position, or to an account value every 3 minutes if it has changed. Only one account can be subscribed at a time.
@@ -385,6 +998,30 @@ impl Client {
         contracts::contract_details(self, contract)
     }
 
+    /// Requests contract details for every listed expiration of a future, sorted by expiry.
+    ///
+    /// This is the futures analog of [Client::option_chain].
+    ///
+    /// # Arguments
+    /// * `symbol`   - Symbol of the future, e.g. ES.
+    /// * `exchange` - Exchange the future trades on, e.g. CME.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let chain = client.futures_chain("ES", "CME").expect("request failed");
+    /// for contract_detail in chain {
+    ///     println!("{} {}", contract_detail.contract.local_symbol, contract_detail.contract.last_trade_date_or_contract_month);
+    /// }
+    /// ```
+    pub fn futures_chain(&self, symbol: &str, exchange: &str) -> Result<Vec<contracts::ContractDetails>, Error> {
+        contracts::futures_chain(self, symbol, exchange)
+    }
+
     /// Get current [FamilyCode]s for all accessible accounts.
     pub fn family_codes(&self) -> Result<Vec<FamilyCode>, Error> {
         accounts::family_codes(self)
@@ -395,15 +1032,25 @@ impl Client {
     /// The market rule for an instrument on a particular exchange provides details about how the minimum price increment changes with price.
     /// A list of market rule ids can be obtained by invoking [Self::contract_details()] for a particular contract.
     /// The returned market rule ID list will provide the market rule ID for the instrument in the correspond valid exchange list in [contracts::ContractDetails].
+    ///
+    /// Market rules do not change during a session, so each rule is requested once and then served from a cache.
+    /// See [Self::clear_market_rule_cache].
     pub fn market_rule(&self, market_rule_id: i32) -> Result<contracts::MarketRule, Error> {
         contracts::market_rule(self, market_rule_id)
     }
 
+    /// Drops the market rules cached by [Self::market_rule], so they are requested again.
+    pub fn clear_market_rule_cache(&self) {
+        self.market_rule_cache.lock().unwrap().clear();
+    }
+
     /// Requests matching stock symbols.
     ///
     /// # Arguments
     /// * `pattern` - Either start of ticker symbol or (for larger strings) company name.
     ///
+    /// Searches can be throttled with [Self::set_matching_symbols_limit].
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -504,6 +1151,55 @@ impl Client {
         contracts::option_chain(self, symbol, exchange, security_type, contract_id)
     }
 
+    /// Requests the option chain like [Client::option_chain], keeping only the expirations and strikes within the given ranges.
+    ///
+    /// TWS always returns the full chain, so the filter is applied as each [contracts::OptionChain] is decoded.
+    /// This saves post-processing and memory for underlyings with thousands of strikes.
+    ///
+    /// # Arguments
+    /// `symbol`   - Contract symbol of the underlying.
+    /// `exchange` - The exchange on which the returned options are trading. Can be set to the empty string for all exchanges.
+    /// `security_type` - The type of the underlying security, i.e. STK
+    /// `contract_id`   - The contract ID of the underlying security.
+    /// `expiry_range`  - First and last expiration date to keep, inclusive. `None` keeps all expirations.
+    /// `strike_range`  - Lowest and highest strike to keep, inclusive. `None` keeps all strikes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::{contracts::SecurityType, Client};
+    /// use time::macros::date;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let subscription = client
+    ///     .option_chain_filtered(
+    ///         "AAPL",
+    ///         "SMART",
+    ///         SecurityType::Stock,
+    ///         265598,
+    ///         Some((date!(2025 - 01 - 01), date!(2025 - 03 - 31))),
+    ///         Some((150.0, 250.0)),
+    ///     )
+    ///     .expect("request option chain failed!");
+    ///
+    /// for option_chain in &subscription {
+    ///     println!("{:?} {:?}", option_chain.expirations, option_chain.strikes)
+    /// }
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn option_chain_filtered(
+        &self,
+        symbol: &str,
+        exchange: &str,
+        security_type: SecurityType,
+        contract_id: i32,
+        expiry_range: Option<(Date, Date)>,
+        strike_range: Option<(f64, f64)>,
+    ) -> Result<Subscription<'_, contracts::OptionChain>, Error> {
+        contracts::option_chain_filtered(self, symbol, exchange, security_type, contract_id, expiry_range, strike_range)
+    }
+
     // === Orders ===
 
     /// Requests all *current* open orders in associated accounts at the current moment.
@@ -571,6 +1267,9 @@ impl Client {
 
     /// Requests completed [Order]s.
     ///
+    /// The subscription ends after the last completed order. An order that fails to decode is skipped and reported as an
+    /// [Orders::SkippedOrder], unless [strict mode](Self::set_strict_completed_orders) is enabled.
+    ///
     /// # Arguments
     /// * `api_only` - request only orders placed by the API.
     ///
@@ -655,45 +1354,266 @@ impl Client {
         orders::open_orders(self)
     }
 
-    /// Places or modifies an [Order].
+    /// Places or modifies an [Order].
+    ///
+    /// Submits an [Order] using [Client] for the given [Contract].
+    /// Upon successful submission, the client will start receiving events related to the order's activity via the subscription, including order status updates and execution reports.
+    ///
+    /// # Arguments
+    /// * `order_id` - ID for [Order]. Get next valid ID using [Client::next_order_id].
+    /// * `contract` - [Contract] to submit order for.
+    /// * `order` - [Order] to submit.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::orders::{order_builder, Action, PlaceOrder};
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let contract = Contract::stock("MSFT");
+    /// let order = order_builder::market_order(Action::Buy, 100.0);
+    /// let order_id = client.next_order_id();
+    ///
+    /// let events = client.place_order(order_id, &contract, &order).expect("request failed");
+    ///
+    /// for event in &events {
+    ///     match event {
+    ///         PlaceOrder::OrderStatus(order_status) => {
+    ///             println!("order status: {order_status:?}")
+    ///         }
+    ///         PlaceOrder::OpenOrder(open_order) => println!("open order: {open_order:?}"),
+    ///         PlaceOrder::ExecutionData(execution) => println!("execution: {execution:?}"),
+    ///         PlaceOrder::CommissionReport(report) => println!("commission report: {report:?}"),
+    ///         PlaceOrder::Message(message) => println!("message: {message:?}"),
+    ///    }
+    /// }
+    /// ```
+    pub fn place_order(&self, order_id: i32, contract: &Contract, order: &Order) -> Result<Subscription<PlaceOrder>, Error> {
+        orders::place_order(self, order_id, contract, order)
+    }
+
+    /// Submits an [Order] without waiting for responses.
+    ///
+    /// Same request and validation as [Client::place_order], but the events concerning the order's activity are not returned.
+    /// Use [Client::open_orders] or [Client::executions] to follow up on the order.
+    ///
+    /// # Arguments
+    /// * `order_id` - ID for [Order]. Get next valid ID using [Client::next_order_id].
+    /// * `contract` - [Contract] to submit order for.
+    /// * `order` - [Order] to submit.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::orders::{order_builder, Action};
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let contract = Contract::stock("MSFT");
+    /// let order = order_builder::market_order(Action::Buy, 100.0);
+    /// let order_id = client.next_order_id();
+    ///
+    /// client.submit_order(order_id, &contract, &order).expect("submit order failed");
+    /// ```
+    pub fn submit_order(&self, order_id: i32, contract: &Contract, order: &Order) -> Result<(), Error> {
+        orders::submit_order(self, order_id, contract, order)
+    }
+
+    /// Places an [Order], returning an error if TWS rejects it immediately.
+    ///
+    /// Same as [Client::place_order], but waits up to `timeout` for the first response. If TWS responds with an error, e.g. for an unknown contract,
+    /// it is returned as [Error::TwsError]. Otherwise the subscription is returned with the first response still available.
+    /// Warnings, such as code 399, do not count as rejections. If nothing arrives within `timeout`, the subscription is returned as is.
+    ///
+    /// # Arguments
+    /// * `order_id` - ID for [Order]. Get next valid ID using [Client::next_order_id].
+    /// * `contract` - [Contract] to submit order for.
+    /// * `order` - [Order] to submit.
+    /// * `timeout` - How long to wait for the first response.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use ibapi::Client;
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::orders::{order_builder, Action};
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let contract = Contract::stock("MSFT");
+    /// let order = order_builder::market_order(Action::Buy, 100.0);
+    /// let order_id = client.next_order_id();
+    ///
+    /// let result = client.try_place_order(order_id, &contract, &order, Duration::from_secs(5));
+    /// match result {
+    ///     Ok(events) => {
+    ///         for event in &events {
+    ///             println!("{event:?}");
+    ///         }
+    ///     }
+    ///     Err(e) => eprintln!("order rejected: {e}"),
+    /// }
+    /// ```
+    pub fn try_place_order(
+        &self,
+        order_id: i32,
+        contract: &Contract,
+        order: &Order,
+        timeout: Duration,
+    ) -> Result<Subscription<'_, PlaceOrder>, Error> {
+        orders::try_place_order(self, order_id, contract, order, timeout)
+    }
+
+    /// Modifies an existing [Order].
+    ///
+    /// TWS treats an order submitted with the ID of an open order as a modification of that order.
+    /// This is the same request as [Client::place_order], but rejects an `order_id` of zero or less, which would otherwise be
+    /// submitted as a new order.
+    ///
+    /// # Arguments
+    /// * `order_id` - ID of the [Order] to modify, as used when the order was placed.
+    /// * `contract` - [Contract] the order was placed for.
+    /// * `order` - [Order] with the updated attributes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::orders::{order_builder, Action};
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let contract = Contract::stock("MSFT");
+    /// let order_id = client.next_order_id();
+    ///
+    /// let order = order_builder::limit_order(Action::Buy, 100.0, 400.0);
+    /// let _events = client.place_order(order_id, &contract, &order).expect("place order failed");
+    ///
+    /// let order = order_builder::limit_order(Action::Buy, 100.0, 405.0);
+    /// let events = client.modify_order(order_id, &contract, &order).expect("modify order failed");
+    /// for event in &events {
+    ///     println!("{event:?}");
+    /// }
+    /// ```
+    pub fn modify_order(&self, order_id: i32, contract: &Contract, order: &Order) -> Result<Subscription<'_, PlaceOrder>, Error> {
+        orders::modify_order(self, order_id, contract, order)
+    }
+
+    /// Places a bracket: an entry order with a take profit and a stop loss attached.
+    ///
+    /// Allocates three order IDs with [Client::next_order_id], links the take profit and stop loss to the entry through
+    /// their `parent_id`, and transmits only the stop loss, which releases the whole bracket at once. The `order_id`,
+    /// `parent_id` and `transmit` fields of the given orders are overwritten. Events for the orders are not returned;
+    /// use [Client::open_orders] to follow up on them.
+    ///
+    /// Returns [Error::InvalidArgument] without submitting anything if the take profit or stop loss price is on the
+    /// wrong side of the entry's limit price.
+    ///
+    /// # Arguments
+    /// * `contract` - [Contract] the bracket is for.
+    /// * `entry` - [Order] opening the position.
+    /// * `take_profit` - Limit [Order] closing the position at a profit.
+    /// * `stop_loss` - Stop [Order] closing the position at a loss.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::orders::{order_builder, Action};
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let contract = Contract::stock("AAPL");
+    /// let entry = order_builder::limit_order(Action::Buy, 100.0, 185.0);
+    /// let take_profit = order_builder::limit_order(Action::Sell, 100.0, 190.0);
+    /// let stop_loss = order_builder::stop(Action::Sell, 100.0, 180.0);
+    ///
+    /// let ids = client
+    ///     .place_bracket_order(&contract, &entry, &take_profit, &stop_loss)
+    ///     .expect("place bracket order failed");
+    /// println!("bracket order ids: {ids:?}");
+    /// ```
+    pub fn place_bracket_order(
+        &self,
+        contract: &Contract,
+        entry: &Order,
+        take_profit: &Order,
+        stop_loss: &Order,
+    ) -> Result<orders::BracketOrderIds, Error> {
+        orders::place_bracket_order(self, contract, entry, take_profit, stop_loss)
+    }
+
+    /// Places a One-Cancels-All group.
+    ///
+    /// Each order is assigned an order ID with [Client::next_order_id] and stamped with the same `group_name` and
+    /// `oca_type` before being submitted. Events for the orders are not returned; use [Client::open_orders] to follow
+    /// up on them. Returns the order IDs in the order the orders were given.
+    ///
+    /// # Arguments
+    /// * `group_name` - Identifier shared by the orders in the group.
+    /// * `oca_type` - How the remaining orders are handled when one executes. See [OcaType](orders::OcaType).
+    /// * `orders` - [Contract] and [Order] pairs making up the group.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::orders::{order_builder, Action, OcaType};
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let orders = vec![
+    ///     (Contract::stock("AAPL"), order_builder::limit_order(Action::Sell, 100.0, 190.0)),
+    ///     (Contract::stock("MSFT"), order_builder::limit_order(Action::Sell, 100.0, 420.0)),
+    /// ];
+    ///
+    /// let order_ids = client
+    ///     .place_oca_group("take-profit", OcaType::CancelWithBlock, orders)
+    ///     .expect("place OCA group failed");
+    /// println!("order ids: {order_ids:?}");
+    /// ```
+    pub fn place_oca_group(&self, group_name: &str, oca_type: orders::OcaType, orders: Vec<(Contract, Order)>) -> Result<Vec<i32>, Error> {
+        orders::place_oca_group(self, group_name, oca_type, orders)
+    }
+
+    /// Previews the margin and commission impact of each leg of a bracket without placing it.
     ///
-    /// Submits an [Order] using [Client] for the given [Contract].
-    /// Upon successful submission, the client will start receiving events related to the order's activity via the subscription, including order status updates and execution reports.
+    /// Every leg is submitted as a what-if order under a new order ID from [Client::next_order_id], with its parent link
+    /// removed, so the previews are estimates for each leg taken on its own. Returns one [OrderState] per leg, in order.
     ///
     /// # Arguments
-    /// * `order_id` - ID for [Order]. Get next valid ID using [Client::next_order_id].
-    /// * `contract` - [Contract] to submit order for.
-    /// * `order` - [Order] to submit.
+    /// * `contract` - [Contract] the bracket is for.
+    /// * `orders` - Legs of the bracket, e.g. from [order_builder::bracket_order](crate::orders::order_builder::bracket_order).
     ///
     /// # Examples
     ///
     /// ```no_run
     /// use ibapi::Client;
     /// use ibapi::contracts::Contract;
-    /// use ibapi::orders::{order_builder, Action, PlaceOrder};
+    /// use ibapi::orders::{order_builder, Action};
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
-    /// let contract = Contract::stock("MSFT");
-    /// let order = order_builder::market_order(Action::Buy, 100.0);
-    /// let order_id = client.next_order_id();
-    ///
-    /// let events = client.place_order(order_id, &contract, &order).expect("request failed");
+    /// let contract = Contract::stock("AAPL");
+    /// let bracket = order_builder::bracket_order(client.next_order_id(), Action::Buy, 100.0, 185.0, 190.0, 180.0);
     ///
-    /// for event in &events {
-    ///     match event {
-    ///         PlaceOrder::OrderStatus(order_status) => {
-    ///             println!("order status: {order_status:?}")
-    ///         }
-    ///         PlaceOrder::OpenOrder(open_order) => println!("open order: {open_order:?}"),
-    ///         PlaceOrder::ExecutionData(execution) => println!("execution: {execution:?}"),
-    ///         PlaceOrder::CommissionReport(report) => println!("commission report: {report:?}"),
-    ///         PlaceOrder::Message(message) => println!("message: {message:?}"),
-    ///    }
+    /// let previews = client.what_if_bracket(&contract, &bracket).expect("what-if request failed");
+    /// for preview in &previews {
+    ///     println!("initial margin change: {:?}, commission: {:?}", preview.initial_margin_change, preview.commission);
     /// }
     /// ```
-    pub fn place_order(&self, order_id: i32, contract: &Contract, order: &Order) -> Result<Subscription<PlaceOrder>, Error> {
-        orders::place_order(self, order_id, contract, order)
+    pub fn what_if_bracket(&self, contract: &Contract, orders: &[Order]) -> Result<Vec<OrderState>, Error> {
+        orders::what_if_bracket(self, contract, orders)
     }
 
     /// Exercises an options contract.
@@ -745,6 +1665,8 @@ impl Client {
 
     /// Requests interval of historical data ending at specified time for [Contract].
     ///
+    /// Use [Contract::continuous_future] to request a continuous series of front month futures bars.
+    ///
     /// # Arguments
     /// * `contract`     - [Contract] to retrieve [historical::HistoricalData] for.
     /// * `interval_end` - optional end date of interval to retrieve [historical::HistoricalData] for. If `None` current time or last trading of contract is implied.
@@ -788,6 +1710,51 @@ impl Client {
         historical::historical_data(self, contract, interval_end, duration, bar_size, Some(what_to_show), use_rth)
     }
 
+    /// Requests the implied volatility of an option, or the 30 day implied volatility of an underlying, over an interval.
+    ///
+    /// IB returns implied volatility as bars of [historical::WhatToShow::OptionImpliedVolatility]. Each bar's close is the
+    /// implied volatility at the end of the bar, e.g. 0.25 for 25%. Only options and underlyings with options (stocks,
+    /// indexes and futures) are accepted.
+    ///
+    /// # Arguments
+    /// * `contract`     - [Contract] to retrieve implied volatility for.
+    /// * `interval_end` - optional end date of interval. If `None` current time or last trading of contract is implied.
+    /// * `duration`     - duration of interval.
+    /// * `bar_size`     - [historical::BarSize] of each value.
+    /// * `use_rth`      - use regular trading hours.
+    ///
+    /// # Returns
+    /// The bar date and implied volatility of each bar.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::market_data::historical::{BarSize, ToDuration};
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let contract = Contract::stock("AAPL");
+    /// let series = client
+    ///     .historical_implied_volatility(&contract, None, 30.days(), BarSize::Day, true)
+    ///     .expect("implied volatility request failed");
+    ///
+    /// for (date, iv) in series {
+    ///     println!("{date}: {iv:.4}");
+    /// }
+    /// ```
+    pub fn historical_implied_volatility(
+        &self,
+        contract: &Contract,
+        interval_end: Option<OffsetDateTime>,
+        duration: historical::Duration,
+        bar_size: historical::BarSize,
+        use_rth: bool,
+    ) -> Result<Vec<(OffsetDateTime, f64)>, Error> {
+        historical::historical_implied_volatility(self, contract, interval_end, duration, bar_size, use_rth)
+    }
+
     /// Requests interval of historical data ending now for [Contract].
     ///
     /// # Arguments
@@ -1028,13 +1995,11 @@ impl Client {
     /// # Arguments
     /// * `contract`  - [Contract] to retrieve [Histogram Entries](historical::HistogramEntry) for.
     /// * `use_rth`   - Data from regular trading hours (true), or all available hours (false).
-    /// * `duration`  - Duration of interval to retrieve.
+    /// * `period`    - Period the histogram covers. TWS accepts [BarSize::Day](historical::BarSize::Day), [BarSize::Week](historical::BarSize::Week) and [BarSize::Month](historical::BarSize::Month); other sizes are rejected.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use time::macros::datetime;
-    //
     /// use ibapi::contracts::Contract;
     /// use ibapi::Client;
     /// use ibapi::market_data::historical::BarSize;
@@ -1090,8 +2055,50 @@ impl Client {
         realtime::realtime_bars(self, contract, &bar_size, &what_to_show, use_rth, Vec::default())
     }
 
+    /// Requests tick by tick data of the given kind.
+    ///
+    /// Ticks are returned as [TickByTick](realtime::TickByTick), so one method covers every [TickByTickType](realtime::TickByTickType).
+    /// [TickByTickType::AllLast](realtime::TickByTickType::AllLast) includes combos, derivatives and other trades that are not
+    /// reported to the tape, which [TickByTickType::Last](realtime::TickByTickType::Last) leaves out.
+    ///
+    /// # Arguments
+    /// * `contract` - The [Contract] to request ticks for.
+    /// * `tick_type` - Kind of ticks to request.
+    /// * `number_of_ticks` - number of historical ticks to return before streaming, or 0 for none.
+    /// * `ignore_size` - ignore size flag.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::contracts::Contract;
+    /// use ibapi::market_data::realtime::{TickByTick, TickByTickType};
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let contract = Contract::stock("AAPL");
+    /// let ticks = client.tick_by_tick(&contract, TickByTickType::AllLast, 0, false).expect("tick by tick request failed");
+    ///
+    /// for tick in &ticks {
+    ///     if let TickByTick::Trade(trade) = tick {
+    ///         println!("{} {} @ {}", trade.tick_type, trade.size, trade.price);
+    ///     }
+    /// }
+    /// ```
+    pub fn tick_by_tick(
+        &self,
+        contract: &Contract,
+        tick_type: realtime::TickByTickType,
+        number_of_ticks: i32,
+        ignore_size: bool,
+    ) -> Result<Subscription<'_, realtime::TickByTick>, Error> {
+        realtime::tick_by_tick(self, contract, tick_type, number_of_ticks, ignore_size)
+    }
+
     /// Requests tick by tick AllLast ticks.
     ///
+    /// AllLast includes combos, derivatives and other trades that are not reported to the tape.
+    ///
     /// # Arguments
     /// * `contract` - The [Contract] used as sample to query the available contracts. Typically, it will contain the [Contract]'s symbol, currency, security_type, and exchange.
     /// * `number_of_ticks` - number of ticks.
@@ -1122,6 +2129,8 @@ impl Client {
 
     /// Requests tick by tick Last ticks.
     ///
+    /// Last only includes trades reported to the tape. See [Client::tick_by_tick_all_last] for all trades.
+    ///
     /// # Arguments
     /// * `contract` - The [Contract] used as sample to query the available contracts. Typically, it will contain the [Contract]'s symbol, currency, security_type, and exchange.
     /// * `number_of_ticks` - number of ticks.
@@ -1171,6 +2180,27 @@ impl Client {
         market_data::switch_market_data_type(self, market_data_type)
     }
 
+    /// Switches market data back to realtime, undoing an earlier switch to frozen or delayed data.
+    ///
+    /// Same as calling [Client::switch_market_data_type] with [MarketDataType::Live]. Subscriptions report the type of data
+    /// they receive with [TickTypes::MarketDataType](realtime::TickTypes::MarketDataType).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    /// use ibapi::market_data::MarketDataType;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// client.switch_market_data_type(MarketDataType::Frozen).expect("request failed");
+    /// // ...
+    /// client.use_realtime_data().expect("request failed");
+    /// ```
+    pub fn use_realtime_data(&self) -> Result<(), Error> {
+        market_data::switch_market_data_type(self, MarketDataType::Live)
+    }
+
     /// Requests the contract's market depth (order book).
     ///
     /// # Arguments
@@ -1202,6 +2232,9 @@ impl Client {
 
     /// Requests venues for which market data is returned to market_depth (those with market makers)
     ///
+    /// Each [DepthMarketDataDescription] lists the exchange, security type, listing exchange, service data type and aggregated group.
+    /// Use the exchange name when building the [Contract] passed to [market_depth](Client::market_depth). Requires server version 112+.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -1217,6 +2250,27 @@ impl Client {
         realtime::market_depth_exchanges(self)
     }
 
+    /// Requests the exchanges making up a BBO exchange, mapping the single letter exchange codes in tick data to exchange names.
+    ///
+    /// # Arguments
+    /// * `bbo_exchange` - BBO exchange from [TickRequestParameters::bbo_exchange](realtime::TickRequestParameters::bbo_exchange), received with market data.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let components = client.smart_components("a6").expect("error requesting smart components");
+    /// for component in &components {
+    ///     println!("{} => {}", component.exchange_letter, component.exchange);
+    /// }
+    /// ```
+    pub fn smart_components(&self, bbo_exchange: &str) -> Result<Vec<realtime::SmartComponent>, Error> {
+        realtime::smart_components(self, bbo_exchange)
+    }
+
     /// Requests real time market data.
     ///
     /// Returns market data for an instrument either in real time or 10-15 minutes delayed data.
@@ -1225,21 +2279,23 @@ impl Client {
     ///
     /// * `contract` - Contract for which the data is being requested.
     /// * `generic_ticks` - IDs of the available generic ticks:
-    ///         - 100 Option Volume (currently for stocks)
-    ///         - 101 Option Open Interest (currently for stocks)
-    ///         - 104 Historical Volatility (currently for stocks)
-    ///         - 105 Average Option Volume (currently for stocks)
-    ///         - 106 Option Implied Volatility (currently for stocks)
-    ///         - 162 Index Future Premium
-    ///         - 165 Miscellaneous Stats
-    ///         - 221 Mark Price (used in TWS P&L computations)
-    ///         - 225 Auction values (volume, price and imbalance)
-    ///         - 233 RTVolume - contains the last trade price, last trade size, last trade time, total volume, VWAP, and single trade flag.
-    ///         - 236 Shortable
-    ///         - 256 Inventory
-    ///         - 258 Fundamental Ratios
-    ///         - 411 Realtime Historical Volatility
-    ///         - 456 IBDividends
+    ///   - 100 Option Volume (currently for stocks)
+    ///   - 101 Option Open Interest (currently for stocks)
+    ///   - 104 Historical Volatility (currently for stocks)
+    ///   - 105 Average Option Volume (currently for stocks)
+    ///   - 106 Option Implied Volatility (currently for stocks)
+    ///   - 162 Index Future Premium
+    ///   - 165 Miscellaneous Stats
+    ///   - 221 Mark Price (used in TWS P&L computations)
+    ///   - 225 Auction values (volume, price and imbalance)
+    ///   - 233 RTVolume - contains the last trade price, last trade size, last trade time, total volume, VWAP, and single trade flag. Decode it with [TickString::daily_volume](crate::market_data::realtime::TickString::daily_volume).
+    ///   - 236 Shortable
+    ///   - 256 Inventory
+    ///   - 258 Fundamental Ratios
+    ///   - 292 Wide News (headlines are delivered as [TickTypes::News])
+    ///   - 375 RTTrdVolume - like 233 RTVolume, but excludes unreportable trades. Decode it with [TickString::daily_volume](crate::market_data::realtime::TickString::daily_volume).
+    ///   - 411 Realtime Historical Volatility
+    ///   - 456 IBDividends
     /// * `snapshot` - for users with corresponding real time market data subscriptions. A true value will return a one-time snapshot, while a false value will provide streaming data.
     /// * `regulatory_snapshot` - snapshot for US stocks requests NBBO snapshots for users which have "US Securities Snapshot Bundle" subscription but not corresponding Network A, B, or C subscription necessary for streaming market data. One-time snapshot of current market price that will incur a fee of 1 cent to the account per snapshot.
     ///
@@ -1272,6 +2328,8 @@ impl Client {
     ///         TickTypes::OptionComputation(option_computation) => println!("{:?}", option_computation),
     ///         TickTypes::RequestParameters(tick_request_parameters) => println!("{:?}", tick_request_parameters),
     ///         TickTypes::Notice(notice) => println!("{:?}", notice),
+    ///         TickTypes::News(news) => println!("{:?}", news),
+    ///         TickTypes::MarketDataType(market_data_type) => println!("{:?}", market_data_type),
     ///         TickTypes::SnapshotEnd => subscription.cancel(),
     ///     }
     /// }
@@ -1286,6 +2344,57 @@ impl Client {
         realtime::market_data(self, contract, generic_ticks, snapshot, regulatory_snapshot)
     }
 
+    /// Requests a one time snapshot of market data and waits for it to complete.
+    ///
+    /// Sends a market data request with the snapshot flag set and consolidates the price and size ticks received
+    /// until TWS signals the end of the snapshot. Errors reported by TWS, such as a missing market data subscription,
+    /// are returned as [Error::TwsError].
+    ///
+    /// # Arguments
+    /// * `contract` - The [Contract] for which the data is being requested.
+    /// * `generic_ticks` - IDs of the available generic ticks. See [Client::market_data].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::{contracts::Contract, Client};
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let contract = Contract::stock("AAPL");
+    /// let snapshot = client.market_data_snapshot(&contract, &[]).expect("error requesting market data snapshot");
+    ///
+    /// println!("bid: {:?} ask: {:?} last: {:?}", snapshot.bid, snapshot.ask, snapshot.last);
+    /// ```
+    pub fn market_data_snapshot(&self, contract: &Contract, generic_ticks: &[&str]) -> Result<MarketDataSnapshot, Error> {
+        realtime::market_data_snapshot(self, contract, generic_ticks, false)
+    }
+
+    /// Requests a regulatory snapshot: a one time NBBO quote for a US stock. **Each request costs $0.01, charged to the account.**
+    ///
+    /// Regulatory snapshots are available to accounts with the "US Securities Snapshot Bundle" subscription, without the
+    /// Network A, B or C subscriptions streaming data requires. The quote is consolidated as in [Self::market_data_snapshot].
+    /// Requires server version 114+; older servers are rejected with [Error::ServerVersion] before a request is sent.
+    ///
+    /// # Arguments
+    /// * `contract` - The US stock [Contract] to quote.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::{contracts::Contract, Client};
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let contract = Contract::stock("AAPL");
+    /// let quote = client.regulatory_snapshot(&contract).expect("error requesting regulatory snapshot");
+    ///
+    /// println!("bid: {:?} ask: {:?}", quote.bid, quote.ask);
+    /// ```
+    pub fn regulatory_snapshot(&self, contract: &Contract) -> Result<MarketDataSnapshot, Error> {
+        realtime::market_data_snapshot(self, contract, &[], true)
+    }
+
     // === News ===
 
     /// Requests news providers which the user has subscribed to.
@@ -1330,6 +2439,9 @@ impl Client {
 
     /// Requests historical news headlines.
     ///
+    /// Headlines are streamed through the returned [Subscription] in the order received, so callers can stop early by dropping it.
+    /// The subscription completes when the historical news end message is received.
+    ///
     /// # Arguments
     ///
     /// * `contract_id`    - Contract ID of ticker. See [contract_details](Client::contract_details) for how to retrieve contract ID.
@@ -1352,9 +2464,13 @@ impl Client {
     /// let end_time = datetime!(2023-04-15 0:00 UTC);
     /// let total_results = 10;
     ///
-    /// let news_bulletins = client.news_bulletins(true).expect("request news providers failed");
-    /// for news_bulletin in &news_bulletins {
-    ///   println!("news bulletin {:?}", news_bulletin);
+    /// let articles = client
+    ///     .historical_news(contract_id, &provider_codes, start_time, end_time, total_results)
+    ///     .expect("request historical news failed");
+    ///
+    /// // Articles are yielded as they arrive; iteration ends on the historical news end marker.
+    /// for article in &articles {
+    ///     println!("article: {:?}", article);
     /// }
     /// ```
     pub fn historical_news(
@@ -1462,22 +2578,59 @@ impl Client {
         scanner::scanner_parameters(self)
     }
 
+    /// Requests the scanner parameters and parses the XML into a [ScannerParameters](scanner::ScannerParameters).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let parameters = client.scanner_parameters_parsed().expect("request scanner parameters failed");
+    /// for scan_code in &parameters.scan_codes {
+    ///     println!("{} - {}", scan_code.code, scan_code.display_name);
+    /// }
+    /// ```
+    pub fn scanner_parameters_parsed(&self) -> Result<scanner::ScannerParameters, Error> {
+        scanner::scanner_parameters_parsed(self)
+    }
+
     /// Starts a subscription to market scan results based on the provided parameters.
     ///
+    /// # Arguments
+    /// * `subscription` - Scan code, instrument, location and common filters.
+    /// * `filter`       - Additional filter options as tag/value pairs, e.g. `changePercAbove`. See [scanner_parameters_parsed](Client::scanner_parameters_parsed) for available tags. Requires server version 143+ when not empty.
+    ///
     /// # Examples
     ///
     /// ```no_run
     /// use ibapi::Client;
+    /// use ibapi::orders::TagValue;
+    /// use ibapi::scanner::ScannerSubscription;
     ///
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
-    /// let parameters = client.scanner_parameters().expect("request scanner parameters failed");
-    /// println!("{:?}", parameters);
+    /// let subscription = ScannerSubscription {
+    ///     instrument: Some("STK".to_string()),
+    ///     location_code: Some("STK.US.MAJOR".to_string()),
+    ///     scan_code: Some("TOP_PERC_GAIN".to_string()),
+    ///     ..ScannerSubscription::default()
+    /// };
+    /// let filter = vec![TagValue {
+    ///     tag: "changePercAbove".to_string(),
+    ///     value: "5".to_string(),
+    /// }];
+    ///
+    /// let results = client.scanner_subscription(&subscription, &filter).expect("request scanner subscription failed");
+    /// for scanner_data in &results {
+    ///     println!("{:?}", scanner_data);
+    /// }
     /// ```
     pub fn scanner_subscription(
         &self,
         subscription: &scanner::ScannerSubscription,
-        filter: &Vec<orders::TagValue>,
+        filter: &[orders::TagValue],
     ) -> Result<Subscription<Vec<ScannerData>>, Error> {
         scanner::scanner_subscription(self, subscription, filter)
     }
@@ -1510,6 +2663,9 @@ impl Client {
     /// * `limit`       - Maximum number of events to return. Maximum of 100.
     /// * `auto_fill`   - Fields to automatically fill in. See [AutoFill] for more information.
     ///
+    /// A start date after the end date is rejected locally. Use [WshMetadata::validate_date_range](wsh::WshMetadata::validate_date_range)
+    /// to also check the dates against the window advertised by [Client::wsh_metadata].
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -1536,9 +2692,13 @@ impl Client {
     ///
     /// # Arguments
     ///
-    /// * `filter`    - Json-formatted string containing all filter values.
-    /// * `limit`     - Maximum number of events to return. Maximum of 100.
-    /// * `auto_fill` - Fields to automatically fill in. See [AutoFill] for more information.
+    /// * `filter`     - Json-formatted string containing all filter values.
+    /// * `start_date` - Start date of the event request.
+    /// * `end_date`   - End date of the event request.
+    /// * `limit`      - Maximum number of events to return. Maximum of 100.
+    /// * `auto_fill`  - Fields to automatically fill in. See [AutoFill] for more information.
+    ///
+    /// A start date after the end date is rejected locally, as in [Client::wsh_event_data_by_contract].
     ///
     /// # Examples
     ///
@@ -1548,16 +2708,18 @@ impl Client {
     /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
     ///
     /// let filter = ""; // see https://www.interactivebrokers.com/campus/ibkr-api-page/twsapi-doc/#wsheventdata-object
-    /// let event_data = client.wsh_event_data_by_filter(filter, None, None).expect("request wsh event data failed");
+    /// let event_data = client.wsh_event_data_by_filter(filter, None, None, None, None).expect("request wsh event data failed");
     /// println!("{:?}", event_data);
     /// ```
     pub fn wsh_event_data_by_filter(
         &self,
         filter: &str,
+        start_date: Option<Date>,
+        end_date: Option<Date>,
         limit: Option<i32>,
         auto_fill: Option<AutoFill>,
     ) -> Result<Subscription<wsh::WshEventData>, Error> {
-        wsh::wsh_event_data_by_filter(self, filter, limit, auto_fill)
+        wsh::wsh_event_data_by_filter(self, filter, start_date, end_date, limit, auto_fill)
     }
 
     // == Internal Use ==
@@ -1572,6 +2734,13 @@ impl Client {
             client_id: 100,
             next_request_id: AtomicI32::new(9000),
             order_id: AtomicI32::new(-1),
+            lenient_decoding: AtomicBool::new(false),
+            strict_completed_orders: AtomicBool::new(false),
+            matching_symbols_limit: Mutex::new(None),
+            historical_data_limit: Mutex::new(None),
+            contract_details_cache: Mutex::new(None),
+            market_rule_cache: Mutex::new(HashMap::new()),
+            read_only: false,
         }
     }
 
@@ -1580,7 +2749,21 @@ impl Client {
         self.message_bus.send_request(request_id, &message)
     }
 
+    // Sends a request and returns a subscription decoding its responses with T's DataStream implementation.
+    // This is the extension point for new TWS messages: implement DataStream for the response type, passing anything
+    // its cancel message needs in the context.
+    pub(crate) fn subscribe<T: DataStream<T>>(
+        &self,
+        request_id: i32,
+        message: RequestMessage,
+        context: ResponseContext,
+    ) -> Result<Subscription<'_, T>, Error> {
+        let subscription = self.send_request(request_id, message)?;
+        Ok(Subscription::new(self, subscription, context))
+    }
+
     pub(crate) fn send_order(&self, order_id: i32, message: RequestMessage) -> Result<InternalSubscription, Error> {
+        self.check_not_read_only()?;
         debug!("send_order({:?}, {:?})", order_id, message);
         self.message_bus.send_order_request(order_id, &message)
     }
@@ -1590,6 +2773,13 @@ impl Client {
         self.message_bus.send_shared_request(message_id, &message)
     }
 
+    pub(crate) fn check_not_read_only(&self) -> Result<(), Error> {
+        if self.read_only {
+            return Err(Error::ReadOnly);
+        }
+        Ok(())
+    }
+
     pub(crate) fn check_server_version(&self, version: i32, message: &str) -> Result<(), Error> {
         if version <= self.server_version {
             Ok(())
@@ -1659,6 +2849,8 @@ pub struct Subscription<'a, T: DataStream<T>> {
     message_type: Option<OutgoingMessages>,
     phantom: PhantomData<T>,
     cancelled: AtomicBool,
+    // Set once the stream has signalled its end; stray messages received afterwards are discarded.
+    ended: AtomicBool,
     subscription: InternalSubscription,
     response_context: ResponseContext,
     error: Mutex<Option<Error>>,
@@ -1668,6 +2860,10 @@ pub struct Subscription<'a, T: DataStream<T>> {
 #[derive(Debug, Default)]
 pub(crate) struct ResponseContext {
     pub(crate) request_type: Option<OutgoingMessages>,
+    // Whether a market depth subscription was requested with SMART depth; needed to encode its cancel.
+    pub(crate) is_smart_depth: bool,
+    // Expirations and strikes to keep from option chains; applied after decoding.
+    pub(crate) option_chain_filter: Option<contracts::OptionChainFilter>,
 }
 
 #[allow(private_bounds)]
@@ -1683,6 +2879,7 @@ impl<'a, T: DataStream<T>> Subscription<'a, T> {
                 response_context: context,
                 phantom: PhantomData,
                 cancelled: AtomicBool::new(false),
+                ended: AtomicBool::new(false),
                 error: Mutex::new(None),
             }
         } else if let Some(order_id) = subscription.order_id {
@@ -1695,6 +2892,7 @@ impl<'a, T: DataStream<T>> Subscription<'a, T> {
                 response_context: context,
                 phantom: PhantomData,
                 cancelled: AtomicBool::new(false),
+                ended: AtomicBool::new(false),
                 error: Mutex::new(None),
             }
         } else if let Some(message_type) = subscription.message_type {
@@ -1707,6 +2905,7 @@ impl<'a, T: DataStream<T>> Subscription<'a, T> {
                 response_context: context,
                 phantom: PhantomData,
                 cancelled: AtomicBool::new(false),
+                ended: AtomicBool::new(false),
                 error: Mutex::new(None),
             }
         } else {
@@ -1751,6 +2950,10 @@ impl<'a, T: DataStream<T>> Subscription<'a, T> {
     /// * `Some(T)` - The next available item from the subscription
     /// * `None` - If the subscription has ended or encountered an error
     pub fn next(&self) -> Option<T> {
+        if self.has_ended() {
+            return None;
+        }
+
         match self.process_response(self.subscription.next()) {
             Some(val) => Some(val),
             None => match self.error() {
@@ -1763,6 +2966,25 @@ impl<'a, T: DataStream<T>> Subscription<'a, T> {
         }
     }
 
+    // Returns true if the end of the stream has been received, discarding any messages queued since.
+    // A connection reset reopens the stream, since the request is re-sent after reconnecting.
+    fn has_ended(&self) -> bool {
+        if !self.ended.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        self.clear_error();
+        while let Some(response) = self.subscription.try_next() {
+            if let Err(Error::ConnectionReset) = response {
+                self.ended.store(false, Ordering::Relaxed);
+                *self.error.lock().unwrap() = Some(Error::ConnectionReset);
+                return true;
+            }
+            debug!("discarding message received after end of stream: {response:?}");
+        }
+        true
+    }
+
     fn process_response(&self, response: Option<Result<ResponseMessage, Error>>) -> Option<T> {
         self.clear_error();
 
@@ -1779,8 +3001,11 @@ impl<'a, T: DataStream<T>> Subscription<'a, T> {
 
     fn process_message(&self, mut message: ResponseMessage) -> Option<T> {
         match T::decode(self.client, &mut message) {
-            Ok(val) => Some(val),
-            Err(Error::EndOfStream) => None,
+            Ok(val) => Some(T::apply_context(val, &self.response_context)),
+            Err(Error::EndOfStream) => {
+                self.ended.store(true, Ordering::Relaxed);
+                None
+            }
             Err(err) => {
                 error!("error decoding message: {err}");
                 let mut error = self.error.lock().unwrap();
@@ -1835,6 +3060,9 @@ impl<'a, T: DataStream<T>> Subscription<'a, T> {
     /// * `Some(T)` - The next available item from the subscription
     /// * `None` - If no data is immediately available or if an error occurred
     pub fn try_next(&self) -> Option<T> {
+        if self.has_ended() {
+            return None;
+        }
         self.process_response(self.subscription.try_next())
     }
 
@@ -1888,22 +3116,75 @@ impl<'a, T: DataStream<T>> Subscription<'a, T> {
     /// * `Some(T)` - The next available item from the subscription
     /// * `None` - If no data arrives within the timeout period or if an error occurred
     ///
+    /// A timeout leaves the subscription active and does not set [error](Subscription::error), so it can be used to implement
+    /// watchdogs: later calls to `next_timeout` or [next](Subscription::next) still receive messages that arrive after the timeout.
+    ///
     /// # See also
     /// - [Subscription::next] - For blocking access without timeout
     /// - [Subscription::try_next] - For immediate non-blocking access
     /// - [Subscription::error] - For checking error status
     pub fn next_timeout(&self, timeout: Duration) -> Option<T> {
+        if self.has_ended() {
+            return None;
+        }
         self.process_response(self.subscription.next_timeout(timeout))
     }
 
+    /// Collects the items of the subscription until the end of the stream, waiting at most `timeout` in total.
+    ///
+    /// Useful for subscriptions that deliver a finite batch and then signal the end, such as [Client::all_open_orders],
+    /// [Client::completed_orders] or [Client::executions]. Notices delivered by the stream are collected along with the other items.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let subscription = client.completed_orders(false).expect("request failed");
+    /// let orders = subscription.collect_timeout(Duration::from_secs(5)).expect("error receiving orders");
+    ///
+    /// println!("received {} completed orders", orders.len());
+    /// ```
+    ///
+    /// # Arguments
+    /// * `timeout` - Maximum duration to wait for the end of the stream
+    ///
+    /// # Returns
+    /// * `Ok(Vec<T>)` - The items received before the end of the stream, or before the timeout elapsed
+    /// * `Err(Error)` - If the subscription encountered an error
+    pub fn collect_timeout(&self, timeout: Duration) -> Result<Vec<T>, Error> {
+        let deadline = Instant::now() + timeout;
+        let mut items = Vec::new();
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(items);
+            }
+
+            match self.next_timeout(remaining) {
+                Some(item) => items.push(item),
+                None => match self.error() {
+                    Some(Error::UnexpectedResponse(m)) => debug!("error in subscription: {m:?}"),
+                    Some(e) => return Err(e),
+                    None => return Ok(items),
+                },
+            }
+        }
+    }
+
     /// Cancel the subscription
+    ///
+    /// Only the first call sends a cancel request to TWS. Later calls, including the implicit cancel on drop, do nothing.
     pub fn cancel(&self) {
-        if self.cancelled.load(Ordering::Relaxed) {
+        if self.cancelled.swap(true, Ordering::Relaxed) {
             return;
         }
 
-        self.cancelled.store(true, Ordering::Relaxed);
-
         if let Some(request_id) = self.request_id {
             if let Ok(message) = T::cancel_message(self.client.server_version(), self.request_id, &self.response_context) {
                 if let Err(e) = self.client.message_bus.cancel_subscription(request_id, &message) {
@@ -1919,6 +3200,7 @@ impl<'a, T: DataStream<T>> Subscription<'a, T> {
                 self.subscription.cancel();
             }
         } else if let Some(message_type) = self.message_type {
+            self.client.message_bus.release_shared_subscription(message_type);
             if let Ok(message) = T::cancel_message(self.client.server_version(), self.request_id, &self.response_context) {
                 if let Err(e) = self.client.message_bus.cancel_shared_subscription(message_type, &message) {
                     warn!("error cancelling shared subscription: {e}")
@@ -2083,6 +3365,7 @@ impl<'a, T: DataStream<T>> Drop for Subscription<'a, T> {
 /// Implementors must provide:
 /// - A decode method to convert response messages into the target type
 /// - Optionally, a cancel message generator for cleaning up subscriptions
+/// - Optionally, a step applying the subscription's [ResponseContext] to decoded items
 ///
 /// This trait is used internally by the Subscription system to handle
 /// different types of streaming data from IB.
@@ -2093,6 +3376,9 @@ pub(crate) trait DataStream<T> {
     fn cancel_message(_server_version: i32, _request_id: Option<i32>, _context: &ResponseContext) -> Result<RequestMessage, Error> {
         Err(Error::NotImplemented)
     }
+    fn apply_context(item: T, _context: &ResponseContext) -> T {
+        item
+    }
 }
 
 /// An iterator that yields items as they become available, blocking if necessary.
@@ -2169,5 +3455,115 @@ impl<'a, T: DataStream<T>> Iterator for SubscriptionTimeoutIter<'a, T> {
     }
 }
 
+/// Combines several subscriptions of the same type into a single blocking iterator.
+///
+/// Items are yielded from whichever subscription has a message ready, polling subscriptions in turn so a busy stream
+/// cannot starve the others. Errors reported by a subscription are yielded as `Err` without ending the iteration.
+/// A subscription is dropped, and thereby cancelled, once its stream ends. The iterator ends when all streams have ended.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::client::multiplex;
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::realtime::{BarSize, WhatToShow};
+/// use ibapi::Client;
+///
+/// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+///
+/// let subscriptions = ["AAPL", "MSFT"]
+///     .iter()
+///     .map(|symbol| {
+///         client
+///             .realtime_bars(&Contract::stock(symbol), BarSize::Sec5, WhatToShow::Trades, false)
+///             .expect("realtime bars request failed!")
+///     })
+///     .collect();
+///
+/// for bar in multiplex(subscriptions) {
+///     match bar {
+///         Ok(bar) => println!("bar: {bar:?}"),
+///         Err(e) => eprintln!("error: {e}"),
+///     }
+/// }
+/// ```
+#[allow(private_bounds)]
+pub fn multiplex<T: DataStream<T>>(subscriptions: Vec<Subscription<'_, T>>) -> SubscriptionMultiplex<'_, T> {
+    SubscriptionMultiplex { subscriptions, next: 0 }
+}
+
+/// An iterator over several subscriptions. See [multiplex].
+#[allow(private_bounds)]
+pub struct SubscriptionMultiplex<'a, T: DataStream<T>> {
+    subscriptions: Vec<Subscription<'a, T>>,
+    next: usize, // index of subscription to poll first
+}
+
+#[allow(private_bounds)]
+impl<T: DataStream<T>> SubscriptionMultiplex<'_, T> {
+    // Polls each subscription once, starting after the last one that produced an item.
+    fn poll(&mut self) -> Option<Result<T, Error>> {
+        let mut i = 0;
+        while i < self.subscriptions.len() {
+            let index = (self.next + i) % self.subscriptions.len();
+            let subscription = &self.subscriptions[index];
+
+            let response = match subscription.subscription.try_receive_response() {
+                Ok(response) => response,
+                Err(TryRecvError::Empty) => {
+                    i += 1;
+                    continue;
+                }
+                Err(TryRecvError::Disconnected) => {
+                    self.subscriptions.remove(index);
+                    continue;
+                }
+            };
+
+            self.next = index + 1;
+
+            if let Some(item) = subscription.process_response(Some(response)) {
+                return Some(Ok(item));
+            }
+
+            match subscription.error() {
+                Some(Error::UnexpectedResponse(message)) => debug!("error in subscription: {message:?}"),
+                Some(err) => return Some(Err(err)),
+                None => {
+                    // end of stream
+                    self.subscriptions.remove(index);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<T: DataStream<T>> Iterator for SubscriptionMultiplex<'_, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.poll() {
+                return Some(item);
+            }
+
+            if self.subscriptions.is_empty() {
+                return None;
+            }
+
+            // Nothing ready, block until any subscription has a message or disconnects.
+            let mut select = Select::new();
+            for subscription in &self.subscriptions {
+                if let Some(receiver) = subscription.subscription.receiver() {
+                    select.recv(receiver);
+                }
+            }
+            select.ready();
+        }
+    }
+}
+
 /// Marker trait for shared channels
 pub trait SharesChannel {}
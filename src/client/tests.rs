@@ -1 +1,272 @@
+use std::sync::RwLock;
+use std::thread;
 
+use crossbeam::channel;
+
+use crate::market_data::realtime::Bar;
+use crate::server_versions;
+use crate::stubs::MessageBusStub;
+use crate::transport::SubscriptionBuilder;
+
+use super::*;
+
+#[test]
+fn test_multiplex() {
+    let aapl_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "50|3|9000|1678323335|100.00|101.00|99.00|100.50|10|100.25|1|".to_owned(),
+            "50|3|9000|1678323340|100.50|101.50|99.50|101.00|20|100.75|2|".to_owned(),
+            "50|3|9000|1678323345|101.00|102.00|100.00|101.50|30|101.25|3|".to_owned(),
+        ],
+    });
+    let msft_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["50|3|9000|1678323335|200.00|201.00|199.00|200.50|40|200.25|4|".to_owned()],
+    });
+
+    let aapl_client = Client::stubbed(aapl_bus, server_versions::SIZE_RULES);
+    let msft_client = Client::stubbed(msft_bus, server_versions::SIZE_RULES);
+
+    let aapl = aapl_client
+        .realtime_bars(&Contract::stock("AAPL"), BarSize::Sec5, WhatToShow::Trades, false)
+        .expect("request failed");
+    let msft = msft_client
+        .realtime_bars(&Contract::stock("MSFT"), BarSize::Sec5, WhatToShow::Trades, false)
+        .expect("request failed");
+
+    let bars: Vec<Bar> = multiplex(vec![aapl, msft]).map(|bar| bar.expect("unexpected error")).collect();
+
+    let closes: Vec<f64> = bars.iter().map(|bar| bar.close).collect();
+    assert_eq!(closes, vec![100.50, 200.50, 101.00, 101.50], "streams should be interleaved");
+}
+
+#[test]
+fn test_next_timeout_recovers() {
+    let client = Client::stubbed(Arc::new(MessageBusStub::default()), server_versions::SIZE_RULES);
+
+    let (sender, receiver) = channel::unbounded();
+    let (signaler, _signals) = channel::unbounded();
+    let internal = SubscriptionBuilder::new().receiver(receiver).signaler(signaler).request_id(9000).build();
+    let subscription: Subscription<Bar> = Subscription::new(&client, internal, ResponseContext::default());
+
+    // gateway responds after the caller's timeout has elapsed
+    let gateway = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(200));
+        let message = ResponseMessage::from("50\03\09000\01678323335\0100.00\0101.00\099.00\0100.50\010\0100.25\01\0");
+        sender.send(Ok(message)).unwrap();
+    });
+
+    assert!(subscription.next_timeout(Duration::from_millis(10)).is_none(), "expected timeout");
+    assert!(subscription.error().is_none(), "timeout should not set an error");
+
+    let bar = subscription.next_timeout(Duration::from_secs(5)).expect("expected bar after timeout");
+    assert_eq!(bar.close, 100.50);
+
+    gateway.join().unwrap();
+}
+
+#[test]
+fn test_collect_timeout() {
+    let completed_order = |symbol: &str| {
+        format!("101|265598|{symbol}|STK||0|?||SMART|USD|{symbol}|NMS|BUY|0|MKT|0.0|0.0|DAY||DU1234567||0||1824933227|0|0|0|||||||||||0||-1||||||2147483647|0|0||3|0||0|None||0|0|0||0|0||||0|0|0|2147483647|2147483647||||IB|0|0||0|Filled|0|0|0|1.7976931348623157E308|1.7976931348623157E308|0|1|0||100|2147483647|0|Not an insider or substantial shareholder|0|0|9223372036854775807|20230306 12:28:30 America/Los_Angeles|Filled Size: 100|")
+    };
+
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            completed_order("AAPL"),
+            completed_order("MSFT"),
+            "4|2|-1|2104|Market data farm connection is OK:usfarm||".to_owned(),
+            "102|".to_owned(),
+        ],
+    });
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let subscription = client.completed_orders(false).expect("request failed");
+    let items = subscription.collect_timeout(Duration::from_secs(5)).expect("collect failed");
+
+    assert_eq!(items.len(), 3, "expected two orders and a notice");
+    let symbols: Vec<&str> = items
+        .iter()
+        .filter_map(|item| match item {
+            Orders::OrderData(order_data) => Some(order_data.contract.symbol.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(symbols, vec!["AAPL", "MSFT"]);
+    assert!(
+        matches!(&items[2], Orders::Notice(notice) if notice.code == 2104),
+        "notice should be collected"
+    );
+
+    // In strict mode decode errors are surfaced instead of being dropped.
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            completed_order("AAPL"),
+            "101|265598|AAPL|STK||0|?||SMART|USD|AAPL|NMS|BUY|".to_owned(),
+            "102|".to_owned(),
+        ],
+    });
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    client.set_strict_completed_orders(true);
+
+    let subscription = client.completed_orders(false).expect("request failed");
+    let result = subscription.collect_timeout(Duration::from_secs(5));
+    assert!(matches!(result, Err(Error::ParseError { .. })), "expected parse error, got {result:?}");
+}
+
+#[test]
+fn test_reserve_order_ids() {
+    let client_a = Client::stubbed(Arc::new(MessageBusStub::default()), server_versions::SIZE_RULES);
+    let client_b = Client::stubbed(Arc::new(MessageBusStub::default()), server_versions::SIZE_RULES);
+    client_a.set_next_order_id(100);
+    client_b.set_next_order_id(40);
+
+    // highest next valid order ID across both clients
+    let floor = 100;
+
+    let ids_a = client_a.reserve_order_ids(floor, 10).expect("reservation failed");
+    let ids_b = client_b.reserve_order_ids(ids_a.end, 10).expect("reservation failed");
+
+    assert_eq!(ids_a, 100..110);
+    assert_eq!(ids_b, 110..120);
+    assert!(ids_a.end <= ids_b.start, "ranges should not overlap");
+
+    assert_eq!(client_a.next_order_id(), 110, "sequence should continue past the reserved range");
+    assert_eq!(client_b.next_order_id(), 120, "sequence should continue past the reserved range");
+
+    // a floor below the current sequence starts at the sequence
+    assert_eq!(client_a.reserve_order_ids(0, 5).unwrap(), 111..116);
+
+    assert!(matches!(client_a.reserve_order_ids(0, 0), Err(Error::InvalidArgument(_))));
+    assert!(matches!(client_a.reserve_order_ids(i32::MAX, 1), Err(Error::InvalidArgument(_))));
+}
+
+#[test]
+fn test_token_bucket() {
+    let start = Instant::now();
+    let limit = RateLimit {
+        burst: 2,
+        interval: Duration::from_secs(1),
+    };
+    let mut bucket = TokenBucket::new(limit, start);
+
+    assert_eq!(bucket.try_acquire(start), Ok(()));
+    assert_eq!(bucket.try_acquire(start), Ok(()));
+    assert_eq!(bucket.try_acquire(start), Err(Duration::from_secs(1)), "burst exhausted");
+
+    let later = start + Duration::from_millis(500);
+    assert_eq!(bucket.try_acquire(later), Err(Duration::from_millis(500)), "half a token refilled");
+
+    let later = start + Duration::from_secs(10);
+    assert_eq!(bucket.try_acquire(later), Ok(()));
+    assert_eq!(bucket.try_acquire(later), Ok(()));
+    assert!(bucket.try_acquire(later).is_err(), "refill is capped at the burst size");
+}
+
+#[test]
+fn test_token_bucket_spacing() {
+    // IB historical data pacing: 60 requests per 10 minutes, sent in bursts of 6 every minute.
+    let start = Instant::now();
+    let limit = RateLimit {
+        burst: 6,
+        interval: Duration::from_secs(10),
+    };
+    let mut bucket = TokenBucket::new(limit, start);
+
+    let mut now = start;
+    let mut sent_at = Vec::new();
+    while sent_at.len() < 60 {
+        match bucket.try_acquire(now) {
+            Ok(()) => sent_at.push(now - start),
+            Err(wait) => now += wait,
+        }
+    }
+
+    assert!(sent_at[..6].iter().all(|at| at.is_zero()), "burst is sent immediately");
+    for (i, at) in sent_at[6..].iter().enumerate() {
+        assert_eq!(
+            *at,
+            Duration::from_secs(10 * (i as u64 + 1)),
+            "request {} is spaced by the interval",
+            i + 6
+        );
+    }
+    assert!(sent_at[59] <= Duration::from_secs(600), "60 requests fit in 10 minutes");
+}
+
+// A message type the crate does not wrap, decoded by a test-only DataStream.
+#[derive(Debug, PartialEq)]
+struct Heartbeat(i32);
+
+impl DataStream<Heartbeat> for Heartbeat {
+    fn decode(_client: &Client, message: &mut ResponseMessage) -> Result<Heartbeat, Error> {
+        message.skip(); // message type
+        message.skip(); // request id
+        Ok(Heartbeat(message.next_int()?))
+    }
+
+    fn cancel_message(_server_version: i32, request_id: Option<i32>, context: &ResponseContext) -> Result<RequestMessage, Error> {
+        let mut message = RequestMessage::new();
+        message.push_field(&context.request_type.expect("request type in context"));
+        message.push_field(&request_id.expect("request id"));
+        Ok(message)
+    }
+}
+
+#[test]
+fn test_subscribe_custom_decoder() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["999|9000|7|".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let request_id = client.next_request_id();
+    let mut request = RequestMessage::new();
+    request.push_field(&OutgoingMessages::RequestMarketData);
+    request.push_field(&request_id);
+
+    let context = ResponseContext {
+        request_type: Some(OutgoingMessages::CancelMarketData),
+        ..Default::default()
+    };
+    let subscription: Subscription<Heartbeat> = client.subscribe(request_id, request, context).expect("request failed");
+
+    assert_eq!(subscription.next(), Some(Heartbeat(7)));
+
+    subscription.cancel();
+    let requests = client.message_bus.request_messages();
+    assert_eq!(requests[0].encode_simple(), "1|9000|");
+    assert_eq!(requests[1].encode_simple(), "2|9000|", "cancel message should use the custom context");
+}
+
+#[test]
+fn test_read_only_client_refuses_orders() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![],
+    });
+
+    let mut client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    client.read_only = true;
+    assert!(client.is_read_only());
+
+    let contract = Contract::stock("AAPL");
+    let order = crate::orders::order_builder::limit_order(crate::orders::Action::Buy, 100.0, 150.0);
+
+    assert!(matches!(client.place_order(1, &contract, &order), Err(Error::ReadOnly)));
+    assert!(matches!(client.submit_order(1, &contract, &order), Err(Error::ReadOnly)));
+    assert!(matches!(client.cancel_order(1, ""), Err(Error::ReadOnly)));
+    assert!(matches!(client.global_cancel(), Err(Error::ReadOnly)));
+    assert!(
+        client.message_bus.request_messages().is_empty(),
+        "order requests must not be sent by a read-only client"
+    );
+
+    client.positions().expect("read requests are allowed");
+    assert_eq!(client.message_bus.request_messages()[0].encode_simple(), "61|1|");
+}
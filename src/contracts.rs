@@ -1,11 +1,15 @@
-use std::convert::From;
+use std::collections::HashMap;
+use std::convert::{From, Infallible};
 use std::fmt::Debug;
+use std::str::FromStr;
 use std::string::ToString;
+use std::time::{Duration, Instant};
 
-use log::{error, info};
+use log::{error, info, warn};
 use serde::Deserialize;
 use serde::Serialize;
 use tick_types::TickType;
+use time::{Date, OffsetDateTime, Time};
 
 use crate::client::DataStream;
 use crate::client::ResponseContext;
@@ -18,10 +22,13 @@ use crate::messages::ResponseMessage;
 use crate::Client;
 use crate::{server_versions, Error, ToField};
 
+pub mod common;
 pub(crate) mod decoders;
 mod encoders;
 pub mod tick_types;
 
+pub use common::contract_builder::ComboContractBuilder;
+
 #[cfg(test)]
 pub(crate) mod contract_samples;
 #[cfg(test)]
@@ -59,6 +66,16 @@ pub enum SecurityType {
     MutualFund,
     /// Crypto currency
     Crypto,
+    /// Contract for difference
+    CFD,
+    /// Continuous future, which follows the front month of a futures contract.
+    /// Used for historical data and contract details requests; it cannot be traded.
+    /// Parsed from both `CONTFUT` and `FUT+CONTFUT`, and sent as `CONTFUT`.
+    ContinuousFuture,
+    /// Treasury bill
+    Bill,
+    /// Security type not known to this library, holding the code as sent by TWS.
+    Other(String),
 }
 
 impl ToField for SecurityType {
@@ -89,6 +106,10 @@ impl std::fmt::Display for SecurityType {
             SecurityType::News => write!(f, "NEWS"),
             SecurityType::MutualFund => write!(f, "FUND"),
             SecurityType::Crypto => write!(f, "CRYPTO"),
+            SecurityType::CFD => write!(f, "CFD"),
+            SecurityType::ContinuousFuture => write!(f, "CONTFUT"),
+            SecurityType::Bill => write!(f, "BILL"),
+            SecurityType::Other(name) => write!(f, "{name}"),
         }
     }
 }
@@ -109,7 +130,60 @@ impl SecurityType {
             "NEWS" => SecurityType::News,
             "FUND" => SecurityType::MutualFund,
             "CRYPTO" => SecurityType::Crypto,
-            unsupported => todo!("Unimplemented security type: {unsupported}"),
+            "CFD" => SecurityType::CFD,
+            "CONTFUT" | "FUT+CONTFUT" => SecurityType::ContinuousFuture,
+            "BILL" => SecurityType::Bill,
+            unknown => {
+                warn!("unknown security type: {unknown}");
+                SecurityType::Other(unknown.to_owned())
+            }
+        }
+    }
+}
+
+impl FromStr for SecurityType {
+    type Err = Infallible;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Ok(SecurityType::from(name))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+/// Right identifies an option as a put or a call.
+pub enum Right {
+    /// Not an option, or right not specified.
+    #[default]
+    None,
+    /// Put option
+    Put,
+    /// Call option
+    Call,
+}
+
+impl ToField for Right {
+    fn to_field(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl std::fmt::Display for Right {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Right::None => write!(f, ""),
+            Right::Put => write!(f, "P"),
+            Right::Call => write!(f, "C"),
+        }
+    }
+}
+
+impl From<&str> for Right {
+    /// Accepts "P", "PUT", "C" and "CALL" in any case. Anything else maps to [Right::None].
+    fn from(name: &str) -> Self {
+        match name.trim().to_uppercase().as_str() {
+            "P" | "PUT" => Right::Put,
+            "C" | "CALL" => Right::Call,
+            _ => Right::None,
         }
     }
 }
@@ -172,6 +246,20 @@ impl Contract {
         }
     }
 
+    /// Creates index contract from specified symbol and exchange.
+    /// currency defaults to USD.
+    ///
+    /// Indexes do not trade, so their [historical bars](crate::market_data::historical::Bar) report -1 for volume, wap and count.
+    pub fn index(symbol: &str, exchange: &str) -> Contract {
+        Contract {
+            symbol: symbol.to_string(),
+            security_type: SecurityType::Index,
+            currency: "USD".to_string(),
+            exchange: exchange.to_string(),
+            ..Default::default()
+        }
+    }
+
     /// Creates futures contract from specified symbol
     pub fn futures(symbol: &str) -> Contract {
         Contract {
@@ -182,13 +270,90 @@ impl Contract {
         }
     }
 
+    /// Creates continuous futures contract from specified symbol.
+    ///
+    /// A continuous future stitches together the front month contracts of a future, which is useful for backtesting
+    /// with [historical data](crate::Client::historical_data). Orders cannot be placed on it; trade the underlying
+    /// [futures](Self::futures) contract instead.
+    pub fn continuous_future(symbol: &str) -> Contract {
+        Contract {
+            symbol: symbol.to_string(),
+            security_type: SecurityType::ContinuousFuture,
+            currency: "USD".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates mutual fund contract from specified symbol
+    /// currency defaults to USD and FUNDSERV exchange.
+    ///
+    /// Mutual funds report one net asset value (NAV) per day, so request their
+    /// [historical data](crate::Client::historical_data) with daily or longer bars.
+    pub fn mutual_fund(symbol: &str) -> Contract {
+        Contract {
+            symbol: symbol.to_string(),
+            security_type: SecurityType::MutualFund,
+            currency: "USD".to_string(),
+            exchange: "FUNDSERV".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates forex pair contract from specified base and quote currencies, e.g. `Contract::forex("EUR", "USD")` for EUR.USD.
+    /// exchange defaults to IDEALPRO.
+    ///
+    /// Currencies are expected to be three letter codes. Other values are passed through with a warning.
+    pub fn forex(base: &str, quote: &str) -> Contract {
+        for currency in [base, quote] {
+            if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+                warn!("forex currency {currency:?} is not a three letter code");
+            }
+        }
+
+        Contract {
+            symbol: base.to_string(),
+            security_type: SecurityType::ForexPair,
+            currency: quote.to_string(),
+            exchange: "IDEALPRO".to_string(),
+            ..Default::default()
+        }
+    }
+
     /// Creates Crypto contract from specified symbol
     pub fn crypto(symbol: &str) -> Contract {
+        Self::crypto_on(symbol, "PAXOS")
+    }
+
+    /// Creates Crypto contract from specified symbol, traded on the given exchange, e.g. ZEROHASH.
+    pub fn crypto_on(symbol: &str, exchange: &str) -> Contract {
         Contract {
             symbol: symbol.to_string(),
             security_type: SecurityType::Crypto,
             currency: "USD".to_string(),
-            exchange: "PAXOS".to_string(),
+            exchange: exchange.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates bond contract from specified CUSIP or ISIN and currency.
+    /// For bonds, the CUSIP or ISIN is input directly into the symbol field of the contract.
+    pub fn bond(cusip_or_isin: &str, currency: &str) -> Contract {
+        Contract {
+            symbol: cusip_or_isin.to_string(),
+            security_type: SecurityType::Bond,
+            currency: currency.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates CFD contract from specified symbol
+    /// currency defaults to USD and SMART exchange.
+    pub fn cfd(symbol: &str) -> Contract {
+        Contract {
+            symbol: symbol.to_string(),
+            security_type: SecurityType::CFD,
+            currency: "USD".to_string(),
+            exchange: "SMART".to_string(),
             ..Default::default()
         }
     }
@@ -210,8 +375,20 @@ impl Contract {
     /// * `symbol` - Symbols of the underlying asset.
     /// * `expiration_date` - Expiration date of option contract (YYYYMMDD)
     /// * `strike` - Strike price of the option contract.
-    /// * `right` - Option type: "C" for Call, "P" for Put
+    /// * `right` - Option type: "C" or "CALL" for Call, "P" or "PUT" for Put
     pub fn option(symbol: &str, expiration_date: &str, strike: f64, right: &str) -> Contract {
+        Contract::option_typed(symbol, expiration_date, strike, Right::from(right))
+    }
+
+    /// Creates option contract from specified symbol, expiry date, strike price and [Right].
+    /// Defaults currency to USD and exchange to SMART.
+    ///
+    /// # Arguments
+    /// * `symbol` - Symbols of the underlying asset.
+    /// * `expiration_date` - Expiration date of option contract (YYYYMMDD)
+    /// * `strike` - Strike price of the option contract.
+    /// * `right` - Option type: [Right::Call] or [Right::Put]
+    pub fn option_typed(symbol: &str, expiration_date: &str, strike: f64, right: Right) -> Contract {
         Contract {
             symbol: symbol.into(),
             security_type: SecurityType::Option,
@@ -219,7 +396,7 @@ impl Contract {
             currency: "USD".into(),
             last_trade_date_or_contract_month: expiration_date.into(), // Expiry date (YYYYMMDD)
             strike,
-            right: right.into(), // Option type: "C" for Call, "P" for Put
+            right: right.to_string(), // Normalized to "C" or "P"
             ..Default::default()
         }
     }
@@ -353,7 +530,7 @@ pub struct ContractDetails {
     pub ev_multiplier: f64,
     /// Aggregated group Indicates the smart-routing group to which a contract belongs. contracts which cannot be smart-routed have aggGroup = -1.
     pub agg_group: i32,
-    /// A list of contract identifiers that the customer is allowed to view. CUSIP/ISIN/etc. For US stocks, receiving the ISIN requires the CUSIP market data subscription. For Bonds, the CUSIP or ISIN is input directly into the symbol field of the Contract class.
+    /// A list of contract identifiers that the customer is allowed to view. CUSIP/ISIN/etc. For US stocks, receiving the ISIN requires the CUSIP market data subscription; see [ContractDetails::isin]. For Bonds, the CUSIP or ISIN is input directly into the symbol field of the Contract class.
     pub sec_id_list: Vec<TagValue>,
     /// For derivatives, the symbol of the underlying contract.
     pub under_symbol: String,
@@ -363,7 +540,8 @@ pub struct ContractDetails {
     pub market_rule_ids: Vec<String>,
     /// Real expiration date. Requires TWS 968+ and API v973.04+. Python API specifically requires API v973.06+.
     pub real_expiration_date: String,
-    /// Last trade time.
+    /// Last trade time, e.g. `16:00:00`, in the contract's [time_zone_id](ContractDetails::time_zone_id).
+    /// See [ContractDetails::parsed_last_trade_time] and [ContractDetails::last_trade_date_time].
     pub last_trade_time: String,
     /// Stock type.
     pub stock_type: String,
@@ -405,6 +583,84 @@ pub struct ContractDetails {
     pub suggested_size_increment: f64,
 }
 
+impl ContractDetails {
+    /// Parses [trading_hours](ContractDetails::trading_hours) into [TradingSession]s in the contract's [time_zone_id](ContractDetails::time_zone_id).
+    ///
+    /// Both the legacy `20090507:0700-1830,1830-2330` format and the TWS 970+ `20180323:0400-20180323:2000` format are supported.
+    pub fn parse_trading_hours(&self) -> Result<Vec<TradingSession>, Error> {
+        decoders::parse_trading_hours(&self.time_zone_id, &self.trading_hours)
+    }
+
+    /// Parses [liquid_hours](ContractDetails::liquid_hours) into [TradingSession]s in the contract's [time_zone_id](ContractDetails::time_zone_id).
+    pub fn parse_liquid_hours(&self) -> Result<Vec<TradingSession>, Error> {
+        decoders::parse_trading_hours(&self.time_zone_id, &self.liquid_hours)
+    }
+
+    /// Parses [last_trade_time](ContractDetails::last_trade_time), in `HH:MM:SS` or `HH:MM` format.
+    ///
+    /// Returns `None` when the last trade time is not set or not recognized.
+    pub fn parsed_last_trade_time(&self) -> Option<Time> {
+        decoders::parse_last_trade_time(&self.last_trade_time)
+    }
+
+    /// Date and time of the last trade of a derivative, in the contract's [time_zone_id](ContractDetails::time_zone_id).
+    ///
+    /// Combines the `YYYYMMDD` date in [last_trade_date_or_contract_month](Contract::last_trade_date_or_contract_month) with
+    /// [ContractDetails::parsed_last_trade_time]. Returns `None` when either is missing, when only a contract month (`YYYYMM`)
+    /// is known, or when the time zone is not recognized.
+    pub fn last_trade_date_time(&self) -> Option<OffsetDateTime> {
+        decoders::parse_last_trade_date_time(
+            &self.contract.last_trade_date_or_contract_month,
+            &self.last_trade_time,
+            &self.time_zone_id,
+        )
+    }
+
+    /// Looks up the identifier of the given type, e.g. `ISIN` or `CUSIP`, in [sec_id_list](ContractDetails::sec_id_list).
+    pub fn security_id(&self, security_id_type: &str) -> Option<&str> {
+        self.sec_id_list
+            .iter()
+            .find(|tag_value| tag_value.tag.eq_ignore_ascii_case(security_id_type))
+            .map(|tag_value| tag_value.value.as_str())
+    }
+
+    /// Returns the contract's ISIN from [sec_id_list](ContractDetails::sec_id_list).
+    ///
+    /// TWS leaves the ISIN out when the account lacks the permissions to see it. For US stocks that is the CUSIP
+    /// market data subscription, so a missing ISIN is reported as an error naming the subscription rather than an empty value.
+    pub fn isin(&self) -> Result<&str, Error> {
+        if let Some(isin) = self.security_id("ISIN").filter(|isin| !isin.is_empty()) {
+            return Ok(isin);
+        }
+
+        let contract = &self.contract;
+        if contract.security_type == SecurityType::Stock && contract.currency == "USD" {
+            Err(Error::Simple(format!(
+                "ISIN not returned for {}: receiving the ISIN of US stocks requires the CUSIP market data subscription",
+                contract.symbol
+            )))
+        } else {
+            Err(Error::Simple(format!(
+                "ISIN not returned for {}: it may be unavailable for this contract or not permitted for the account",
+                contract.symbol
+            )))
+        }
+    }
+}
+
+/// A trading session parsed from [ContractDetails::trading_hours] or [ContractDetails::liquid_hours].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TradingSession {
+    /// Trading date the session belongs to.
+    pub date: Date,
+    /// Time the session opens. `None` when the market is closed.
+    pub open: Option<OffsetDateTime>,
+    /// Time the session closes. `None` when the market is closed.
+    pub close: Option<OffsetDateTime>,
+    /// True if the market is closed for the date.
+    pub closed: bool,
+}
+
 /// TagValue is a convenience struct to define key-value pairs.
 #[derive(Clone, Debug, PartialEq, Default, Serialize, Deserialize)]
 pub struct TagValue {
@@ -470,6 +726,44 @@ impl DataStream<OptionComputation> for OptionComputation {
     }
 }
 
+// Expirations and strikes to keep from an option chain. See Client::option_chain_filtered.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct OptionChainFilter {
+    expiry_range: Option<(Date, Date)>,
+    strike_range: Option<(f64, f64)>,
+}
+
+impl OptionChainFilter {
+    pub(crate) fn new(expiry_range: Option<(Date, Date)>, strike_range: Option<(f64, f64)>) -> Result<Self, Error> {
+        if let Some((first, last)) = expiry_range {
+            if first > last {
+                return Err(Error::InvalidArgument(format!("expiry range starts after it ends: {first} > {last}")));
+            }
+        }
+
+        if let Some((low, high)) = strike_range {
+            if low.is_nan() || high.is_nan() || low > high {
+                return Err(Error::InvalidArgument(format!("invalid strike range: {low} to {high}")));
+            }
+        }
+
+        Ok(Self { expiry_range, strike_range })
+    }
+
+    // Expirations that are not a yyyymmdd date are dropped when an expiry range is set.
+    fn apply(&self, option_chain: &mut OptionChain) {
+        if let Some((first, last)) = self.expiry_range {
+            option_chain
+                .expirations
+                .retain(|expiration| Date::parse(expiration, crate::DATE_FORMAT.as_slice()).is_ok_and(|date| first <= date && date <= last));
+        }
+
+        if let Some((low, high)) = self.strike_range {
+            option_chain.strikes.retain(|strike| low <= *strike && *strike <= high);
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct OptionChain {
     /// The contract ID of the underlying security.
@@ -494,6 +788,94 @@ impl DataStream<OptionChain> for OptionChain {
             _ => Err(Error::UnexpectedResponse(message.clone())),
         }
     }
+
+    fn apply_context(mut option_chain: OptionChain, context: &ResponseContext) -> OptionChain {
+        if let Some(filter) = &context.option_chain_filter {
+            filter.apply(&mut option_chain);
+        }
+        option_chain
+    }
+}
+
+// Identifies a contract details query by every contract field sent in the request. Text fields are compared case-insensitively.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ContractDetailsKey {
+    contract_id: i32,
+    symbol: String,
+    security_type: String,
+    last_trade_date_or_contract_month: String,
+    strike: u64,
+    right: String,
+    multiplier: String,
+    exchange: String,
+    primary_exchange: String,
+    currency: String,
+    local_symbol: String,
+    trading_class: String,
+    include_expired: bool,
+    security_id_type: String,
+    security_id: String,
+    issuer_id: String,
+}
+
+impl From<&Contract> for ContractDetailsKey {
+    fn from(contract: &Contract) -> Self {
+        Self {
+            contract_id: contract.contract_id,
+            symbol: contract.symbol.to_uppercase(),
+            security_type: contract.security_type.to_string(),
+            last_trade_date_or_contract_month: contract.last_trade_date_or_contract_month.clone(),
+            strike: contract.strike.to_bits(),
+            right: contract.right.to_uppercase(),
+            multiplier: contract.multiplier.clone(),
+            exchange: contract.exchange.to_uppercase(),
+            primary_exchange: contract.primary_exchange.to_uppercase(),
+            currency: contract.currency.to_uppercase(),
+            local_symbol: contract.local_symbol.to_uppercase(),
+            trading_class: contract.trading_class.to_uppercase(),
+            include_expired: contract.include_expired,
+            security_id_type: contract.security_id_type.to_uppercase(),
+            security_id: contract.security_id.to_uppercase(),
+            issuer_id: contract.issuer_id.clone(),
+        }
+    }
+}
+
+// Contract details responses kept for `ttl` after they are received.
+#[derive(Debug)]
+pub(crate) struct ContractDetailsCache {
+    ttl: Duration,
+    entries: HashMap<ContractDetailsKey, (Instant, Vec<ContractDetails>)>,
+}
+
+impl ContractDetailsCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: HashMap::new(),
+        }
+    }
+
+    // Returns the cached details for the contract, dropping them if expired.
+    fn get(&mut self, contract: &Contract, now: Instant) -> Option<Vec<ContractDetails>> {
+        let key = ContractDetailsKey::from(contract);
+        match self.entries.get(&key) {
+            Some((cached_at, details)) if now.saturating_duration_since(*cached_at) < self.ttl => Some(details.clone()),
+            Some(_) => {
+                self.entries.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&mut self, contract: &Contract, details: &[ContractDetails], now: Instant) {
+        self.entries.insert(ContractDetailsKey::from(contract), (now, details.to_vec()));
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
 }
 
 // === API ===
@@ -508,6 +890,12 @@ impl DataStream<OptionChain> for OptionChain {
 pub(super) fn contract_details(client: &Client, contract: &Contract) -> Result<Vec<ContractDetails>, Error> {
     verify_contract(client, contract)?;
 
+    if let Some(cache) = client.contract_details_cache.lock()?.as_mut() {
+        if let Some(contract_details) = cache.get(contract, Instant::now()) {
+            return Ok(contract_details);
+        }
+    }
+
     let request_id = client.next_request_id();
     let packet = encoders::encode_request_contract_data(client.server_version(), request_id, contract)?;
 
@@ -535,6 +923,33 @@ pub(super) fn contract_details(client: &Client, contract: &Contract) -> Result<V
         }
     }
 
+    // Empty responses are not cached, the contract may not be listed yet.
+    if !contract_details.is_empty() {
+        if let Some(cache) = client.contract_details_cache.lock()?.as_mut() {
+            cache.insert(contract, &contract_details, Instant::now());
+        }
+    }
+
+    Ok(contract_details)
+}
+
+// Requests contract details for all expirations of a future, sorted by expiry.
+pub(super) fn futures_chain(client: &Client, symbol: &str, exchange: &str) -> Result<Vec<ContractDetails>, Error> {
+    let contract = Contract {
+        symbol: symbol.to_string(),
+        security_type: SecurityType::Future,
+        exchange: exchange.to_string(),
+        ..Default::default()
+    };
+
+    let mut contract_details = contract_details(client, &contract)?;
+    contract_details.sort_by(|a, b| {
+        a.contract
+            .last_trade_date_or_contract_month
+            .cmp(&b.contract.last_trade_date_or_contract_month)
+            .then_with(|| a.contract_month.cmp(&b.contract_month))
+    });
+
     Ok(contract_details)
 }
 
@@ -584,6 +999,7 @@ pub struct ContractDescription {
 // * `pattern` - Either start of ticker symbol or (for larger strings) company name.
 pub(super) fn matching_symbols(client: &Client, pattern: &str) -> Result<Vec<ContractDescription>, Error> {
     client.check_server_version(server_versions::REQ_MATCHING_SYMBOLS, "It does not support matching symbols requests.")?;
+    client.check_matching_symbols_limit()?;
 
     let request_id = client.next_request_id();
     let request = encoders::encode_request_matching_symbols(request_id, pattern)?;
@@ -609,7 +1025,7 @@ pub(super) fn matching_symbols(client: &Client, pattern: &str) -> Result<Vec<Con
     Ok(Vec::default())
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 /// Minimum price increment structure for a particular market rule ID.
 pub struct MarketRule {
     /// Market Rule ID requested.
@@ -618,7 +1034,7 @@ pub struct MarketRule {
     pub price_increments: Vec<PriceIncrement>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct PriceIncrement {
     pub low_edge: f64,
     pub increment: f64,
@@ -631,11 +1047,20 @@ pub struct PriceIncrement {
 pub(super) fn market_rule(client: &Client, market_rule_id: i32) -> Result<MarketRule, Error> {
     client.check_server_version(server_versions::MARKET_RULES, "It does not support market rule requests.")?;
 
+    // Market rules do not change during a session.
+    if let Some(market_rule) = client.market_rule_cache.lock()?.get(&market_rule_id) {
+        return Ok(market_rule.clone());
+    }
+
     let request = encoders::encode_request_market_rule(market_rule_id)?;
     let subscription = client.send_shared_request(OutgoingMessages::RequestMarketRule, request)?;
 
     match subscription.next() {
-        Some(Ok(mut message)) => Ok(decoders::decode_market_rule(&mut message)?),
+        Some(Ok(mut message)) => {
+            let market_rule = decoders::decode_market_rule(&mut message)?;
+            client.market_rule_cache.lock()?.insert(market_rule.market_rule_id, market_rule.clone());
+            Ok(market_rule)
+        }
         Some(Err(e)) => Err(e),
         None => Err(Error::Simple("no market rule found".into())),
     }
@@ -700,6 +1125,35 @@ pub(super) fn option_chain<'a>(
     exchange: &str,
     security_type: SecurityType,
     contract_id: i32,
+) -> Result<Subscription<'a, OptionChain>, Error> {
+    request_option_chain(client, symbol, exchange, security_type, contract_id, ResponseContext::default())
+}
+
+pub(super) fn option_chain_filtered<'a>(
+    client: &'a Client,
+    symbol: &str,
+    exchange: &str,
+    security_type: SecurityType,
+    contract_id: i32,
+    expiry_range: Option<(Date, Date)>,
+    strike_range: Option<(f64, f64)>,
+) -> Result<Subscription<'a, OptionChain>, Error> {
+    let context = ResponseContext {
+        option_chain_filter: Some(OptionChainFilter::new(expiry_range, strike_range)?),
+        ..Default::default()
+    };
+
+    request_option_chain(client, symbol, exchange, security_type, contract_id, context)
+}
+
+// Sends the option chain request shared by the unfiltered and filtered variants; the context carries any filter.
+fn request_option_chain<'a>(
+    client: &'a Client,
+    symbol: &str,
+    exchange: &str,
+    security_type: SecurityType,
+    contract_id: i32,
+    context: ResponseContext,
 ) -> Result<Subscription<'a, OptionChain>, Error> {
     client.check_server_version(
         server_versions::SEC_DEF_OPT_PARAMS_REQ,
@@ -710,5 +1164,5 @@ pub(super) fn option_chain<'a>(
     let request = encoders::encode_request_option_chain(request_id, symbol, exchange, security_type, contract_id)?;
     let subscription = client.send_request(request_id, request)?;
 
-    Ok(Subscription::new(client, subscription, ResponseContext::default()))
+    Ok(Subscription::new(client, subscription, context))
 }
@@ -0,0 +1,94 @@
+use crate::contracts::{ComboLeg, Contract, SecurityType};
+use crate::orders::Action;
+use crate::Error;
+
+#[cfg(test)]
+mod tests;
+
+/// Builds a combo (BAG) contract from its legs.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::contracts::ComboContractBuilder;
+/// use ibapi::orders::Action;
+///
+/// // Calendar spread: sell the front month, buy the back month.
+/// let contract = ComboContractBuilder::new("CL", "USD")
+///     .add_leg(174230608, 1, Action::Sell, "NYMEX")
+///     .add_leg(174230596, 1, Action::Buy, "NYMEX")
+///     .build()
+///     .expect("invalid combo");
+///
+/// assert!(contract.is_bag());
+/// ```
+#[derive(Clone, Debug)]
+pub struct ComboContractBuilder {
+    symbol: String,
+    currency: String,
+    exchange: String,
+    legs: Vec<ComboLeg>,
+}
+
+impl ComboContractBuilder {
+    /// Starts a combo on the given symbol and currency. Exchange defaults to SMART.
+    /// The symbol can be the symbol of the first leg or the currency.
+    pub fn new(symbol: &str, currency: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            currency: currency.to_string(),
+            exchange: "SMART".to_string(),
+            legs: vec![],
+        }
+    }
+
+    /// Sets the exchange the combo is routed to.
+    pub fn exchange(mut self, exchange: &str) -> Self {
+        self.exchange = exchange.to_string();
+        self
+    }
+
+    /// Adds a leg for the given contract id.
+    ///
+    /// # Arguments
+    /// * `contract_id` - IB contract id of the leg.
+    /// * `ratio` - Relative number of contracts for the leg. Must be positive.
+    /// * `action` - Side of the leg.
+    /// * `exchange` - Exchange the leg is routed to.
+    pub fn add_leg(mut self, contract_id: i32, ratio: i32, action: Action, exchange: &str) -> Self {
+        self.legs.push(ComboLeg {
+            contract_id,
+            ratio,
+            action: action.to_string(),
+            exchange: exchange.to_string(),
+            ..ComboLeg::default()
+        });
+        self
+    }
+
+    /// Validates the legs and returns the combo contract.
+    pub fn build(self) -> Result<Contract, Error> {
+        if self.legs.len() < 2 {
+            return Err(Error::InvalidArgument(format!(
+                "combo contract requires at least two legs, found {}",
+                self.legs.len()
+            )));
+        }
+
+        if let Some(leg) = self.legs.iter().find(|leg| leg.ratio <= 0) {
+            return Err(Error::InvalidArgument(format!(
+                "combo leg {} has non-positive ratio {}",
+                leg.contract_id, leg.ratio
+            )));
+        }
+
+        Ok(Contract {
+            symbol: self.symbol,
+            security_type: SecurityType::Spread,
+            currency: self.currency,
+            exchange: self.exchange,
+            combo_legs: self.legs,
+            ..Contract::default()
+        })
+    }
+}
@@ -0,0 +1,41 @@
+use super::*;
+
+#[test]
+fn test_calendar_spread() {
+    let contract = ComboContractBuilder::new("CL", "USD")
+        .add_leg(174230608, 1, Action::Sell, "NYMEX")
+        .add_leg(174230596, 1, Action::Buy, "NYMEX")
+        .build()
+        .expect("failed to build combo");
+
+    assert!(contract.is_bag());
+    assert_eq!(contract.security_type, SecurityType::Spread);
+    assert_eq!(contract.symbol, "CL");
+    assert_eq!(contract.currency, "USD");
+    assert_eq!(contract.exchange, "SMART");
+    assert_eq!(contract.combo_legs.len(), 2);
+
+    assert_eq!(contract.combo_legs[0].contract_id, 174230608);
+    assert_eq!(contract.combo_legs[0].ratio, 1);
+    assert_eq!(contract.combo_legs[0].action, "SELL");
+    assert_eq!(contract.combo_legs[0].exchange, "NYMEX");
+
+    assert_eq!(contract.combo_legs[1].contract_id, 174230596);
+    assert_eq!(contract.combo_legs[1].ratio, 1);
+    assert_eq!(contract.combo_legs[1].action, "BUY");
+    assert_eq!(contract.combo_legs[1].exchange, "NYMEX");
+}
+
+#[test]
+fn test_combo_validation() {
+    let result = ComboContractBuilder::new("CL", "USD")
+        .add_leg(174230608, 1, Action::Sell, "NYMEX")
+        .build();
+    assert!(matches!(result, Err(Error::InvalidArgument(_))), "single leg: {result:?}");
+
+    let result = ComboContractBuilder::new("CL", "USD")
+        .add_leg(174230608, 1, Action::Sell, "NYMEX")
+        .add_leg(174230596, 0, Action::Buy, "NYMEX")
+        .build();
+    assert!(matches!(result, Err(Error::InvalidArgument(_))), "zero ratio: {result:?}");
+}
@@ -1,6 +1,11 @@
+use log::warn;
+use time::macros::format_description;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+use time_tz::{timezones, PrimitiveDateTimeExt, Tz};
+
 use crate::{contracts::tick_types::TickType, contracts::SecurityType, messages::ResponseMessage, orders::TagValue, server_versions, Error};
 
-use super::{Contract, ContractDescription, ContractDetails, MarketRule, OptionChain, OptionComputation, PriceIncrement};
+use super::{Contract, ContractDescription, ContractDetails, MarketRule, OptionChain, OptionComputation, PriceIncrement, TradingSession};
 
 #[cfg(test)]
 mod tests;
@@ -135,6 +140,101 @@ fn read_last_trade_date(contract: &mut ContractDetails, last_trade_date_or_contr
     Ok(())
 }
 
+// Parses trading or liquid hours. e.g. 20180323:0400-20180323:2000;20180326:CLOSED or 20090507:0700-1830,1830-2330;20090508:CLOSED
+pub(super) fn parse_trading_hours(time_zone_id: &str, hours: &[String]) -> Result<Vec<TradingSession>, Error> {
+    let time_zone = trading_hours_time_zone(time_zone_id)?;
+
+    let mut sessions = Vec::new();
+    for day in hours.iter().map(|day| day.trim()).filter(|day| !day.is_empty()) {
+        let (date, ranges) = day
+            .split_once(':')
+            .ok_or_else(|| Error::Simple(format!("invalid trading hours: {day}")))?;
+        let date = parse_trading_hours_date(date)?;
+
+        if ranges == "CLOSED" {
+            sessions.push(TradingSession {
+                date,
+                open: None,
+                close: None,
+                closed: true,
+            });
+            continue;
+        }
+
+        for range in ranges.split(',') {
+            let (open, close) = range
+                .split_once('-')
+                .ok_or_else(|| Error::Simple(format!("invalid trading hours range: {range}")))?;
+
+            let open = parse_trading_hours_time(date, open)?;
+            let mut close = parse_trading_hours_time(date, close)?;
+
+            // legacy format omits the closing date, so a close before the open spans midnight.
+            if close < open {
+                close += time::Duration::days(1);
+            }
+
+            sessions.push(TradingSession {
+                date,
+                open: Some(assume_time_zone(open, time_zone)?),
+                close: Some(assume_time_zone(close, time_zone)?),
+                closed: false,
+            });
+        }
+    }
+
+    Ok(sessions)
+}
+
+fn trading_hours_time_zone(time_zone_id: &str) -> Result<&'static Tz, Error> {
+    if time_zone_id.is_empty() {
+        warn!("time zone id not specified. assuming UTC, but that may be incorrect!");
+        return Ok(timezones::db::UTC);
+    }
+
+    timezones::get_by_name(time_zone_id).ok_or_else(|| Error::Simple(format!("unknown time zone: {time_zone_id}")))
+}
+
+fn parse_trading_hours_date(date: &str) -> Result<Date, Error> {
+    Ok(Date::parse(date, format_description!("[year][month][day]"))?)
+}
+
+// Parses HHMM or YYYYMMDD:HHMM. Times without a date fall on the session date.
+fn parse_trading_hours_time(date: Date, time: &str) -> Result<PrimitiveDateTime, Error> {
+    let (date, time) = match time.split_once(':') {
+        Some((date, time)) => (parse_trading_hours_date(date)?, time),
+        None => (date, time),
+    };
+
+    let time = Time::parse(time, format_description!("[hour][minute]"))?;
+
+    Ok(PrimitiveDateTime::new(date, time))
+}
+
+// Parses HH:MM:SS or HH:MM.
+pub(super) fn parse_last_trade_time(text: &str) -> Option<Time> {
+    let text = text.trim();
+    Time::parse(text, format_description!("[hour]:[minute]:[second]"))
+        .or_else(|_| Time::parse(text, format_description!("[hour]:[minute]")))
+        .ok()
+}
+
+// Combines a YYYYMMDD last trade date with the last trade time. Contract months (YYYYMM) have no day and yield None.
+pub(super) fn parse_last_trade_date_time(date: &str, time: &str, time_zone_id: &str) -> Option<OffsetDateTime> {
+    let date = parse_trading_hours_date(date.trim()).ok()?;
+    let time = parse_last_trade_time(time)?;
+    let time_zone = trading_hours_time_zone(time_zone_id).ok()?;
+
+    assume_time_zone(PrimitiveDateTime::new(date, time), time_zone).ok()
+}
+
+fn assume_time_zone(date_time: PrimitiveDateTime, time_zone: &Tz) -> Result<OffsetDateTime, Error> {
+    date_time
+        .assume_timezone(time_zone)
+        .take_first()
+        .ok_or_else(|| Error::Simple(format!("invalid time {date_time} for time zone")))
+}
+
 pub(super) fn decode_contract_descriptions(server_version: i32, message: &mut ResponseMessage) -> Result<Vec<ContractDescription>, Error> {
     message.skip(); // message type
 
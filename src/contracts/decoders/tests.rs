@@ -1,3 +1,5 @@
+use time::macros::{date, datetime};
+
 use crate::testdata::responses::MARKET_RULE;
 
 use super::*;
@@ -17,3 +19,73 @@ fn test_decode_market_rule() {
         "market_rule.price_increments[0].increment"
     );
 }
+
+#[test]
+fn test_parse_trading_hours() {
+    let hours = vec![
+        "20180323:0400-20180323:2000".to_string(),
+        "20180324:CLOSED".to_string(),
+        "20180325:1700-20180326:1600".to_string(),
+    ];
+
+    let sessions = parse_trading_hours("US/Eastern", &hours).expect("error parsing trading hours");
+
+    assert_eq!(sessions.len(), 3, "sessions.len()");
+
+    assert_eq!(sessions[0].date, date!(2018 - 03 - 23), "sessions[0].date");
+    assert_eq!(sessions[0].open, Some(datetime!(2018-03-23 04:00 -4)), "sessions[0].open");
+    assert_eq!(sessions[0].close, Some(datetime!(2018-03-23 20:00 -4)), "sessions[0].close");
+    assert!(!sessions[0].closed, "sessions[0].closed");
+
+    assert_eq!(sessions[1].date, date!(2018 - 03 - 24), "sessions[1].date");
+    assert_eq!(sessions[1].open, None, "sessions[1].open");
+    assert_eq!(sessions[1].close, None, "sessions[1].close");
+    assert!(sessions[1].closed, "sessions[1].closed");
+
+    // overnight session
+    assert_eq!(sessions[2].date, date!(2018 - 03 - 25), "sessions[2].date");
+    assert_eq!(sessions[2].open, Some(datetime!(2018-03-25 17:00 -4)), "sessions[2].open");
+    assert_eq!(sessions[2].close, Some(datetime!(2018-03-26 16:00 -4)), "sessions[2].close");
+    assert!(!sessions[2].closed, "sessions[2].closed");
+}
+
+#[test]
+fn test_parse_trading_hours_legacy_format() {
+    let hours = vec![
+        "20090507:0700-1830,1830-2330".to_string(),
+        "20090508:CLOSED".to_string(),
+        "20090510:1800-0200".to_string(),
+    ];
+
+    let sessions = parse_trading_hours("US/Central", &hours).expect("error parsing trading hours");
+
+    assert_eq!(sessions.len(), 4, "sessions.len()");
+
+    assert_eq!(sessions[0].date, date!(2009 - 05 - 07), "sessions[0].date");
+    assert_eq!(sessions[0].open, Some(datetime!(2009-05-07 07:00 -5)), "sessions[0].open");
+    assert_eq!(sessions[0].close, Some(datetime!(2009-05-07 18:30 -5)), "sessions[0].close");
+
+    assert_eq!(sessions[1].date, date!(2009 - 05 - 07), "sessions[1].date");
+    assert_eq!(sessions[1].open, Some(datetime!(2009-05-07 18:30 -5)), "sessions[1].open");
+    assert_eq!(sessions[1].close, Some(datetime!(2009-05-07 23:30 -5)), "sessions[1].close");
+
+    assert_eq!(sessions[2].date, date!(2009 - 05 - 08), "sessions[2].date");
+    assert!(sessions[2].closed, "sessions[2].closed");
+
+    // close before open spans midnight
+    assert_eq!(sessions[3].date, date!(2009 - 05 - 10), "sessions[3].date");
+    assert_eq!(sessions[3].open, Some(datetime!(2009-05-10 18:00 -5)), "sessions[3].open");
+    assert_eq!(sessions[3].close, Some(datetime!(2009-05-11 02:00 -5)), "sessions[3].close");
+}
+
+#[test]
+fn test_parse_trading_hours_errors() {
+    let hours = vec!["20180323:0400-20180323:2000".to_string()];
+    assert!(parse_trading_hours("Not/AZone", &hours).is_err(), "unknown time zone");
+
+    let hours = vec!["20180323".to_string()];
+    assert!(parse_trading_hours("US/Eastern", &hours).is_err(), "missing hours");
+
+    let hours = vec!["".to_string()];
+    assert_eq!(parse_trading_hours("US/Eastern", &hours).unwrap(), vec![], "empty hours");
+}
@@ -1,7 +1,11 @@
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use time::macros::{date, datetime, time};
 
 use super::*;
 
+use crate::client::RateLimit;
 use crate::stubs::MessageBusStub;
 
 #[test]
@@ -189,4 +193,531 @@ fn test_read_last_trade_date() {
 }
 
 #[test]
-fn request_matching_symbols() {}
+fn request_matching_symbols() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["79|9000|1|265598|AAPL|STK|NASDAQ|USD|2|OPT|WAR|".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let descriptions: Vec<ContractDescription> = client.matching_symbols("AAP").expect("request failed").collect();
+
+    assert_eq!(client.message_bus.request_messages()[0].encode_simple(), "81|9000|AAP|");
+    assert_eq!(descriptions.len(), 1);
+    assert_eq!(descriptions[0].contract.contract_id, 265598);
+    assert_eq!(descriptions[0].contract.symbol, "AAPL");
+    assert_eq!(descriptions[0].contract.primary_exchange, "NASDAQ");
+    assert_eq!(descriptions[0].derivative_security_types, vec!["OPT", "WAR"]);
+}
+
+#[test]
+fn request_matching_symbols_rate_limited() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["79|9000|0|".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    client.set_matching_symbols_limit(Some(RateLimit {
+        burst: 2,
+        interval: Duration::from_secs(60),
+    }));
+
+    assert!(client.matching_symbols("A").is_ok());
+    assert!(client.matching_symbols("AA").is_ok());
+    let result = client.matching_symbols("AAP");
+    assert!(matches!(result, Err(Error::RateLimited(_))), "third search should be throttled");
+    assert_eq!(client.message_bus.request_messages().len(), 2, "throttled search should not be sent");
+
+    client.set_matching_symbols_limit(None);
+    assert!(client.matching_symbols("AAPL").is_ok(), "searches are not throttled without a limit");
+    assert_eq!(client.message_bus.request_messages().len(), 3);
+}
+
+#[test]
+fn request_contract_details_cached() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "10|9001|TSLA|STK||0||SMART|USD|TSLA|NMS|NMS|76792991|0.01||ACTIVETIM,AD,ADJUST,ALERT,ALGO,ALLOC,AON,AVGCOST,BASKET,BENCHPX,CASHQTY,COND,CONDORDER,DARKONLY,DARKPOLL,DAY,DEACT,DEACTDIS,DEACTEOD,DIS,DUR,GAT,GTC,GTD,GTT,HID,IBKRATS,ICE,IMB,IOC,LIT,LMT,LOC,MIDPX,MIT,MKT,MOC,MTL,NGCOMB,NODARK,NONALGO,OCA,OPG,OPGREROUT,PEGBENCH,PEGMID,POSTATS,POSTONLY,PREOPGRTH,PRICECHK,REL,REL2MID,RELPCTOFS,RPI,RTH,SCALE,SCALEODD,SCALERST,SIZECHK,SNAPMID,SNAPMKT,SNAPREL,STP,STPLMT,SWEEP,TRAIL,TRAILLIT,TRAILLMT,TRAILMIT,WHATIF|SMART,AMEX,NYSE,CBOE,PHLX,ISE,CHX,ARCA,ISLAND,DRCTEDGE,BEX,BATS,EDGEA,CSFBALGO,JEFFALGO,BYX,IEX,EDGX,FOXRIVER,PEARL,NYSENAT,LTSE,MEMX,PSX|1|0|TESLA INC|NASDAQ||Consumer, Cyclical|Auto Manufacturers|Auto-Cars/Light Trucks|US/Eastern|20221229:0400-20221229:2000;20221230:0400-20221230:2000;20221231:CLOSED;20230101:CLOSED;20230102:CLOSED;20230103:0400-20230103:2000|20221229:0930-20221229:1600;20221230:0930-20221230:1600;20221231:CLOSED;20230101:CLOSED;20230102:CLOSED;20230103:0930-20230103:1600|||1|ISIN|US88160R1014|1|||26,26,26,26,26,26,26,26,26,26,26,26,26,26,26,26,26,26,26,26,26,26,26,26||COMMON|1|1|100||".to_string(),
+            "52|1|9000||".to_string(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    client.set_contract_details_cache(Some(Duration::from_secs(3600)));
+
+    let first = client.contract_details(&Contract::stock("TSLA")).expect("request failed");
+    let second = client.contract_details(&Contract::stock("tsla")).expect("request failed");
+
+    assert_eq!(first.len(), 1);
+    assert_eq!(first, second);
+    assert_eq!(
+        client.message_bus.request_messages().len(),
+        1,
+        "second request should be served from cache"
+    );
+
+    client.contract_details(&Contract::stock("AAPL")).expect("request failed");
+    assert_eq!(client.message_bus.request_messages().len(), 2, "different contract should be requested");
+
+    client.clear_contract_details_cache();
+    client.contract_details(&Contract::stock("TSLA")).expect("request failed");
+    assert_eq!(client.message_bus.request_messages().len(), 3, "cleared cache should be requested");
+
+    // An expired response is requested again.
+    client.set_contract_details_cache(Some(Duration::ZERO));
+    client.contract_details(&Contract::stock("TSLA")).expect("request failed");
+    client.contract_details(&Contract::stock("TSLA")).expect("request failed");
+    assert_eq!(client.message_bus.request_messages().len(), 5, "expired response should be requested");
+}
+
+#[test]
+fn test_contract_details_cache_ttl() {
+    let mut cache = ContractDetailsCache::new(Duration::from_secs(60));
+    let contract = Contract::stock("TSLA");
+    let details = vec![ContractDetails::default(), ContractDetails::default()];
+    let now = Instant::now();
+
+    cache.insert(&contract, &details, now);
+
+    assert_eq!(
+        cache.get(&contract, now + Duration::from_secs(59)),
+        Some(details),
+        "multiple results are cached as a unit"
+    );
+    assert_eq!(cache.get(&Contract::futures("TSLA"), now), None, "security type is part of the key");
+    assert_eq!(cache.get(&contract, now + Duration::from_secs(60)), None, "entry expires after ttl");
+    assert!(cache.entries.is_empty(), "expired entry is dropped");
+}
+
+#[test]
+fn test_contract_details_cache_key() {
+    let mut cache = ContractDetailsCache::new(Duration::from_secs(60));
+    let now = Instant::now();
+
+    let base = Contract::futures("ES");
+    let details = vec![ContractDetails::default()];
+    cache.insert(&base, &details, now);
+
+    let queries = [
+        (
+            "local_symbol",
+            Contract {
+                local_symbol: "ESZ4".into(),
+                ..base.clone()
+            },
+        ),
+        (
+            "trading_class",
+            Contract {
+                trading_class: "MES".into(),
+                ..base.clone()
+            },
+        ),
+        (
+            "multiplier",
+            Contract {
+                multiplier: "5".into(),
+                ..base.clone()
+            },
+        ),
+        (
+            "primary_exchange",
+            Contract {
+                primary_exchange: "CME".into(),
+                ..base.clone()
+            },
+        ),
+        (
+            "security_id_type",
+            Contract {
+                security_id_type: "ISIN".into(),
+                ..base.clone()
+            },
+        ),
+        (
+            "security_id",
+            Contract {
+                security_id: "US0000000000".into(),
+                ..base.clone()
+            },
+        ),
+        (
+            "include_expired",
+            Contract {
+                include_expired: true,
+                ..base.clone()
+            },
+        ),
+        (
+            "issuer_id",
+            Contract {
+                issuer_id: "e1234567".into(),
+                ..base.clone()
+            },
+        ),
+    ];
+
+    for (field, contract) in queries {
+        assert_eq!(cache.get(&contract, now), None, "{field} is part of the key");
+    }
+
+    let same = Contract {
+        symbol: "es".into(),
+        ..base.clone()
+    };
+    assert_eq!(cache.get(&same, now), Some(details), "text fields are compared case-insensitively");
+}
+
+#[test]
+fn test_security_type_round_trip() {
+    let security_types = [
+        SecurityType::Stock,
+        SecurityType::Option,
+        SecurityType::Future,
+        SecurityType::Index,
+        SecurityType::FuturesOption,
+        SecurityType::ForexPair,
+        SecurityType::Spread,
+        SecurityType::Warrant,
+        SecurityType::Bond,
+        SecurityType::Commodity,
+        SecurityType::News,
+        SecurityType::MutualFund,
+        SecurityType::Crypto,
+        SecurityType::CFD,
+        SecurityType::ContinuousFuture,
+        SecurityType::Bill,
+        SecurityType::Other("IOPT".to_owned()),
+    ];
+
+    for security_type in security_types {
+        let name = security_type.to_string();
+        assert_eq!(SecurityType::from(&name), security_type, "{name} should round trip");
+        assert_eq!(name.parse::<SecurityType>(), Ok(security_type));
+    }
+
+    assert_eq!("CONTFUT".parse::<SecurityType>(), Ok(SecurityType::ContinuousFuture));
+    assert_eq!("FUND".parse::<SecurityType>(), Ok(SecurityType::MutualFund));
+    assert_eq!("BAG".parse::<SecurityType>(), Ok(SecurityType::Spread));
+    assert_eq!("BILL".parse::<SecurityType>(), Ok(SecurityType::Bill));
+}
+
+#[test]
+fn request_market_rule_cached() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["93|26|1|0|0.01|".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let first = client.market_rule(26).expect("request failed");
+    let second = client.market_rule(26).expect("request failed");
+
+    assert_eq!(first.market_rule_id, 26);
+    assert_eq!(second.price_increments[0].increment, 0.01);
+    assert_eq!(
+        client.message_bus.request_messages().len(),
+        1,
+        "second lookup should be served from cache"
+    );
+
+    client.clear_market_rule_cache();
+    client.market_rule(26).expect("request failed");
+    assert_eq!(client.message_bus.request_messages().len(), 2, "cleared rule should be requested again");
+}
+
+#[test]
+fn test_right_from_str() {
+    assert_eq!(Right::from("C"), Right::Call);
+    assert_eq!(Right::from("CALL"), Right::Call);
+    assert_eq!(Right::from("call"), Right::Call);
+    assert_eq!(Right::from("P"), Right::Put);
+    assert_eq!(Right::from("PUT"), Right::Put);
+    assert_eq!(Right::from("put"), Right::Put);
+    assert_eq!(Right::from(""), Right::None);
+    assert_eq!(Right::from("?"), Right::None);
+
+    assert_eq!(Right::Call.to_string(), "C");
+    assert_eq!(Right::Put.to_string(), "P");
+    assert_eq!(Right::None.to_string(), "");
+}
+
+#[test]
+fn test_option_contract_right_normalized() {
+    let typed = Contract::option_typed("AAPL", "20250620", 240.0, Right::Call);
+
+    assert_eq!(typed.right, "C");
+    assert_eq!(Contract::option("AAPL", "20250620", 240.0, "CALL"), typed);
+    assert_eq!(Contract::option("AAPL", "20250620", 240.0, "C"), typed);
+
+    let typed = Contract::option_typed("AAPL", "20250620", 240.0, Right::Put);
+
+    assert_eq!(typed.right, "P");
+    assert_eq!(Contract::option("AAPL", "20250620", 240.0, "PUT"), typed);
+    assert_eq!(Contract::option("AAPL", "20250620", 240.0, "P"), typed);
+}
+
+#[test]
+fn test_bond_contract() {
+    let contract = Contract::bond("912828C57", "USD");
+
+    assert_eq!(contract.symbol, "912828C57");
+    assert_eq!(contract.security_type, SecurityType::Bond);
+    assert_eq!(contract.currency, "USD");
+    assert_eq!(contract.exchange, "");
+
+    let contract = Contract::bond("DE0001102580", "EUR");
+
+    assert_eq!(contract.symbol, "DE0001102580");
+    assert_eq!(contract.currency, "EUR");
+}
+
+#[test]
+fn test_cfd_contract() {
+    let contract = Contract::cfd("IBDE30");
+
+    assert_eq!(contract.symbol, "IBDE30");
+    assert_eq!(contract.security_type, SecurityType::CFD);
+    assert_eq!(contract.security_type.to_string(), "CFD");
+    assert_eq!(SecurityType::from("CFD"), SecurityType::CFD);
+    assert_eq!(contract.currency, "USD");
+    assert_eq!(contract.exchange, "SMART");
+}
+
+#[test]
+fn test_forex_contract() {
+    let contract = Contract::forex("EUR", "USD");
+
+    assert_eq!(contract.symbol, "EUR");
+    assert_eq!(contract.security_type, SecurityType::ForexPair);
+    assert_eq!(contract.security_type.to_string(), "CASH");
+    assert_eq!(contract.currency, "USD");
+    assert_eq!(contract.exchange, "IDEALPRO");
+
+    let contract = Contract::forex("GBP", "JPY");
+
+    assert_eq!(contract.symbol, "GBP");
+    assert_eq!(contract.security_type, SecurityType::ForexPair);
+    assert_eq!(contract.currency, "JPY");
+    assert_eq!(contract.exchange, "IDEALPRO");
+}
+
+#[test]
+fn test_crypto_contract() {
+    let contract = Contract::crypto("BTC");
+
+    assert_eq!(contract.symbol, "BTC");
+    assert_eq!(contract.security_type, SecurityType::Crypto);
+    assert_eq!(contract.currency, "USD");
+    assert_eq!(contract.exchange, "PAXOS");
+
+    let contract = Contract::crypto_on("ETH", "ZEROHASH");
+
+    assert_eq!(contract.symbol, "ETH");
+    assert_eq!(contract.security_type, SecurityType::Crypto);
+    assert_eq!(contract.currency, "USD");
+    assert_eq!(contract.exchange, "ZEROHASH");
+}
+
+#[test]
+fn test_continuous_future_contract() {
+    let contract = Contract::continuous_future("ES");
+
+    assert_eq!(contract.symbol, "ES");
+    assert_eq!(contract.security_type, SecurityType::ContinuousFuture);
+    assert_eq!(contract.security_type.to_string(), "CONTFUT");
+    assert_eq!(SecurityType::from("CONTFUT"), SecurityType::ContinuousFuture);
+    assert_eq!(SecurityType::from("FUT+CONTFUT"), SecurityType::ContinuousFuture);
+    assert_eq!(contract.currency, "USD");
+    assert_eq!(contract.exchange, "");
+    assert_eq!(contract.last_trade_date_or_contract_month, "");
+}
+
+#[test]
+fn test_mutual_fund_contract() {
+    let contract = Contract::mutual_fund("VFIAX");
+
+    assert_eq!(contract.symbol, "VFIAX");
+    assert_eq!(contract.security_type, SecurityType::MutualFund);
+    assert_eq!(contract.security_type.to_string(), "FUND");
+    assert_eq!(contract.currency, "USD");
+    assert_eq!(contract.exchange, "FUNDSERV");
+}
+
+#[test]
+fn request_futures_chain() {
+    const TEMPLATE: &str = "10|9001|ES|FUT|EXPIRY|0||CME|USD|LOCAL|ES|ES|CONID|0.25||ACTIVETIM,AD,LMT,MKT,STP,STPLMT|CME,QBALGO|1|0|E-mini S&P 500|CME|MONTH|Indices|Equity Index|S&P 500|US/Central|20230102:1700-20230103:1600|20230103:0830-20230103:1600|||1|||1|||26,26||IND|EXPIRY|1|50||";
+
+    let futures = [
+        ("20230915", "ESU3", "495512563", "202309"),
+        ("20230317", "ESH3", "495512551", "202303"),
+        ("20230616", "ESM3", "495512557", "202306"),
+    ];
+
+    let mut response_messages: Vec<String> = futures
+        .iter()
+        .map(|(expiry, local_symbol, contract_id, month)| {
+            TEMPLATE
+                .replace("EXPIRY", expiry)
+                .replace("LOCAL", local_symbol)
+                .replace("CONID", contract_id)
+                .replace("MONTH", month)
+        })
+        .collect();
+    response_messages.push("52|1|9001||".to_string());
+
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages,
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let chain = client.futures_chain("ES", "CME").expect("request futures chain failed");
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(request_messages[0].encode_simple(), "9|8|9000|0|ES|FUT||0|||CME|||||0|||");
+
+    let expirations: Vec<&str> = chain.iter().map(|d| d.contract.last_trade_date_or_contract_month.as_str()).collect();
+    assert_eq!(expirations, vec!["20230317", "20230616", "20230915"]);
+
+    let local_symbols: Vec<&str> = chain.iter().map(|d| d.contract.local_symbol.as_str()).collect();
+    assert_eq!(local_symbols, vec!["ESH3", "ESM3", "ESU3"]);
+    assert!(chain.iter().all(|d| d.contract.security_type == SecurityType::Future));
+}
+
+#[test]
+fn test_contract_details_last_trade_time() {
+    let mut details = ContractDetails {
+        contract: Contract::futures("ES"),
+        contract_month: "202403".to_owned(),
+        last_trade_time: "08:30:00".to_owned(),
+        time_zone_id: "US/Central".to_owned(),
+        ..ContractDetails::default()
+    };
+    details.contract.last_trade_date_or_contract_month = "20240315".to_owned();
+
+    assert_eq!(details.parsed_last_trade_time(), Some(time!(08:30:00)));
+    assert_eq!(details.last_trade_date_time(), Some(datetime!(2024-03-15 08:30:00 -5)));
+
+    details.last_trade_time = "16:00".to_owned();
+    assert_eq!(details.parsed_last_trade_time(), Some(time!(16:00)), "seconds are optional");
+
+    details.contract.last_trade_date_or_contract_month = "202403".to_owned();
+    assert_eq!(details.last_trade_date_time(), None, "contract month has no day");
+
+    details.last_trade_time = "".to_owned();
+    assert_eq!(details.parsed_last_trade_time(), None);
+}
+
+#[test]
+fn test_contract_details_isin() {
+    let details = ContractDetails {
+        contract: Contract::stock("TSLA"),
+        sec_id_list: vec![TagValue {
+            tag: "ISIN".to_owned(),
+            value: "US88160R1014".to_owned(),
+        }],
+        ..ContractDetails::default()
+    };
+
+    assert_eq!(details.isin().unwrap(), "US88160R1014");
+    assert_eq!(details.security_id("isin"), Some("US88160R1014"));
+    assert_eq!(details.security_id("CUSIP"), None);
+}
+
+#[test]
+fn test_contract_details_isin_missing() {
+    let details = ContractDetails {
+        contract: Contract::stock("TSLA"),
+        sec_id_list: vec![],
+        ..ContractDetails::default()
+    };
+
+    match details.isin() {
+        Err(Error::Simple(message)) => assert!(message.contains("CUSIP market data subscription"), "{message}"),
+        other => panic!("expected missing ISIN diagnostic, got {other:?}"),
+    }
+
+    let details = ContractDetails {
+        contract: Contract::bond("DE0001102580", "EUR"),
+        sec_id_list: vec![],
+        ..ContractDetails::default()
+    };
+
+    match details.isin() {
+        Err(Error::Simple(message)) => assert!(!message.contains("CUSIP"), "{message}"),
+        other => panic!("expected missing ISIN diagnostic, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_index_contract() {
+    let contract = Contract::index("SPX", "CBOE");
+
+    assert_eq!(contract.symbol, "SPX");
+    assert_eq!(contract.security_type, SecurityType::Index);
+    assert_eq!(contract.exchange, "CBOE");
+    assert_eq!(contract.currency, "USD");
+}
+
+#[test]
+fn test_option_chain_filtered() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "75|9000|SMART|265598|AAPL|100|4|20230616|20230721|20231215|bad|5|100|150|200|250|300|".to_string(),
+            "76|9000|".to_string(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let subscription = client
+        .option_chain_filtered(
+            "AAPL",
+            "SMART",
+            SecurityType::Stock,
+            265598,
+            Some((date!(2023 - 07 - 01), date!(2023 - 12 - 31))),
+            Some((150.0, 250.0)),
+        )
+        .expect("request option chain failed");
+
+    let option_chain = subscription.next().expect("expected option chain");
+    assert_eq!(option_chain.expirations, vec!["20230721", "20231215"]);
+    assert_eq!(option_chain.strikes, vec![150.0, 200.0, 250.0]);
+    assert!(subscription.next().is_none());
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(request_messages[0].encode_simple(), "78|9000|AAPL|SMART|STK|265598|");
+}
+
+#[test]
+fn test_option_chain_filtered_invalid_ranges() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let result = client.option_chain_filtered(
+        "AAPL",
+        "SMART",
+        SecurityType::Stock,
+        265598,
+        Some((date!(2023 - 12 - 31), date!(2023 - 07 - 01))),
+        None,
+    );
+    assert!(matches!(result, Err(Error::InvalidArgument(_))), "{result:?}");
+
+    let result = client.option_chain_filtered("AAPL", "SMART", SecurityType::Stock, 265598, None, Some((250.0, 150.0)));
+    assert!(matches!(result, Err(Error::InvalidArgument(_))), "{result:?}");
+
+    assert!(client.message_bus.request_messages().is_empty());
+}
@@ -1,6 +1,6 @@
-use std::{num::ParseIntError, string::FromUtf8Error, sync::Arc};
+use std::{num::ParseIntError, string::FromUtf8Error, sync::Arc, time::Duration};
 
-use crate::messages::{ResponseMessage, CODE_INDEX, MESSAGE_INDEX};
+use crate::messages::{IncomingMessages, ResponseMessage, CODE_INDEX, MESSAGE_INDEX};
 
 #[derive(Debug, Clone)]
 #[non_exhaustive]
@@ -15,6 +15,12 @@ pub enum Error {
     // Errors from by IBAPI library
     NotImplemented,
     Parse(usize, String, String),
+    /// A decoder read past the last field of a message.
+    ParseError {
+        field_index: usize,
+        message_type: IncomingMessages,
+        fields_len: usize,
+    },
     ServerVersion(i32, i32, String),
     Simple(String),
     InvalidArgument(String),
@@ -25,12 +31,24 @@ pub enum Error {
     EndOfStream,
     UnexpectedResponse(ResponseMessage),
     UnexpectedEndOfStream,
+    /// Error code and message. No longer produced: TWS errors are reported as [Error::TwsError].
+    #[deprecated(note = "TWS errors are reported as Error::TwsError")]
     Message(i32, String),
+    /// An error or notice TWS sent in response to a request. See [Error::tws_code] and [Error::is_informational].
+    TwsError {
+        code: i32,
+        message: String,
+    },
+    /// A client-side rate limit was exceeded. Holds the time until the next request is allowed.
+    RateLimited(Duration),
+    /// An order request was refused because the client is read-only. See [Client::connect_read_only](crate::Client::connect_read_only).
+    ReadOnly,
 }
 
 impl std::error::Error for Error {}
 
 impl std::fmt::Display for Error {
+    #[allow(deprecated)]
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Error::Io(ref err) => err.fmt(f),
@@ -41,22 +59,71 @@ impl std::fmt::Display for Error {
 
             Error::NotImplemented => write!(f, "not implemented"),
             Error::Parse(i, value, message) => write!(f, "parse error: {i} - {value} - {message}"),
+            Error::ParseError {
+                field_index,
+                message_type,
+                fields_len,
+            } => write!(
+                f,
+                "parse error: end of message at field {field_index} of {message_type:?} with {fields_len} fields"
+            ),
             Error::ServerVersion(wanted, have, message) => write!(f, "server version {wanted} required, got {have}: {message}"),
             Error::ConnectionFailed => write!(f, "ConnectionFailed"),
             Error::ConnectionReset => write!(f, "ConnectionReset"),
             Error::Cancelled => write!(f, "Cancelled"),
             Error::Shutdown => write!(f, "Shutdown"),
             Error::EndOfStream => write!(f, "EndOfStream"),
-            Error::UnexpectedResponse(message) => write!(f, "UnexpectedResponse: {:?}", message),
+            Error::UnexpectedResponse(message) => {
+                write!(f, "UnexpectedResponse({:?}", message.message_type())?;
+                if let Some(request_id) = message.request_id() {
+                    write!(f, " req_id={request_id}")?;
+                }
+                write!(f, "): {}", message.fields.join("|"))
+            }
             Error::UnexpectedEndOfStream => write!(f, "UnexpectedEndOfStream"),
 
             Error::Simple(ref err) => write!(f, "error occurred: {err}"),
             Error::InvalidArgument(ref err) => write!(f, "InvalidArgument: {err}"),
             Error::Message(code, message) => write!(f, "[{code}] {message}"),
+            Error::TwsError { code, message } => write!(f, "[{code}] {message}"),
+            Error::RateLimited(retry_after) => write!(f, "RateLimited: retry after {retry_after:?}"),
+            Error::ReadOnly => write!(f, "ReadOnly: orders cannot be sent by a read-only client"),
+        }
+    }
+}
+
+impl Error {
+    /// Returns the TWS error code, if the error was reported by TWS.
+    #[allow(deprecated)]
+    pub fn tws_code(&self) -> Option<i32> {
+        match self {
+            Error::TwsError { code, .. } | Error::Message(code, _) => Some(*code),
+            _ => None,
+        }
+    }
+
+    /// Returns true for informational TWS notices, codes 2100 to 2199, such as 2104 "Market data farm connection is OK".
+    /// These report on connectivity and are not failures of the request.
+    pub fn is_informational(&self) -> bool {
+        self.tws_code().is_some_and(is_informational_code)
+    }
+
+    /// Returns true if TWS rejected the request for exceeding its pacing limits: codes 100 and 420, and
+    /// historical data service errors (162) reporting a pacing violation.
+    #[allow(deprecated)]
+    pub fn is_pacing_violation(&self) -> bool {
+        match self {
+            Error::TwsError { code: 162, message } | Error::Message(162, message) => message.to_lowercase().contains("pacing violation"),
+            _ => matches!(self.tws_code(), Some(100 | 420)),
         }
     }
 }
 
+// Informational TWS notices, codes 2100 to 2199, report on connectivity and do not mean a request failed.
+pub(crate) fn is_informational_code(code: i32) -> bool {
+    (2100..2200).contains(&code)
+}
+
 impl From<std::io::Error> for Error {
     fn from(err: std::io::Error) -> Error {
         Error::Io(Arc::new(err))
@@ -85,7 +152,7 @@ impl From<ResponseMessage> for Error {
     fn from(err: ResponseMessage) -> Error {
         let code = err.peek_int(CODE_INDEX).unwrap();
         let message = err.peek_string(MESSAGE_INDEX);
-        Error::Message(code, message)
+        Error::TwsError { code, message }
     }
 }
 
@@ -132,13 +199,37 @@ mod tests {
                 Error::Parse(1, "value".to_string(), "message".to_string()),
                 "parse error: 1 - value - message",
             ),
+            (
+                Error::ParseError {
+                    field_index: 4,
+                    message_type: IncomingMessages::OpenOrder,
+                    fields_len: 4,
+                },
+                "parse error: end of message at field 4 of OpenOrder with 4 fields",
+            ),
             (
                 Error::ServerVersion(2, 1, "old version".to_string()),
                 "server version 2 required, got 1: old version",
             ),
+            (
+                Error::UnexpectedResponse(ResponseMessage::from_simple("17|9000|20230413  16:31:22|20230415  16:31:22|0")),
+                "UnexpectedResponse(HistoricalData req_id=9000): 17|9000|20230413  16:31:22|20230415  16:31:22|0",
+            ),
+            (
+                Error::UnexpectedResponse(ResponseMessage::from_simple("49|1|1678323335")),
+                "UnexpectedResponse(CurrentTime): 49|1|1678323335",
+            ),
             (Error::ConnectionFailed, "ConnectionFailed"),
             (Error::Cancelled, "Cancelled"),
             (Error::Simple("simple error".to_string()), "error occurred: simple error"),
+            (
+                Error::TwsError {
+                    code: 200,
+                    message: "No security definition has been found for the request".to_string(),
+                },
+                "[200] No security definition has been found for the request",
+            ),
+            (Error::RateLimited(Duration::from_millis(250)), "RateLimited: retry after 250ms"),
         ];
 
         for (error, expected) in cases {
@@ -188,6 +279,47 @@ mod tests {
         assert!(matches!(error, Error::Poison(_)));
     }
 
+    #[test]
+    fn test_tws_error_from_notice() {
+        let cases = [
+            ("4|2|9000|200|No security definition has been found for the request|", 200, false, false),
+            ("4|2|9000|201|Order rejected - reason:Margin requirements not met|", 201, false, false),
+            ("4|2|-1|2103|Market data farm connection is broken:usfarm|", 2103, true, false),
+            ("4|2|-1|2104|Market data farm connection is OK:usfarm|", 2104, true, false),
+            ("4|2|9000|100|Max rate of messages per second has been exceeded|", 100, false, true),
+            ("4|2|9000|420|Invalid Real-time Query:Pacing violation|", 420, false, true),
+            (
+                "4|2|9000|162|Historical Market Data Service error message:Historical data request pacing violation|",
+                162,
+                false,
+                true,
+            ),
+            (
+                "4|2|9000|162|Historical Market Data Service error message:HMDS query returned no data|",
+                162,
+                false,
+                false,
+            ),
+        ];
+
+        for (notice, code, informational, pacing) in cases {
+            let error = Error::from(ResponseMessage::from_simple(notice));
+
+            assert!(matches!(&error, Error::TwsError { code: c, .. } if *c == code), "{notice}: {error:?}");
+            assert_eq!(error.tws_code(), Some(code), "{notice}: tws_code()");
+            assert_eq!(error.is_informational(), informational, "{notice}: is_informational()");
+            assert_eq!(error.is_pacing_violation(), pacing, "{notice}: is_pacing_violation()");
+        }
+
+        let error = Error::from(ResponseMessage::from_simple(
+            "4|2|9000|200|No security definition has been found for the request|",
+        ));
+        assert_eq!(error.to_string(), "[200] No security definition has been found for the request");
+
+        assert_eq!(Error::Cancelled.tws_code(), None);
+        assert!(!Error::Cancelled.is_informational());
+    }
+
     #[test]
     fn test_non_exhaustive() {
         fn assert_non_exhaustive<T: StdError>() {}
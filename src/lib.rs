@@ -125,7 +125,13 @@ impl ToField for Option<i32> {
 
 impl ToField for f64 {
     fn to_field(&self) -> String {
-        self.to_string()
+        // TWS parses doubles with Java's Double.parseDouble, which does not accept Rust's "inf".
+        if self.is_infinite() {
+            let sign = if self.is_sign_negative() { "-" } else { "" };
+            format!("{sign}{}", messages::INFINITY_STR)
+        } else {
+            self.to_string()
+        }
     }
 }
 
@@ -1,10 +1,11 @@
 use crate::{messages::OutgoingMessages, server_versions, Client, Error};
 
+pub mod builder;
 pub mod historical;
 pub mod realtime;
 
 /// By default only Realtime market data is enabled sending.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MarketDataType {
     /// Disables frozen, delayed and delayed-frozen market data sending.
     Live = 1,
@@ -93,4 +94,24 @@ mod tests {
 
         assert_eq!(request_messages[0].encode_simple(), "59|1|3|");
     }
+
+    #[test]
+    fn test_use_realtime_data() {
+        let message_bus = Arc::new(MessageBusStub {
+            request_messages: RwLock::new(vec![]),
+            response_messages: vec![],
+        });
+
+        let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+        client
+            .switch_market_data_type(MarketDataType::Frozen)
+            .expect("switch market data type failed");
+        client.use_realtime_data().expect("use realtime data failed");
+
+        let request_messages = client.message_bus.request_messages();
+
+        assert_eq!(request_messages[0].encode_simple(), "59|1|2|");
+        assert_eq!(request_messages[1].encode_simple(), "59|1|1|");
+    }
 }
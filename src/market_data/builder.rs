@@ -0,0 +1,94 @@
+use crate::client::{ResponseContext, Subscription};
+use crate::contracts::Contract;
+use crate::market_data::realtime::{encoders, GenericTick, TickTypes};
+use crate::messages::RequestMessage;
+use crate::{Client, Error};
+
+#[cfg(test)]
+mod tests;
+
+/// Builds a market data request, an alternative to the positional arguments of [Client::market_data].
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::contracts::Contract;
+/// use ibapi::market_data::builder::MarketDataRequestBuilder;
+/// use ibapi::Client;
+///
+/// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+///
+/// let subscription = MarketDataRequestBuilder::new(&Contract::stock("AAPL"))
+///     .generic_ticks(&["233", "293"])
+///     .subscribe(&client)
+///     .expect("error requesting market data");
+///
+/// for tick in &subscription {
+///     println!("{tick:?}");
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MarketDataRequestBuilder {
+    contract: Contract,
+    generic_ticks: Vec<String>,
+    snapshot: bool,
+    regulatory_snapshot: bool,
+}
+
+impl MarketDataRequestBuilder {
+    /// Starts a streaming request for the given contract, without generic ticks.
+    pub fn new(contract: &Contract) -> Self {
+        Self {
+            contract: contract.clone(),
+            generic_ticks: vec![],
+            snapshot: false,
+            regulatory_snapshot: false,
+        }
+    }
+
+    /// Requests additional tick types, e.g. "233" for RT Volume. See [Client::market_data] for the available ticks.
+    pub fn generic_ticks(mut self, generic_ticks: &[&str]) -> Self {
+        self.generic_ticks = generic_ticks.iter().map(|tick| tick.to_string()).collect();
+        self
+    }
+
+    /// Adds a generic tick to the requested ticks.
+    pub fn generic_tick(mut self, generic_tick: GenericTick) -> Self {
+        self.generic_ticks.push(generic_tick.to_string());
+        self
+    }
+
+    /// Requests a one time snapshot instead of streaming data. Requires a market data subscription for the contract.
+    pub fn snapshot(mut self) -> Self {
+        self.snapshot = true;
+        self
+    }
+
+    /// Requests a regulatory NBBO snapshot of a US stock. Requires server version 114+.
+    ///
+    /// Each regulatory snapshot costs 1 cent, charged to the account.
+    pub fn regulatory_snapshot(mut self) -> Self {
+        self.regulatory_snapshot = true;
+        self
+    }
+
+    /// Sends the request and returns the subscription to its ticks.
+    pub fn subscribe<'a>(&self, client: &'a Client) -> Result<Subscription<'a, TickTypes>, Error> {
+        let request_id = client.next_request_id();
+        let request = self.encode(client.server_version(), request_id)?;
+
+        client.subscribe(request_id, request, ResponseContext::default())
+    }
+
+    fn encode(&self, server_version: i32, request_id: i32) -> Result<RequestMessage, Error> {
+        let generic_ticks: Vec<&str> = self.generic_ticks.iter().map(String::as_str).collect();
+        encoders::encode_request_market_data(
+            server_version,
+            request_id,
+            &self.contract,
+            &generic_ticks,
+            self.snapshot,
+            self.regulatory_snapshot,
+        )
+    }
+}
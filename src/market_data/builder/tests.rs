@@ -0,0 +1,77 @@
+use std::sync::{Arc, RwLock};
+
+use crate::server_versions;
+use crate::stubs::MessageBusStub;
+
+use super::*;
+
+#[test]
+fn test_builder_matches_positional_encoder() {
+    let server_version = server_versions::SIZE_RULES;
+    let contract = Contract::stock("AAPL");
+
+    let cases: [(MarketDataRequestBuilder, &[&str], bool, bool); 4] = [
+        (MarketDataRequestBuilder::new(&contract), &[], false, false),
+        (
+            MarketDataRequestBuilder::new(&contract).generic_ticks(&["233", "293"]),
+            &["233", "293"],
+            false,
+            false,
+        ),
+        (MarketDataRequestBuilder::new(&contract).snapshot(), &[], true, false),
+        (MarketDataRequestBuilder::new(&contract).snapshot().regulatory_snapshot(), &[], true, true),
+    ];
+
+    for (builder, generic_ticks, snapshot, regulatory_snapshot) in cases {
+        let expected = encoders::encode_request_market_data(server_version, 9000, &contract, generic_ticks, snapshot, regulatory_snapshot).unwrap();
+        assert_eq!(builder.encode(server_version, 9000).unwrap().encode_simple(), expected.encode_simple());
+    }
+}
+
+#[test]
+fn test_builder_generic_tick() {
+    let builder = MarketDataRequestBuilder::new(&Contract::stock("AAPL"))
+        .generic_tick(GenericTick::RtVolume)
+        .generic_tick(GenericTick::RtTradeVolume);
+
+    let expected =
+        encoders::encode_request_market_data(server_versions::SIZE_RULES, 9000, &Contract::stock("AAPL"), &["233", "375"], false, false).unwrap();
+    assert_eq!(
+        builder.encode(server_versions::SIZE_RULES, 9000).unwrap().encode_simple(),
+        expected.encode_simple()
+    );
+}
+
+#[test]
+fn test_regulatory_snapshot_server_version() {
+    let builder = MarketDataRequestBuilder::new(&Contract::stock("AAPL")).regulatory_snapshot();
+
+    let result = builder.encode(server_versions::REQ_SMART_COMPONENTS - 1, 9000);
+    assert!(
+        matches!(result, Err(Error::ServerVersion(server_versions::REQ_SMART_COMPONENTS, _, _))),
+        "{result:?}"
+    );
+    assert!(builder.encode(server_versions::REQ_SMART_COMPONENTS, 9000).is_ok());
+}
+
+#[test]
+fn test_builder_subscribe() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["57|1|9000|".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let subscription = MarketDataRequestBuilder::new(&Contract::stock("AAPL"))
+        .generic_ticks(&["233"])
+        .snapshot()
+        .subscribe(&client)
+        .expect("request failed");
+
+    assert!(matches!(subscription.next(), Some(TickTypes::SnapshotEnd)));
+    assert_eq!(
+        client.message_bus.request_messages()[0].encode_simple(),
+        "1|11|9000|0|AAPL|STK||0|||SMART||USD|||0|233|1|0||"
+    );
+}
@@ -1,13 +1,16 @@
 use std::collections::VecDeque;
 use std::fmt::{Debug, Display};
+use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
+use std::thread;
 
 use log::{debug, warn};
 use serde::{Deserialize, Serialize};
-use time::{Date, OffsetDateTime};
+use time::{Date, OffsetDateTime, UtcOffset};
+use time_tz::{timezones, OffsetDateTimeExt};
 
-use crate::contracts::Contract;
+use crate::contracts::{Contract, SecurityType};
 use crate::messages::{IncomingMessages, RequestMessage, ResponseMessage};
 use crate::transport::{InternalSubscription, Response};
 use crate::{server_versions, Client, Error, ToField, MAX_RETRIES};
@@ -31,11 +34,11 @@ pub struct Bar {
     pub low: f64,
     /// The bar's close price.
     pub close: f64,
-    /// The bar's traded volume if available (only available for TRADES)
+    /// The bar's traded volume if available (only available for TRADES). -1 when not available, e.g. for indexes.
     pub volume: f64,
-    /// The bar's Weighted Average Price (only available for TRADES)
+    /// The bar's Weighted Average Price (only available for TRADES). -1 when not available, e.g. for indexes.
     pub wap: f64,
-    /// The number of trades during the bar's timespan (only available for TRADES)
+    /// The number of trades during the bar's timespan (only available for TRADES). -1 when not available, e.g. for indexes.
     pub count: i32,
 }
 
@@ -94,6 +97,35 @@ impl ToField for BarSize {
     }
 }
 
+impl BarSize {
+    // Length of a bar in seconds. Months are counted as 30 days.
+    fn seconds(&self) -> i64 {
+        match self {
+            Self::Sec => 1,
+            Self::Sec5 => 5,
+            Self::Sec15 => 15,
+            Self::Sec30 => 30,
+            Self::Min => 60,
+            Self::Min2 => 2 * 60,
+            Self::Min3 => 3 * 60,
+            Self::Min5 => 5 * 60,
+            Self::Min15 => 15 * 60,
+            Self::Min20 => 20 * 60,
+            Self::Min30 => 30 * 60,
+            Self::Hour => 3600,
+            Self::Hour2 => 2 * 3600,
+            Self::Hour3 => 3 * 3600,
+            Self::Hour4 => 4 * 3600,
+            Self::Hour8 => 8 * 3600,
+            Self::Day => DAY_SECONDS,
+            Self::Week => 7 * DAY_SECONDS,
+            Self::Month => 30 * DAY_SECONDS,
+        }
+    }
+}
+
+const DAY_SECONDS: i64 = 24 * 3600;
+
 #[derive(Clone, Debug, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Duration {
     value: i32,
@@ -126,6 +158,91 @@ impl Duration {
     pub const fn years(years: i32) -> Self {
         Self { value: years, unit: 'Y' }
     }
+
+    /// Checks the duration against the bar sizes IB allows for it.
+    ///
+    /// IB rejects requests with too many bars, such as a year of 1 second bars, and bars longer than the duration.
+    /// The smallest bar allowed grows with the duration, e.g. 1 min for 1 D, 3 mins for 1 W and 1 day beyond 1 M.
+    pub fn validate_for(&self, bar_size: BarSize) -> Result<(), Error> {
+        if self.value < 1 {
+            return Err(Error::InvalidArgument(format!("duration must be positive: {self}")));
+        }
+
+        let seconds = self.length_in_seconds();
+
+        // Smallest bar size per duration, from IB's table of valid duration and bar size settings.
+        let smallest = match seconds {
+            s if s <= 1800 => BarSize::Sec,
+            s if s <= 3600 => BarSize::Sec5,
+            s if s <= 4 * 3600 => BarSize::Sec15,
+            s if s <= 8 * 3600 => BarSize::Sec30,
+            s if s <= DAY_SECONDS => BarSize::Min,
+            s if s <= 2 * DAY_SECONDS => BarSize::Min2,
+            s if s <= 7 * DAY_SECONDS => BarSize::Min3,
+            s if s <= 30 * DAY_SECONDS => BarSize::Min30,
+            _ => BarSize::Day,
+        };
+
+        if bar_size.seconds() < smallest.seconds() {
+            return Err(Error::InvalidArgument(format!(
+                "bar size {bar_size} is too small for duration {self}, the smallest allowed is {smallest}"
+            )));
+        }
+
+        if bar_size.seconds() > seconds {
+            return Err(Error::InvalidArgument(format!("bar size {bar_size} is longer than duration {self}")));
+        }
+
+        Ok(())
+    }
+
+    // Length of the duration in seconds. Months are counted as 30 days and years as 365.
+    fn length_in_seconds(&self) -> i64 {
+        let unit = match self.unit {
+            'S' => 1,
+            'D' => DAY_SECONDS,
+            'W' => 7 * DAY_SECONDS,
+            'M' => 30 * DAY_SECONDS,
+            _ => 365 * DAY_SECONDS,
+        };
+        self.value as i64 * unit
+    }
+}
+
+impl FromStr for Duration {
+    type Err = Error;
+
+    /// Parses a duration in TWS format, e.g. "1 D", or without the space, e.g. "1D".
+    ///
+    /// Units are S (seconds), D (days), W (weeks), M (months) and Y (years).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let (value, unit) = (&s[..split], s[split..].trim());
+
+        let value: i32 = value
+            .parse()
+            .map_err(|_| Error::InvalidArgument(format!("invalid duration value: {s:?}")))?;
+
+        let unit = match unit {
+            "S" | "D" | "W" | "M" | "Y" => unit.chars().next().unwrap(),
+            _ => {
+                return Err(Error::InvalidArgument(format!(
+                    "invalid duration unit in {s:?}, expected S, D, W, M or Y"
+                )))
+            }
+        };
+
+        Ok(Duration { value, unit })
+    }
+}
+
+impl TryFrom<&str> for Duration {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
 }
 
 impl Display for Duration {
@@ -177,11 +294,114 @@ pub struct HistogramEntry {
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct HistoricalData {
+    /// Start of the returned period, in the time zone of the TWS or IB Gateway connection. See [HistoricalData::start_utc].
     pub start: OffsetDateTime,
+    /// End of the returned period, in the time zone of the TWS or IB Gateway connection. See [HistoricalData::end_utc].
     pub end: OffsetDateTime,
     pub bars: Vec<Bar>,
 }
 
+impl HistoricalData {
+    /// [start](HistoricalData::start) converted from the connection time zone to UTC.
+    pub fn start_utc(&self) -> OffsetDateTime {
+        self.start.to_offset(UtcOffset::UTC)
+    }
+
+    /// [end](HistoricalData::end) converted from the connection time zone to UTC.
+    pub fn end_utc(&self) -> OffsetDateTime {
+        self.end.to_offset(UtcOffset::UTC)
+    }
+
+    /// Simple moving average of bar closes.
+    ///
+    /// Returns one entry per bar. Entries are `None` until `period` bars are available.
+    ///
+    /// # Arguments
+    /// * `period` - number of bars in the averaging window.
+    pub fn sma(&self, period: usize) -> Vec<Option<f64>> {
+        let mut averages = vec![None; self.bars.len()];
+        if period == 0 {
+            return averages;
+        }
+
+        let mut sum = 0.0;
+        for (i, bar) in self.bars.iter().enumerate() {
+            sum += bar.close;
+            if i >= period {
+                sum -= self.bars[i - period].close;
+            }
+            if i + 1 >= period {
+                averages[i] = Some(sum / period as f64);
+            }
+        }
+
+        averages
+    }
+
+    /// Exponential moving average of bar closes.
+    ///
+    /// The average is seeded with the simple moving average of the first `period` closes
+    /// and then smoothed with a factor of `2 / (period + 1)`. Entries are `None` until `period` bars are available.
+    ///
+    /// # Arguments
+    /// * `period` - number of bars in the averaging window.
+    pub fn ema(&self, period: usize) -> Vec<Option<f64>> {
+        let mut averages = vec![None; self.bars.len()];
+        if period == 0 || self.bars.len() < period {
+            return averages;
+        }
+
+        let alpha = 2.0 / (period as f64 + 1.0);
+
+        let seed = self.bars[..period].iter().map(|bar| bar.close).sum::<f64>() / period as f64;
+        averages[period - 1] = Some(seed);
+
+        let mut previous = seed;
+        for (i, bar) in self.bars.iter().enumerate().skip(period) {
+            previous = alpha * bar.close + (1.0 - alpha) * previous;
+            averages[i] = Some(previous);
+        }
+
+        averages
+    }
+
+    /// Checks that at least `expected_min_bars` bars were returned.
+    ///
+    /// TWS may return partial data, e.g. when the requested range extends past available history.
+    /// Returns a [BarCountShortfall] and logs a warning when fewer bars than expected were received.
+    ///
+    /// # Arguments
+    /// * `expected_min_bars` - minimum number of bars expected for the requested duration and bar size.
+    pub fn bar_count_shortfall(&self, expected_min_bars: usize) -> Option<BarCountShortfall> {
+        if self.bars.len() >= expected_min_bars {
+            return None;
+        }
+
+        let shortfall = BarCountShortfall {
+            expected_min_bars,
+            actual_bars: self.bars.len(),
+        };
+        warn!("historical data from {} to {}: {shortfall}", self.start, self.end);
+
+        Some(shortfall)
+    }
+}
+
+/// Diagnostic for a [HistoricalData] response with fewer bars than expected.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct BarCountShortfall {
+    /// Minimum number of bars expected.
+    pub expected_min_bars: usize,
+    /// Number of bars received.
+    pub actual_bars: usize,
+}
+
+impl Display for BarCountShortfall {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "received {} bars, expected at least {}", self.actual_bars, self.expected_min_bars)
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Schedule {
     pub start: OffsetDateTime,
@@ -190,6 +410,18 @@ pub struct Schedule {
     pub sessions: Vec<Session>,
 }
 
+impl Schedule {
+    /// Number of trading sessions in the schedule.
+    pub fn session_count(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Sum of the lengths of all trading sessions in the schedule.
+    pub fn total_trading_hours(&self) -> time::Duration {
+        self.sessions.iter().map(|session| session.end - session.start).sum()
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub struct Session {
     pub reference: Date,
@@ -200,7 +432,7 @@ pub struct Session {
 /// The historical tick's description. Used when requesting historical tick data with whatToShow = MIDPOINT
 #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub struct TickMidpoint {
-    /// timestamp of the historical tick.
+    /// timestamp of the historical tick, in UTC.
     pub timestamp: OffsetDateTime,
     /// historical tick price.
     pub price: f64,
@@ -211,7 +443,7 @@ pub struct TickMidpoint {
 /// The historical tick's description. Used when requesting historical tick data with whatToShow = BID_ASK.
 #[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub struct TickBidAsk {
-    /// Timestamp of the historical tick.
+    /// Timestamp of the historical tick, in UTC.
     pub timestamp: OffsetDateTime,
     /// Tick attributes of historical bid/ask tick.
     pub tick_attribute_bid_ask: TickAttributeBidAsk,
@@ -234,7 +466,7 @@ pub struct TickAttributeBidAsk {
 /// The historical last tick's description. Used when requesting historical tick data with whatToShow = TRADES.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct TickLast {
-    /// Timestamp of the historical tick.
+    /// Timestamp of the historical tick, in UTC.
     pub timestamp: OffsetDateTime,
     /// Tick attributes of historical bid/ask tick.
     pub tick_attribute_last: TickAttributeLast,
@@ -248,6 +480,35 @@ pub struct TickLast {
     pub special_conditions: String,
 }
 
+impl TickMidpoint {
+    /// Timestamp of the tick converted from UTC to the given time zone, e.g. [ContractDetails::time_zone_id](crate::contracts::ContractDetails::time_zone_id).
+    pub fn timestamp_in(&self, time_zone_id: &str) -> Result<OffsetDateTime, Error> {
+        to_time_zone(self.timestamp, time_zone_id)
+    }
+}
+
+impl TickBidAsk {
+    /// Timestamp of the tick converted from UTC to the given time zone, e.g. [ContractDetails::time_zone_id](crate::contracts::ContractDetails::time_zone_id).
+    pub fn timestamp_in(&self, time_zone_id: &str) -> Result<OffsetDateTime, Error> {
+        to_time_zone(self.timestamp, time_zone_id)
+    }
+}
+
+impl TickLast {
+    /// Timestamp of the tick converted from UTC to the given time zone, e.g. [ContractDetails::time_zone_id](crate::contracts::ContractDetails::time_zone_id).
+    pub fn timestamp_in(&self, time_zone_id: &str) -> Result<OffsetDateTime, Error> {
+        to_time_zone(self.timestamp, time_zone_id)
+    }
+}
+
+// Converts a UTC timestamp to the named time zone.
+fn to_time_zone(timestamp: OffsetDateTime, time_zone_id: &str) -> Result<OffsetDateTime, Error> {
+    match timezones::get_by_name(time_zone_id) {
+        Some(time_zone) => Ok(timestamp.to_timezone(time_zone)),
+        None => Err(Error::Simple(format!("unknown time zone: {time_zone_id}"))),
+    }
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Copy)]
 pub struct TickAttributeLast {
     pub past_limit: bool,
@@ -317,6 +578,43 @@ pub(crate) fn head_timestamp(client: &Client, contract: &Contract, what_to_show:
     }
 }
 
+// Requests OPTION_IMPLIED_VOLATILITY bars, which carry the implied volatility in their close.
+pub(crate) fn historical_implied_volatility(
+    client: &Client,
+    contract: &Contract,
+    end_date: Option<OffsetDateTime>,
+    duration: Duration,
+    bar_size: BarSize,
+    use_rth: bool,
+) -> Result<Vec<(OffsetDateTime, f64)>, Error> {
+    if !matches!(
+        contract.security_type,
+        SecurityType::Stock
+            | SecurityType::Index
+            | SecurityType::Future
+            | SecurityType::ContinuousFuture
+            | SecurityType::Option
+            | SecurityType::FuturesOption
+    ) {
+        return Err(Error::InvalidArgument(format!(
+            "implied volatility is only available for options and their underlyings, not {}",
+            contract.security_type
+        )));
+    }
+
+    let historical_data = historical_data(
+        client,
+        contract,
+        end_date,
+        duration,
+        bar_size,
+        Some(WhatToShow::OptionImpliedVolatility),
+        use_rth,
+    )?;
+
+    Ok(historical_data.bars.iter().map(|bar| (bar.date, bar.close)).collect())
+}
+
 // https://interactivebrokers.github.io/tws-api/historical_bars.html#hd_duration
 pub(crate) fn historical_data(
     client: &Client,
@@ -347,7 +645,10 @@ pub(crate) fn historical_data(
         ));
     }
 
-    for _ in 0..MAX_RETRIES {
+    let mut last_error = Error::ConnectionReset;
+    for attempt in 0..MAX_RETRIES {
+        client.pace_historical_data()?;
+
         let request_id = client.next_request_id();
         let request = encoders::encode_request_historical_data(
             client.server_version(),
@@ -368,15 +669,30 @@ pub(crate) fn historical_data(
             Some(Ok(mut message)) if message.message_type() == IncomingMessages::HistoricalData => {
                 return decoders::decode_historical_data(client.server_version, time_zone(client), &mut message)
             }
-            Some(Ok(message)) if message.message_type() == IncomingMessages::Error => return Err(Error::from(message)),
+            Some(Ok(message)) if message.message_type() == IncomingMessages::Error => {
+                let error = Error::from(message);
+
+                // Pacing violations are only retried when requests are being throttled.
+                match client.historical_data_interval() {
+                    Some(interval) if error.is_pacing_violation() => {
+                        if attempt + 1 < MAX_RETRIES {
+                            let backoff = interval * 2_u32.pow(attempt as u32);
+                            warn!("historical data pacing violation, retrying in {backoff:?}: {error}");
+                            thread::sleep(backoff);
+                        }
+                        last_error = error;
+                    }
+                    _ => return Err(error),
+                }
+            }
             Some(Ok(message)) => return Err(Error::UnexpectedResponse(message)),
-            Some(Err(Error::ConnectionReset)) => continue,
+            Some(Err(Error::ConnectionReset)) => last_error = Error::ConnectionReset,
             Some(Err(e)) => return Err(e),
             None => return Err(Error::UnexpectedEndOfStream),
         }
     }
 
-    Err(Error::ConnectionReset)
+    Err(last_error)
 }
 
 fn time_zone(client: &Client) -> &time_tz::Tz {
@@ -499,6 +815,12 @@ pub(crate) fn historical_ticks_trade(
 pub(crate) fn histogram_data(client: &Client, contract: &Contract, use_rth: bool, period: BarSize) -> Result<Vec<HistogramEntry>, Error> {
     client.check_server_version(server_versions::REQ_HISTOGRAM, "It does not support histogram data requests.")?;
 
+    if !matches!(period, BarSize::Day | BarSize::Week | BarSize::Month) {
+        return Err(Error::InvalidArgument(format!(
+            "histogram period must be 1 day, 1 week or 1 month, got {period}"
+        )));
+    }
+
     loop {
         let request_id = client.next_request_id();
         let request = encoders::encode_request_histogram_data(request_id, contract, use_rth, period)?;
@@ -574,6 +896,18 @@ impl<T: TickDecoder<T>> TickSubscription<T> {
         }
     }
 
+    /// Returns true until TWS reports that the final batch of ticks was sent.
+    ///
+    /// Buffered ticks from the final batch may still be returned by [next](TickSubscription::next) after this returns false.
+    pub fn has_more(&self) -> bool {
+        !self.done.load(Ordering::Relaxed)
+    }
+
+    /// Stops receiving ticks. TWS has no cancel request for historical ticks, so any remaining batches are discarded.
+    pub fn cancel(self) {
+        debug!("historical ticks subscription cancelled, has_more: {}", self.has_more());
+    }
+
     pub fn next(&self) -> Option<T> {
         self.next_helper(|| self.messages.next())
     }
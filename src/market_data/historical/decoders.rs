@@ -45,8 +45,9 @@ pub(super) fn decode_historical_data(server_version: i32, time_zone: &Tz, messag
         let high = message.next_double()?;
         let low = message.next_double()?;
         let close = message.next_double()?;
-        let volume = message.next_double()?;
-        let wap = message.next_double()?;
+        // Bars without trades, such as those of indexes, may leave volume, wap and count empty.
+        let volume = message.next_optional_double()?.unwrap_or(-1.0);
+        let wap = message.next_optional_double()?.unwrap_or(-1.0);
 
         if server_version < server_versions::SYNT_REALTIME_BARS {
             // hasGaps
@@ -55,7 +56,7 @@ pub(super) fn decode_historical_data(server_version: i32, time_zone: &Tz, messag
 
         let mut bar_count = -1;
         if message_version >= 3 {
-            bar_count = message.next_int()?;
+            bar_count = message.next_optional_int()?.unwrap_or(-1);
         }
 
         bars.push(Bar {
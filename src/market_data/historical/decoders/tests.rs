@@ -66,6 +66,13 @@ fn test_decode_historical_data() {
         datetime!(2023-04-15 16:31:22).assume_timezone(time_zone).unwrap(),
         "historical_data.end"
     );
+    assert_eq!(
+        historical_data.start_utc(),
+        datetime!(2023-04-13 20:31:22 UTC),
+        "historical_data.start_utc()"
+    );
+    assert_eq!(historical_data.end_utc(), datetime!(2023-04-15 20:31:22 UTC), "historical_data.end_utc()");
+    assert_eq!(historical_data.start_utc().offset(), UtcOffset::UTC, "start_utc() offset");
 
     assert_eq!(historical_data.bars.len(), 2, "historical_data.bars.len()");
     assert_eq!(
@@ -82,6 +89,25 @@ fn test_decode_historical_data() {
     assert_eq!(historical_data.bars[0].count, 324891, "historical_data.bars[0].count");
 }
 
+#[test]
+fn test_decode_historical_data_index_bars() {
+    let mut message = ResponseMessage::from("17\09000\020230413  16:31:22\020230415  16:31:22\02\020230413\04105.0200\04133.1300\04086.8700\04124.5100\0-1\0-1\0-1\020230414\04129.6300\04163.1900\04113.2000\04137.6400\0\0\0\0");
+
+    let server_version = server_versions::HISTORICAL_SCHEDULE;
+    let time_zone: &Tz = time_tz::timezones::db::america::NEW_YORK;
+
+    let historical_data = decode_historical_data(server_version, time_zone, &mut message).expect("error decoding index bars");
+
+    assert_eq!(historical_data.bars.len(), 2, "historical_data.bars.len()");
+    for bar in &historical_data.bars {
+        assert_eq!(bar.volume, -1.0, "bar.volume");
+        assert_eq!(bar.wap, -1.0, "bar.wap");
+        assert_eq!(bar.count, -1, "bar.count");
+    }
+    assert_eq!(historical_data.bars[0].close, 4124.51, "historical_data.bars[0].close");
+    assert_eq!(historical_data.bars[1].close, 4137.64, "historical_data.bars[1].close");
+}
+
 #[test]
 fn test_decode_historical_tick_bid_ask() {
     let sample_message = "97\09000\04\01681133399\00\011.63\011.83\02800\0100\01681133400\00\011.63\011.83\02800\0200\01681133400\00\011.63\011.72\02800\0100\01681133400\00\011.63\011.83\02800\0200\01\0";
@@ -178,3 +204,34 @@ fn test_decode_historical_tick_midpoint() {
     assert_eq!(ticks[23].price, 91.31, "ticks[0].price");
     assert_eq!(ticks[23].size, 0, "ticks[0].size");
 }
+
+#[test]
+fn test_decode_historical_tick_timestamp_utc() {
+    let mut message = ResponseMessage::from("96\09000\01\01681133398\00\091.36\00\01\0");
+
+    let (ticks, _) = decode_historical_ticks_mid_point(&mut message).unwrap();
+
+    let timestamp = ticks[0].timestamp;
+    assert_eq!(timestamp.unix_timestamp(), 1681133398, "unix timestamp");
+    assert!(timestamp.offset().is_utc(), "offset should be UTC: {}", timestamp.offset());
+    assert_eq!(timestamp, datetime!(2023-04-10 13:29:58 UTC), "timestamp");
+
+    let eastern = ticks[0].timestamp_in("US/Eastern").unwrap();
+    assert_eq!(eastern, datetime!(2023-04-10 09:29:58 -4), "US/Eastern timestamp");
+    assert_eq!(eastern.offset().whole_hours(), -4, "US/Eastern offset");
+    assert_eq!(eastern, timestamp, "conversion should not change the instant");
+
+    assert!(ticks[0].timestamp_in("Mars/Olympus_Mons").is_err(), "unknown time zone");
+}
+
+#[test]
+fn test_decode_histogram_data() {
+    let mut message = ResponseMessage::from("89\09000\03\0125.50\01200\0125.75\0800\0126.00\0350\0");
+
+    let histogram = super::decode_histogram_data(&mut message).expect("error decoding histogram data");
+
+    assert_eq!(histogram.len(), 3, "histogram.len()");
+    assert_eq!(histogram[0], HistogramEntry { price: 125.50, size: 1200 }, "histogram[0]");
+    assert_eq!(histogram[1], HistogramEntry { price: 125.75, size: 800 }, "histogram[1]");
+    assert_eq!(histogram[2], HistogramEntry { price: 126.00, size: 350 }, "histogram[2]");
+}
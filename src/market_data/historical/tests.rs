@@ -2,6 +2,7 @@ use std::sync::{Arc, RwLock};
 
 use time::macros::datetime;
 
+use crate::client::RateLimit;
 use crate::market_data::historical::ToDuration;
 use crate::messages::OutgoingMessages;
 use crate::stubs::MessageBusStub;
@@ -64,8 +65,83 @@ fn test_head_timestamp() {
 
 #[test]
 fn test_histogram_data() {
-    let result = 2 + 2;
-    assert_eq!(result, 4);
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["89\09000\02\0185.50\0100\0186.00\0250\0".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let contract = Contract::stock("GM");
+    let histogram = client.histogram_data(&contract, true, BarSize::Week).expect("histogram request failed");
+
+    assert_eq!(
+        histogram,
+        vec![HistogramEntry { price: 185.50, size: 100 }, HistogramEntry { price: 186.00, size: 250 },]
+    );
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(request_messages.len(), 1);
+    assert_eq!(
+        request_messages[0][0],
+        OutgoingMessages::RequestHistogramData.to_field(),
+        "message.message_type"
+    );
+    assert_eq!(request_messages[0][request_messages[0].len() - 1], "1 week", "message.period");
+}
+
+#[test]
+fn test_histogram_data_rejects_unsupported_period() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let contract = Contract::stock("GM");
+    for period in [BarSize::Hour, BarSize::Min5, BarSize::Sec30] {
+        let result = client.histogram_data(&contract, true, period);
+        assert!(matches!(result, Err(Error::InvalidArgument(_))), "{period}: {result:?}");
+    }
+
+    assert!(client.message_bus.request_messages().is_empty());
+}
+
+#[test]
+fn test_historical_implied_volatility() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "17\09000\020230413  16:31:22\020230415  16:31:22\02\020230413\00.2611\00.2725\00.2580\00.2698\0-1\00.2650\0-1\020230414\00.2701\00.2733\00.2512\00.2534\0-1\00.2620\0-1\0".to_owned()
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let series = client
+        .historical_implied_volatility(&Contract::stock("MSFT"), None, 2.days(), BarSize::Day, true)
+        .expect("implied volatility request failed");
+
+    assert_eq!(
+        series,
+        vec![(datetime!(2023-04-13 00:00:00 UTC), 0.2698), (datetime!(2023-04-14 00:00:00 UTC), 0.2534)]
+    );
+
+    let request_messages = client.message_bus.request_messages();
+    assert!(
+        request_messages[0].encode_simple().contains("|OPTION_IMPLIED_VOLATILITY|"),
+        "request: {}",
+        request_messages[0].encode_simple()
+    );
+
+    let forex = Contract {
+        security_type: SecurityType::ForexPair,
+        ..Contract::stock("EUR")
+    };
+    let result = client.historical_implied_volatility(&forex, None, 2.days(), BarSize::Day, true);
+    assert!(matches!(result, Err(Error::InvalidArgument(_))), "{result:?}");
+    assert_eq!(client.message_bus.request_messages().len(), 1, "rejected request must not be sent");
 }
 
 #[test]
@@ -147,6 +223,69 @@ fn test_historical_data() {
     assert_eq!(head_timestamp_request[22], "", "message.chart_options");
 }
 
+#[test]
+fn test_historical_data_pacing_violation_retry() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["4|2|9000|162|Historical Market Data Service error message:Historical data request pacing violation|".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    let contract = Contract::stock("MSFT");
+
+    // Without throttling the pacing error is returned as is.
+    let result = client.historical_data(&contract, None, 2.days(), BarSize::Hour, WhatToShow::Trades, true);
+    assert!(matches!(&result, Err(e) if e.is_pacing_violation()), "{result:?}");
+    assert_eq!(client.message_bus.request_messages().len(), 1, "request should not be retried");
+
+    client.set_historical_data_limit(Some(RateLimit {
+        burst: 10,
+        interval: std::time::Duration::from_millis(1),
+    }));
+
+    let result = client.historical_data(&contract, None, 2.days(), BarSize::Hour, WhatToShow::Trades, true);
+    assert!(matches!(&result, Err(e) if e.is_pacing_violation()), "{result:?}");
+    assert_eq!(
+        client.message_bus.request_messages().len(),
+        1 + MAX_RETRIES as usize,
+        "request should be retried a bounded number of times"
+    );
+}
+
+#[test]
+fn test_historical_data_mutual_fund_nav() {
+    // Funds report a single NAV per day, with open, high, low and close all equal and no volume.
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "17|9000|20240102  16:00:00|20240105  16:00:00|3|20240102|437.1200|437.1200|437.1200|437.1200|-1|-1|-1|20240103|434.5300|434.5300|434.5300|434.5300|-1|-1|-1|20240104|433.0100|433.0100|433.0100|433.0100|-1|-1|-1|".replace('|', "\0")
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let contract = Contract::mutual_fund("VFIAX");
+
+    let historical_data = client
+        .historical_data(&contract, None, 3.days(), BarSize::Day, WhatToShow::Trades, true)
+        .expect("historical data request failed");
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(request_messages[0][4], "FUND", "message.security_type");
+    assert_eq!(request_messages[0][9], "FUNDSERV", "message.exchange");
+
+    let navs: Vec<f64> = historical_data.bars.iter().map(|bar| bar.close).collect();
+    assert_eq!(navs, vec![437.12, 434.53, 433.01], "bar.close");
+
+    let bar = &historical_data.bars[0];
+    assert_eq!(bar.date, datetime!(2024-01-02 00:00:00 UTC), "bar.date");
+    assert_eq!(bar.open, bar.close, "bar.open");
+    assert_eq!(bar.high, bar.close, "bar.high");
+    assert_eq!(bar.low, bar.close, "bar.low");
+    assert_eq!(bar.volume, -1.0, "bar.volume");
+    assert_eq!(bar.count, -1, "bar.count");
+}
+
 #[test]
 fn test_bar_size() {
     assert_eq!(BarSize::Sec.to_string(), "1 sec");
@@ -191,3 +330,219 @@ fn test_duration() {
     assert_eq!(5.months().to_field(), "5 M");
     assert_eq!(6.years().to_field(), "6 Y");
 }
+
+#[test]
+fn test_duration_from_str() {
+    assert_eq!("1 D".parse::<Duration>().unwrap(), Duration::DAY);
+    assert_eq!("1D".parse::<Duration>().unwrap(), Duration::DAY);
+    assert_eq!("30S".parse::<Duration>().unwrap(), 30.seconds());
+    assert_eq!(" 2  W ".parse::<Duration>().unwrap(), 2.weeks());
+    assert_eq!(Duration::try_from("6M").unwrap(), 6.months());
+    assert_eq!(Duration::try_from("10 Y").unwrap(), 10.years());
+
+    for invalid in ["", "D", "1", "1 X", "1 DD", "-1 D", "1.5 D"] {
+        assert!(
+            matches!(invalid.parse::<Duration>(), Err(Error::InvalidArgument(_))),
+            "{invalid:?} should not parse"
+        );
+    }
+}
+
+#[test]
+fn test_duration_validate_for() {
+    assert!(Duration::DAY.validate_for(BarSize::Min).is_ok());
+    assert!(Duration::DAY.validate_for(BarSize::Day).is_ok());
+    assert!(1800.seconds().validate_for(BarSize::Sec).is_ok());
+    assert!(Duration::WEEK.validate_for(BarSize::Min3).is_ok());
+    assert!(Duration::YEAR.validate_for(BarSize::Day).is_ok());
+    assert!(Duration::YEAR.validate_for(BarSize::Month).is_ok());
+
+    let invalid = [
+        ("years of 1 second bars", Duration::YEAR, BarSize::Sec),
+        ("a day of 30 second bars", Duration::DAY, BarSize::Sec30),
+        ("a month of 5 minute bars", Duration::MONTH, BarSize::Min5),
+        ("bars longer than the duration", 60.seconds(), BarSize::Hour),
+        ("zero duration", 0.days(), BarSize::Day),
+    ];
+
+    for (case, duration, bar_size) in invalid {
+        assert!(
+            matches!(duration.validate_for(bar_size), Err(Error::InvalidArgument(_))),
+            "{case} should be rejected"
+        );
+    }
+}
+
+fn historical_data_with_closes(closes: &[f64]) -> HistoricalData {
+    let start = datetime!(2023-04-10 0:00 UTC);
+    let bars = closes
+        .iter()
+        .enumerate()
+        .map(|(i, close)| Bar {
+            date: start + time::Duration::days(i as i64),
+            open: *close,
+            high: *close,
+            low: *close,
+            close: *close,
+            volume: 0.0,
+            wap: 0.0,
+            count: 0,
+        })
+        .collect::<Vec<Bar>>();
+
+    HistoricalData {
+        start,
+        end: start + time::Duration::days(closes.len() as i64),
+        bars,
+    }
+}
+
+#[test]
+fn test_sma() {
+    let historical_data = historical_data_with_closes(&[10.0, 11.0, 12.0, 13.0, 12.0, 14.0]);
+
+    let sma = historical_data.sma(3);
+    assert_eq!(sma.len(), 6, "sma.len()");
+    assert_eq!(sma[0], None, "sma[0]");
+    assert_eq!(sma[1], None, "sma[1]");
+    assert_eq!(sma[2], Some(11.0), "sma[2]");
+    assert_eq!(sma[3], Some(12.0), "sma[3]");
+    assert!((sma[4].unwrap() - 37.0 / 3.0).abs() < 1e-9, "sma[4]");
+    assert_eq!(sma[5], Some(13.0), "sma[5]");
+
+    assert_eq!(historical_data.sma(0), vec![None; 6], "sma(0)");
+    assert_eq!(historical_data.sma(7), vec![None; 6], "sma(7)");
+}
+
+#[test]
+fn test_ema() {
+    let historical_data = historical_data_with_closes(&[10.0, 11.0, 12.0, 13.0, 12.0, 14.0]);
+
+    let ema = historical_data.ema(3);
+    assert_eq!(ema, vec![None, None, Some(11.0), Some(12.0), Some(12.0), Some(13.0)], "ema(3)");
+
+    assert_eq!(
+        historical_data.ema(1),
+        vec![Some(10.0), Some(11.0), Some(12.0), Some(13.0), Some(12.0), Some(14.0)],
+        "ema(1)"
+    );
+    assert_eq!(historical_data.ema(0), vec![None; 6], "ema(0)");
+    assert_eq!(historical_data.ema(7), vec![None; 6], "ema(7)");
+}
+
+#[test]
+fn test_bar_count_shortfall() {
+    let historical_data = historical_data_with_closes(&[10.0, 11.0, 12.0]);
+
+    let shortfall = historical_data
+        .bar_count_shortfall(5)
+        .expect("expected shortfall for under-filled response");
+    assert_eq!(
+        shortfall,
+        BarCountShortfall {
+            expected_min_bars: 5,
+            actual_bars: 3
+        }
+    );
+    assert_eq!(shortfall.to_string(), "received 3 bars, expected at least 5");
+
+    assert_eq!(historical_data.bar_count_shortfall(3), None, "exact count");
+    assert_eq!(historical_data.bar_count_shortfall(0), None, "no minimum");
+}
+
+#[test]
+fn test_schedule_totals() {
+    let schedule = Schedule {
+        start: datetime!(2023-03-13 4:00 -4),
+        end: datetime!(2023-03-15 20:00 -4),
+        time_zone: "US/Eastern".to_owned(),
+        sessions: vec![
+            Session {
+                reference: time::macros::date!(2023 - 03 - 13),
+                start: datetime!(2023-03-13 9:30 -4),
+                end: datetime!(2023-03-13 16:00 -4),
+            },
+            Session {
+                reference: time::macros::date!(2023 - 03 - 14),
+                start: datetime!(2023-03-14 9:30 -4),
+                end: datetime!(2023-03-14 16:00 -4),
+            },
+            Session {
+                reference: time::macros::date!(2023 - 03 - 15),
+                start: datetime!(2023-03-15 9:30 -4),
+                end: datetime!(2023-03-15 13:00 -4),
+            },
+        ],
+    };
+
+    assert_eq!(schedule.session_count(), 3);
+    assert_eq!(schedule.total_trading_hours(), time::Duration::minutes(390 * 2 + 210));
+
+    let empty = Schedule {
+        sessions: vec![],
+        ..schedule
+    };
+    assert_eq!(empty.session_count(), 0);
+    assert_eq!(empty.total_trading_hours(), time::Duration::ZERO);
+}
+
+#[test]
+fn test_historical_ticks_has_more() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "96|9000|2|1681133400||91.36|0|1681133401||91.37|0|0|".to_owned(),
+            "96|9000|1|1681133402||91.38|0|1|".to_owned(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    let contract = Contract::stock("MSFT");
+
+    let subscription = client
+        .historical_ticks_mid_point(&contract, Some(datetime!(2023-04-10 13:30 UTC)), None, 3, true)
+        .expect("historical ticks request failed");
+
+    assert!(subscription.has_more(), "nothing received yet");
+
+    let first = subscription.next().expect("expected first tick");
+    assert_eq!(first.price, 91.36);
+    assert!(subscription.has_more(), "first batch is not final");
+
+    assert_eq!(subscription.next().expect("expected second tick").price, 91.37);
+    assert!(subscription.has_more(), "first batch is not final");
+
+    assert_eq!(subscription.next().expect("expected third tick").price, 91.38);
+    assert!(!subscription.has_more(), "second batch is final");
+
+    assert!(subscription.next().is_none(), "stream should end after final batch");
+}
+
+#[test]
+fn test_historical_ticks_cancel() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "96|9000|1|1681133400||91.36|0|0|".to_owned(),
+            "96|9000|1|1681133402||91.38|0|1|".to_owned(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    let contract = Contract::stock("MSFT");
+
+    let subscription = client
+        .historical_ticks_mid_point(&contract, Some(datetime!(2023-04-10 13:30 UTC)), None, 2, true)
+        .expect("historical ticks request failed");
+
+    assert!(subscription.next().is_some(), "expected first tick");
+    assert!(subscription.has_more(), "more ticks available");
+
+    subscription.cancel();
+
+    assert_eq!(
+        client.message_bus.request_messages().len(),
+        1,
+        "no cancel message is sent for historical ticks"
+    );
+}
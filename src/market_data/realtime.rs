@@ -1,11 +1,14 @@
-use log::debug;
+use log::{debug, warn};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
 use crate::client::{DataStream, ResponseContext, Subscription};
 use crate::contracts::tick_types::TickType;
 use crate::contracts::{Contract, OptionComputation};
+use crate::errors::is_informational_code;
+use crate::market_data::MarketDataType;
 use crate::messages::{self, IncomingMessages, Notice, OutgoingMessages, RequestMessage, ResponseMessage};
+use crate::news::{self, NewsArticle};
 use crate::orders::TagValue;
 use crate::server_versions;
 use crate::ToField;
@@ -34,6 +37,69 @@ pub enum BarSize {
     // Day,
 }
 
+/// Kind of tick-by-tick data to request.
+#[derive(Clone, Debug, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TickByTickType {
+    /// Trades reported to the tape. Excludes combos, odd lots and other non-reportable trades.
+    Last,
+    /// All trades, including combos, derivatives and other trades that are not reported to the tape.
+    AllLast,
+    /// Bid and ask quotes.
+    BidAsk,
+    /// Midpoint between bid and ask.
+    MidPoint,
+}
+
+impl std::fmt::Display for TickByTickType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Last => write!(f, "Last"),
+            Self::AllLast => write!(f, "AllLast"),
+            Self::BidAsk => write!(f, "BidAsk"),
+            Self::MidPoint => write!(f, "MidPoint"),
+        }
+    }
+}
+
+impl ToField for TickByTickType {
+    fn to_field(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Tick returned by [Client::tick_by_tick](crate::Client::tick_by_tick).
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub enum TickByTick {
+    /// `Last` or `AllLast` trade.
+    Trade(Trade),
+    /// `BidAsk` quote.
+    BidAsk(BidAsk),
+    /// `MidPoint` tick.
+    MidPoint(MidPoint),
+}
+
+impl DataStream<TickByTick> for TickByTick {
+    const RESPONSE_MESSAGE_IDS: &[IncomingMessages] = &[IncomingMessages::TickByTick];
+
+    fn decode(_client: &Client, message: &mut ResponseMessage) -> Result<Self, Error> {
+        match message.message_type() {
+            IncomingMessages::TickByTick => match message.peek_int(2)? {
+                1 | 2 => Ok(TickByTick::Trade(decoders::decode_trade_tick(message)?)),
+                3 => Ok(TickByTick::BidAsk(decoders::decode_bid_ask_tick(message)?)),
+                4 => Ok(TickByTick::MidPoint(decoders::decode_mid_point_tick(message)?)),
+                tick_type => Err(Error::Simple(format!("Unexpected tick_type: {tick_type}"))),
+            },
+            IncomingMessages::Error => Err(Error::from(message.clone())),
+            _ => Err(Error::UnexpectedResponse(message.clone())),
+        }
+    }
+
+    fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: &ResponseContext) -> Result<RequestMessage, Error> {
+        let request_id = request_id.expect("Request ID required to encode cancel tick by tick");
+        encoders::encode_cancel_tick_by_tick(request_id)
+    }
+}
+
 /// Represents `BidAsk` tick by tick realtime tick.
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct BidAsk {
@@ -63,7 +129,7 @@ impl DataStream<BidAsk> for BidAsk {
     }
 
     fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: &ResponseContext) -> Result<RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel realtime bars");
+        let request_id = request_id.expect("Request ID required to encode cancel tick by tick");
         encoders::encode_cancel_tick_by_tick(request_id)
     }
 }
@@ -137,7 +203,7 @@ impl DataStream<Bar> for Bar {
 /// Represents `Last` or `AllLast` tick-by-tick real-time tick.
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct Trade {
-    /// Tick type: `Last` or `AllLast`
+    /// Tick type: `Last` or `AllLast`. See [TickByTickType] for the difference.
     pub tick_type: String,
     /// The trade's date and time (either as a yyyymmss hh:mm:ss formatted string or as system time according to the request). Time zone is the TWS time zone chosen on login.
     pub time: OffsetDateTime,
@@ -165,7 +231,7 @@ impl DataStream<Trade> for Trade {
     }
 
     fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: &ResponseContext) -> Result<RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel realtime bars");
+        let request_id = request_id.expect("Request ID required to encode cancel tick by tick");
         encoders::encode_cancel_tick_by_tick(request_id)
     }
 }
@@ -264,9 +330,9 @@ impl DataStream<MarketDepths> for MarketDepths {
         }
     }
 
-    fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: &ResponseContext) -> Result<RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel realtime bars");
-        encoders::encode_cancel_tick_by_tick(request_id)
+    fn cancel_message(server_version: i32, request_id: Option<i32>, context: &ResponseContext) -> Result<RequestMessage, Error> {
+        let request_id = request_id.expect("Request ID required to encode cancel market depth");
+        encoders::encode_cancel_market_depth(server_version, request_id, context.is_smart_depth)
     }
 }
 
@@ -285,6 +351,17 @@ pub struct DepthMarketDataDescription {
     pub aggregated_group: Option<String>,
 }
 
+/// Maps an exchange letter, as found in tick-by-tick and RTVolume data, to the exchange name.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmartComponent {
+    /// Bit number identifying the component.
+    pub bit_number: i32,
+    /// The exchange name, e.g. ISLAND.
+    pub exchange: String,
+    /// Single letter code for the exchange, e.g. Q.
+    pub exchange_letter: String,
+}
+
 #[derive(Debug)]
 pub enum TickTypes {
     Price(TickPrice),
@@ -297,6 +374,10 @@ pub enum TickTypes {
     Notice(Notice),
     RequestParameters(TickRequestParameters),
     PriceSize(TickPriceSize),
+    /// News headline, received when generic tick 292 is requested.
+    News(NewsArticle),
+    /// Type of market data the subscription is receiving, e.g. delayed data when no realtime subscription is available.
+    MarketDataType(MarketDataType),
 }
 
 impl DataStream<TickTypes> for TickTypes {
@@ -310,6 +391,8 @@ impl DataStream<TickTypes> for TickTypes {
         IncomingMessages::TickSnapshotEnd,
         IncomingMessages::Error,
         IncomingMessages::TickReqParams,
+        IncomingMessages::TickNews,
+        IncomingMessages::MarketDataType,
     ];
 
     fn decode(client: &Client, message: &mut ResponseMessage) -> Result<Self, Error> {
@@ -324,14 +407,16 @@ impl DataStream<TickTypes> for TickTypes {
                 message,
             )?)),
             IncomingMessages::TickReqParams => Ok(TickTypes::RequestParameters(decoders::decode_tick_request_parameters(message)?)),
+            IncomingMessages::TickNews => Ok(TickTypes::News(news::decoders::decode_tick_news(message.clone())?)),
             IncomingMessages::TickSnapshotEnd => Ok(TickTypes::SnapshotEnd),
+            IncomingMessages::MarketDataType => Ok(TickTypes::MarketDataType(decoders::decode_market_data_type(message)?)),
             IncomingMessages::Error => Ok(TickTypes::Notice(Notice::from(message))),
             _ => Err(Error::NotImplemented),
         }
     }
 
     fn cancel_message(_server_version: i32, request_id: Option<i32>, _context: &ResponseContext) -> Result<RequestMessage, Error> {
-        let request_id = request_id.expect("Request ID required to encode cancel realtime bars");
+        let request_id = request_id.expect("Request ID required to encode cancel market data");
         encoders::encode_cancel_market_data(request_id)
     }
 }
@@ -371,6 +456,107 @@ pub struct TickString {
     pub value: String,
 }
 
+impl TickString {
+    /// Decodes an RT Volume (generic tick 233) or RT Trade Volume (generic tick 375) value.
+    ///
+    /// Returns `None` for other tick types, or if the value is malformed.
+    pub fn daily_volume(&self) -> Option<DailyVolume> {
+        if !matches!(self.tick_type, TickType::RtVolume | TickType::RtTrdVolume) {
+            return None;
+        }
+
+        match decoders::decode_daily_volume(&self.value) {
+            Ok(volume) => Some(volume),
+            Err(err) => {
+                warn!("error decoding {:?} tick {:?}: {err}", self.tick_type, self.value);
+                None
+            }
+        }
+    }
+}
+
+/// Last trade and cumulative volume of the day, from an RT Volume or RT Trade Volume tick. See [TickString::daily_volume].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DailyVolume {
+    /// Price of the last trade. `None` when the update carries no trade.
+    pub last: Option<f64>,
+    /// Size of the last trade. `None` when the update carries no trade.
+    pub size: Option<f64>,
+    /// Time of the last trade.
+    pub time: OffsetDateTime,
+    /// Total volume traded during the day.
+    pub total_volume: f64,
+    /// Volume weighted average price of the day.
+    pub vwap: f64,
+    /// Whether the trade was filled by a single market maker.
+    pub single_trade: bool,
+}
+
+/// Generic ticks that can be requested with [Client::market_data] or [MarketDataRequestBuilder::generic_tick](crate::market_data::builder::MarketDataRequestBuilder::generic_tick).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GenericTick {
+    /// 100, option volume for stocks.
+    OptionVolume,
+    /// 101, option open interest for stocks.
+    OptionOpenInterest,
+    /// 104, historical volatility for stocks.
+    HistoricalVolatility,
+    /// 105, average option volume for stocks.
+    AverageOptionVolume,
+    /// 106, option implied volatility for stocks.
+    OptionImpliedVolatility,
+    /// 162, index future premium.
+    IndexFuturePremium,
+    /// 165, miscellaneous stats.
+    MiscellaneousStats,
+    /// 221, mark price.
+    MarkPrice,
+    /// 225, auction volume, price and imbalance.
+    AuctionValues,
+    /// 233, last trade, total volume and VWAP, including unreportable trades. See [TickString::daily_volume].
+    RtVolume,
+    /// 236, shortable.
+    Shortable,
+    /// 256, inventory.
+    Inventory,
+    /// 258, fundamental ratios.
+    FundamentalRatios,
+    /// 292, news headlines.
+    WideNews,
+    /// 375, last trade, total volume and VWAP, excluding unreportable trades. See [TickString::daily_volume].
+    RtTradeVolume,
+    /// 411, realtime historical volatility.
+    RealtimeHistoricalVolatility,
+    /// 456, IB dividends.
+    IbDividends,
+}
+
+impl std::fmt::Display for GenericTick {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let id = match self {
+            GenericTick::OptionVolume => 100,
+            GenericTick::OptionOpenInterest => 101,
+            GenericTick::HistoricalVolatility => 104,
+            GenericTick::AverageOptionVolume => 105,
+            GenericTick::OptionImpliedVolatility => 106,
+            GenericTick::IndexFuturePremium => 162,
+            GenericTick::MiscellaneousStats => 165,
+            GenericTick::MarkPrice => 221,
+            GenericTick::AuctionValues => 225,
+            GenericTick::RtVolume => 233,
+            GenericTick::Shortable => 236,
+            GenericTick::Inventory => 256,
+            GenericTick::FundamentalRatios => 258,
+            GenericTick::WideNews => 292,
+            GenericTick::RtTradeVolume => 375,
+            GenericTick::RealtimeHistoricalVolatility => 411,
+            GenericTick::IbDividends => 456,
+        };
+
+        write!(f, "{id}")
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct TickEFP {
     pub tick_type: TickType,
@@ -396,6 +582,48 @@ pub struct TickRequestParameters {
     pub snapshot_permissions: i32,
 }
 
+/// Consolidated quote returned by [Client::market_data_snapshot](crate::Client::market_data_snapshot) and [Client::regulatory_snapshot](crate::Client::regulatory_snapshot).
+/// Fields are `None` when TWS sent no value for them. Delayed ticks populate the same fields.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MarketDataSnapshot {
+    pub bid: Option<f64>,
+    pub bid_size: Option<f64>,
+    pub ask: Option<f64>,
+    pub ask_size: Option<f64>,
+    pub last: Option<f64>,
+    pub last_size: Option<f64>,
+    pub high: Option<f64>,
+    pub low: Option<f64>,
+    pub open: Option<f64>,
+    pub close: Option<f64>,
+    pub volume: Option<f64>,
+}
+
+impl MarketDataSnapshot {
+    fn update_price(&mut self, tick_type: &TickType, price: f64) {
+        match tick_type {
+            TickType::Bid | TickType::DelayedBid => self.bid = Some(price),
+            TickType::Ask | TickType::DelayedAsk => self.ask = Some(price),
+            TickType::Last | TickType::DelayedLast => self.last = Some(price),
+            TickType::High | TickType::DelayedHigh => self.high = Some(price),
+            TickType::Low | TickType::DelayedLow => self.low = Some(price),
+            TickType::Open | TickType::DelayedOpen => self.open = Some(price),
+            TickType::Close | TickType::DelayedClose => self.close = Some(price),
+            _ => {}
+        }
+    }
+
+    fn update_size(&mut self, tick_type: &TickType, size: f64) {
+        match tick_type {
+            TickType::BidSize | TickType::DelayedBidSize => self.bid_size = Some(size),
+            TickType::AskSize | TickType::DelayedAskSize => self.ask_size = Some(size),
+            TickType::LastSize | TickType::DelayedLastSize => self.last_size = Some(size),
+            TickType::Volume | TickType::DelayedVolume => self.volume = Some(size),
+            _ => {}
+        }
+    }
+}
+
 // === Implementation ===
 
 // Requests realtime bars.
@@ -414,6 +642,25 @@ pub(crate) fn realtime_bars<'a>(
     Ok(Subscription::new(client, subscription, ResponseContext::default()))
 }
 
+// Requests tick by tick ticks of the given kind.
+pub(crate) fn tick_by_tick<'a>(
+    client: &'a Client,
+    contract: &Contract,
+    tick_type: TickByTickType,
+    number_of_ticks: i32,
+    ignore_size: bool,
+) -> Result<Subscription<'a, TickByTick>, Error> {
+    validate_tick_by_tick_request(client, contract, number_of_ticks, ignore_size)?;
+
+    let server_version = client.server_version();
+    let request_id = client.next_request_id();
+
+    let request = encoders::encode_tick_by_tick(server_version, request_id, contract, tick_type, number_of_ticks, ignore_size)?;
+    let subscription = client.send_request(request_id, request)?;
+
+    Ok(Subscription::new(client, subscription, ResponseContext::default()))
+}
+
 // Requests tick by tick AllLast ticks.
 pub(crate) fn tick_by_tick_all_last<'a>(
     client: &'a Client,
@@ -426,7 +673,14 @@ pub(crate) fn tick_by_tick_all_last<'a>(
     let server_version = client.server_version();
     let request_id = client.next_request_id();
 
-    let request = encoders::encode_tick_by_tick(server_version, request_id, contract, "AllLast", number_of_ticks, ignore_size)?;
+    let request = encoders::encode_tick_by_tick(
+        server_version,
+        request_id,
+        contract,
+        TickByTickType::AllLast,
+        number_of_ticks,
+        ignore_size,
+    )?;
     let subscription = client.send_request(request_id, request)?;
 
     Ok(Subscription::new(client, subscription, ResponseContext::default()))
@@ -458,7 +712,7 @@ pub(crate) fn tick_by_tick_last<'a>(
     let server_version = client.server_version();
     let request_id = client.next_request_id();
 
-    let request = encoders::encode_tick_by_tick(server_version, request_id, contract, "Last", number_of_ticks, ignore_size)?;
+    let request = encoders::encode_tick_by_tick(server_version, request_id, contract, TickByTickType::Last, number_of_ticks, ignore_size)?;
     let subscription = client.send_request(request_id, request)?;
 
     Ok(Subscription::new(client, subscription, ResponseContext::default()))
@@ -476,7 +730,7 @@ pub(crate) fn tick_by_tick_bid_ask<'a>(
     let server_version = client.server_version();
     let request_id = client.next_request_id();
 
-    let request = encoders::encode_tick_by_tick(server_version, request_id, contract, "BidAsk", number_of_ticks, ignore_size)?;
+    let request = encoders::encode_tick_by_tick(server_version, request_id, contract, TickByTickType::BidAsk, number_of_ticks, ignore_size)?;
     let subscription = client.send_request(request_id, request)?;
 
     Ok(Subscription::new(client, subscription, ResponseContext::default()))
@@ -494,7 +748,14 @@ pub(crate) fn tick_by_tick_midpoint<'a>(
     let server_version = client.server_version();
     let request_id = client.next_request_id();
 
-    let request = encoders::encode_tick_by_tick(server_version, request_id, contract, "MidPoint", number_of_ticks, ignore_size)?;
+    let request = encoders::encode_tick_by_tick(
+        server_version,
+        request_id,
+        contract,
+        TickByTickType::MidPoint,
+        number_of_ticks,
+        ignore_size,
+    )?;
     let subscription = client.send_request(request_id, request)?;
 
     Ok(Subscription::new(client, subscription, ResponseContext::default()))
@@ -520,7 +781,11 @@ pub(crate) fn market_depth<'a>(
     let request = encoders::encode_request_market_depth(client.server_version, request_id, contract, number_of_rows, is_smart_depth)?;
     let subscription = client.send_request(request_id, request)?;
 
-    Ok(Subscription::new(client, subscription, ResponseContext::default()))
+    let context = ResponseContext {
+        is_smart_depth,
+        ..Default::default()
+    };
+    Ok(Subscription::new(client, subscription, context))
 }
 
 // Requests venues for which market data is returned to market_depth (those with market makers)
@@ -547,6 +812,27 @@ pub fn market_depth_exchanges(client: &Client) -> Result<Vec<DepthMarketDataDesc
     }
 }
 
+// Requests the exchanges making up the SMART routing of the given BBO exchange.
+pub fn smart_components(client: &Client, bbo_exchange: &str) -> Result<Vec<SmartComponent>, Error> {
+    client.check_server_version(server_versions::REQ_SMART_COMPONENTS, "It does not support smart components request.")?;
+
+    loop {
+        let request_id = client.next_request_id();
+        let request = encoders::encode_request_smart_components(request_id, bbo_exchange)?;
+        let subscription = client.send_request(request_id, request)?;
+
+        match subscription.next() {
+            Some(Ok(mut message)) => return decoders::decode_smart_components(&mut message),
+            Some(Err(Error::ConnectionReset)) => {
+                debug!("connection reset. retrying smart_components");
+                continue;
+            }
+            Some(Err(e)) => return Err(e),
+            None => return Ok(Vec::new()),
+        }
+    }
+}
+
 // Requests real time market data.
 pub fn market_data<'a>(
     client: &'a Client,
@@ -564,7 +850,43 @@ pub fn market_data<'a>(
         snapshot,
         regulatory_snapshot,
     )?;
-    let subscription = client.send_request(request_id, request)?;
 
-    Ok(Subscription::new(client, subscription, ResponseContext::default()))
+    client.subscribe(request_id, request, ResponseContext::default())
+}
+
+// Requests a one time snapshot of market data and consolidates the ticks received until SnapshotEnd.
+pub(crate) fn market_data_snapshot(
+    client: &Client,
+    contract: &Contract,
+    generic_ticks: &[&str],
+    regulatory_snapshot: bool,
+) -> Result<MarketDataSnapshot, Error> {
+    let subscription = market_data(client, contract, generic_ticks, true, regulatory_snapshot)?;
+
+    let mut snapshot = MarketDataSnapshot::default();
+    loop {
+        match subscription.next() {
+            Some(TickTypes::Price(tick)) => snapshot.update_price(&tick.tick_type, tick.price),
+            Some(TickTypes::Size(tick)) => snapshot.update_size(&tick.tick_type, tick.size),
+            Some(TickTypes::PriceSize(tick)) => {
+                snapshot.update_price(&tick.price_tick_type, tick.price);
+                snapshot.update_size(&tick.size_tick_type, tick.size);
+            }
+            Some(TickTypes::SnapshotEnd) => return Ok(snapshot),
+            Some(TickTypes::Notice(notice)) if is_informational_notice(notice.code) => debug!("market data snapshot notice: {notice}"),
+            Some(TickTypes::Notice(notice)) => {
+                return Err(Error::TwsError {
+                    code: notice.code,
+                    message: notice.message,
+                })
+            }
+            Some(_) => {}
+            None => return Err(subscription.error().unwrap_or(Error::UnexpectedEndOfStream)),
+        }
+    }
+}
+
+// Warnings (2100-2199) and the delayed market data notice (10167) do not prevent a snapshot from completing.
+fn is_informational_notice(code: i32) -> bool {
+    is_informational_code(code) || code == 10167
 }
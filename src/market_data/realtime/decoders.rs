@@ -1,12 +1,15 @@
+use time::OffsetDateTime;
+
 use crate::contracts::decoders::decode_option_computation;
 use crate::contracts::tick_types::TickType;
 use crate::contracts::OptionComputation;
+use crate::market_data::MarketDataType;
 use crate::Error;
 use crate::{messages::ResponseMessage, server_versions};
 
 use super::{
-    Bar, BidAsk, BidAskAttribute, DepthMarketDataDescription, MarketDepth, MarketDepthL2, MidPoint, TickEFP, TickGeneric, TickPrice, TickPriceSize,
-    TickRequestParameters, TickSize, TickString, TickTypes, Trade, TradeAttribute,
+    Bar, BidAsk, BidAskAttribute, DailyVolume, DepthMarketDataDescription, MarketDepth, MarketDepthL2, MidPoint, SmartComponent, TickEFP,
+    TickGeneric, TickPrice, TickPriceSize, TickRequestParameters, TickSize, TickString, TickTypes, Trade, TradeAttribute,
 };
 
 #[cfg(test)]
@@ -172,6 +175,24 @@ pub(super) fn decode_market_depth_exchanges(server_version: i32, message: &mut R
     Ok(descriptions)
 }
 
+pub(super) fn decode_smart_components(message: &mut ResponseMessage) -> Result<Vec<SmartComponent>, Error> {
+    message.skip(); // message type
+    message.skip(); // request id
+
+    let count = message.next_int()?;
+    let mut components = Vec::with_capacity(count.max(0) as usize);
+
+    for _ in 0..count {
+        components.push(SmartComponent {
+            bit_number: message.next_int()?,
+            exchange: message.next_string()?,
+            exchange_letter: message.next_string()?,
+        });
+    }
+
+    Ok(components)
+}
+
 pub(super) fn decode_tick_price(server_version: i32, message: &mut ResponseMessage) -> Result<TickTypes, Error> {
     message.skip(); // message type
     let message_version = message.next_int()?;
@@ -243,6 +264,36 @@ pub(super) fn decode_tick_string(message: &mut ResponseMessage) -> Result<TickSt
     })
 }
 
+// Parses an RT Volume value, e.g. 701.28;1;1348075471534;67854;701.46918464;true
+pub(super) fn decode_daily_volume(value: &str) -> Result<DailyVolume, Error> {
+    let fields: Vec<&str> = value.split(';').collect();
+    if fields.len() != 6 {
+        return Err(Error::Simple(format!("expected 6 fields in RT volume: {value}")));
+    }
+
+    let number = |field: &str| -> Result<f64, Error> { field.parse().map_err(|_| Error::Simple(format!("invalid number in RT volume: {value}"))) };
+    let optional = |field: &str| -> Result<Option<f64>, Error> {
+        if field.is_empty() {
+            Ok(None)
+        } else {
+            number(field).map(Some)
+        }
+    };
+
+    let millis: i64 = fields[2].parse()?;
+    let time = OffsetDateTime::from_unix_timestamp_nanos(millis as i128 * 1_000_000)
+        .map_err(|_| Error::Simple(format!("invalid time in RT volume: {value}")))?;
+
+    Ok(DailyVolume {
+        last: optional(fields[0])?,
+        size: optional(fields[1])?,
+        time,
+        total_volume: number(fields[3])?,
+        vwap: number(fields[4])?,
+        single_trade: fields[5] == "true",
+    })
+}
+
 pub(super) fn decode_tick_efp(message: &mut ResponseMessage) -> Result<TickEFP, Error> {
     message.skip(); // message type
     message.skip(); // message version
@@ -275,6 +326,20 @@ pub(super) fn decode_tick_option_computation(server_version: i32, message: &mut
     decode_option_computation(server_version, message)
 }
 
+pub(super) fn decode_market_data_type(message: &mut ResponseMessage) -> Result<MarketDataType, Error> {
+    message.skip(); // message type
+    message.skip(); // message version
+    message.skip(); // message request id
+
+    match message.next_int()? {
+        1 => Ok(MarketDataType::Live),
+        2 => Ok(MarketDataType::Frozen),
+        3 => Ok(MarketDataType::Delayed),
+        4 => Ok(MarketDataType::DelayedFrozen),
+        market_data_type => Err(Error::Simple(format!("Unexpected market data type: {market_data_type}"))),
+    }
+}
+
 pub(super) fn decode_tick_request_parameters(message: &mut ResponseMessage) -> Result<TickRequestParameters, Error> {
     message.skip(); // message type
     message.skip(); // message request id
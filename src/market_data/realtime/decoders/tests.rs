@@ -233,6 +233,29 @@ mod market_depth_tests {
         assert_eq!(second.aggregated_group, Some("1".to_string()), "Wrong aggregated group");
     }
 
+    #[test]
+    fn test_decode_smart_components() {
+        let mut message = ResponseMessage::from("82\09000\02\01\0ISLAND\0Q\02\0ARCA\0P\0");
+
+        let components = decode_smart_components(&mut message).expect("Failed to decode smart components");
+
+        assert_eq!(
+            components,
+            vec![
+                SmartComponent {
+                    bit_number: 1,
+                    exchange: "ISLAND".to_owned(),
+                    exchange_letter: "Q".to_owned(),
+                },
+                SmartComponent {
+                    bit_number: 2,
+                    exchange: "ARCA".to_owned(),
+                    exchange_letter: "P".to_owned(),
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_decode_market_depth_exchanges_old_version() {
         let mut message = ResponseMessage::from("71\02\0ISLAND\0STK\01\0NYSE\0STK\00\0");
@@ -370,6 +393,47 @@ mod tick_string_tests {
             assert_eq!(tick.value, value, "Wrong value for type_id {}", type_id);
         }
     }
+
+    #[test]
+    fn test_decode_rt_volume() {
+        let mut message = ResponseMessage::from("46\06\09000\048\0701.28;1;1348075471534;67854;701.46918464;true\0");
+
+        let tick = decode_tick_string(&mut message).expect("Failed to decode tick string");
+        let volume = tick.daily_volume().expect("Failed to decode RT volume");
+
+        assert_eq!(volume.last, Some(701.28), "Wrong last");
+        assert_eq!(volume.size, Some(1.0), "Wrong size");
+        assert_eq!(
+            volume.time,
+            OffsetDateTime::from_unix_timestamp_nanos(1_348_075_471_534_000_000).unwrap(),
+            "Wrong time"
+        );
+        assert_eq!(volume.total_volume, 67854.0, "Wrong total volume");
+        assert_eq!(volume.vwap, 701.46918464, "Wrong vwap");
+        assert!(volume.single_trade, "Wrong single trade flag");
+
+        // volume only update, without a trade
+        let volume = decode_daily_volume(";;1348075471534;67900;701.5;false").expect("Failed to decode RT volume");
+        assert_eq!(volume.last, None);
+        assert_eq!(volume.size, None);
+        assert_eq!(volume.total_volume, 67900.0);
+        assert!(!volume.single_trade);
+
+        let tick = TickString {
+            tick_type: TickType::RtTrdVolume,
+            value: "701.28;1;1348075471534;67854;701.46918464;false".to_owned(),
+        };
+        assert!(tick.daily_volume().is_some(), "RT trade volume should decode");
+
+        let tick = TickString {
+            tick_type: TickType::LastExch,
+            value: "701.28;1;1348075471534;67854;701.46918464;false".to_owned(),
+        };
+        assert!(tick.daily_volume().is_none(), "other tick types are not volume");
+
+        assert!(decode_daily_volume("701.28;1;1348075471534").is_err(), "missing fields");
+        assert!(decode_daily_volume("701.28;x;1348075471534;67854;701.46;false").is_err(), "invalid size");
+    }
 }
 
 #[cfg(test)]
@@ -445,3 +509,32 @@ mod tick_efp_tests {
         }
     }
 }
+
+#[cfg(test)]
+mod market_data_type_tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_market_data_type() {
+        let test_cases = vec![
+            (1, MarketDataType::Live),
+            (2, MarketDataType::Frozen),
+            (3, MarketDataType::Delayed),
+            (4, MarketDataType::DelayedFrozen),
+        ];
+
+        for (type_id, expected_type) in test_cases {
+            let mut message = ResponseMessage::from(format!("58\01\09000\0{type_id}\0").as_str());
+
+            let market_data_type = decode_market_data_type(&mut message).expect("Failed to decode market data type");
+            assert_eq!(market_data_type, expected_type, "Wrong market data type for type_id {type_id}");
+        }
+    }
+
+    #[test]
+    fn test_decode_market_data_type_unknown() {
+        let mut message = ResponseMessage::from("58\01\09000\07\0");
+
+        assert!(decode_market_data_type(&mut message).is_err(), "Unknown market data type should fail");
+    }
+}
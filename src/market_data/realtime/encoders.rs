@@ -1,4 +1,4 @@
-use super::{BarSize, WhatToShow};
+use super::{BarSize, TickByTickType, WhatToShow};
 use crate::contracts::Contract;
 use crate::contracts::SecurityType;
 use crate::messages::OutgoingMessages;
@@ -72,7 +72,7 @@ pub(super) fn encode_tick_by_tick(
     server_version: i32,
     request_id: i32,
     contract: &Contract,
-    tick_type: &str,
+    tick_type: TickByTickType,
     number_of_ticks: i32,
     ignore_size: bool,
 ) -> Result<RequestMessage, Error> {
@@ -155,6 +155,32 @@ pub(super) fn encode_request_market_depth(
     Ok(message)
 }
 
+pub(super) fn encode_cancel_market_depth(server_version: i32, request_id: i32, is_smart_depth: bool) -> Result<RequestMessage, Error> {
+    const VERSION: i32 = 1;
+
+    let mut message = RequestMessage::new();
+
+    message.push_field(&OutgoingMessages::CancelMarketDepth);
+    message.push_field(&VERSION);
+    message.push_field(&request_id);
+
+    if server_version >= server_versions::SMART_DEPTH {
+        message.push_field(&is_smart_depth);
+    }
+
+    Ok(message)
+}
+
+pub(super) fn encode_request_smart_components(request_id: i32, bbo_exchange: &str) -> Result<RequestMessage, Error> {
+    let mut message = RequestMessage::new();
+
+    message.push_field(&OutgoingMessages::RequestSmartComponents);
+    message.push_field(&request_id);
+    message.push_field(&bbo_exchange);
+
+    Ok(message)
+}
+
 pub(super) fn encode_request_market_depth_exchanges() -> Result<RequestMessage, Error> {
     let mut message = RequestMessage::new();
 
@@ -173,6 +199,14 @@ pub(crate) fn encode_request_market_data(
 ) -> Result<RequestMessage, Error> {
     const VERSION: i32 = 11;
 
+    if regulatory_snapshot && server_version < server_versions::REQ_SMART_COMPONENTS {
+        return Err(Error::ServerVersion(
+            server_versions::REQ_SMART_COMPONENTS,
+            server_version,
+            "It does not support regulatory snapshots.".into(),
+        ));
+    }
+
     let mut message = RequestMessage::new();
 
     message.push_field(&OutgoingMessages::RequestMarketData);
@@ -25,7 +25,7 @@ mod tick_by_tick_tests {
         let request_id = 9000;
         let server_version = server_versions::TICK_BY_TICK;
         let contract = contract_samples::simple_future();
-        let tick_type = "AllLast";
+        let tick_type = TickByTickType::AllLast;
         let number_of_ticks = 1;
         let ignore_size = true;
 
@@ -51,7 +51,7 @@ mod tick_by_tick_tests {
         assert_eq!(message[13], contract.trading_class, "Wrong trading class");
 
         // Verify tick parameters
-        assert_eq!(message[14], tick_type, "Wrong tick type");
+        assert_eq!(message[14], tick_type.to_field(), "Wrong tick type");
 
         // Version specific fields
         if server_version >= server_versions::TICK_BY_TICK_IGNORE_SIZE {
@@ -60,12 +60,29 @@ mod tick_by_tick_tests {
         }
     }
 
+    #[test]
+    fn test_encode_tick_by_tick_types() {
+        let contract = create_test_contract();
+        let cases = [
+            (TickByTickType::Last, "Last"),
+            (TickByTickType::AllLast, "AllLast"),
+            (TickByTickType::BidAsk, "BidAsk"),
+            (TickByTickType::MidPoint, "MidPoint"),
+        ];
+
+        for (tick_type, expected) in cases {
+            let message = encode_tick_by_tick(server_versions::TICK_BY_TICK_IGNORE_SIZE, 9000, &contract, tick_type, 0, false)
+                .expect("Failed to encode tick by tick");
+            assert_eq!(message[14], expected, "Wrong tick type for {tick_type:?}");
+        }
+    }
+
     #[test]
     fn test_tick_by_tick_with_old_server() {
         let request_id = 9000;
         let server_version = server_versions::TICK_BY_TICK - 1; // Version before TICK_BY_TICK
         let contract = contract_samples::simple_future();
-        let tick_type = "AllLast";
+        let tick_type = TickByTickType::AllLast;
         let number_of_ticks = 1;
         let ignore_size = true;
 
@@ -183,6 +200,43 @@ mod market_data_tests {
         assert_eq!(message[18], regulatory_snapshot.to_field(), "Wrong regulatory snapshot flag");
     }
 
+    #[test]
+    fn test_encode_request_market_data_generic_ticks() {
+        let server_version = server_versions::SIZE_RULES;
+        let contract = create_test_contract();
+
+        let message = encode_request_market_data(server_version, 9000, &contract, &[], false, false).expect("Failed to encode market data request");
+        assert_eq!(message[16], "", "Empty generic tick list should encode as empty field");
+
+        let message =
+            encode_request_market_data(server_version, 9000, &contract, &["233"], false, false).expect("Failed to encode market data request");
+        assert_eq!(message[16], "233", "Wrong single generic tick");
+
+        let message = encode_request_market_data(server_version, 9000, &contract, &["100", "104", "233", "236", "292"], true, false)
+            .expect("Failed to encode market data request");
+        assert_eq!(message[16], "100,104,233,236,292", "Wrong generic ticks");
+        assert_eq!(message[17], "1", "Generic ticks should precede snapshot flag");
+    }
+
+    #[test]
+    fn test_encode_request_market_data_regulatory_snapshot() {
+        let contract = create_test_contract();
+
+        let message = encode_request_market_data(server_versions::REQ_SMART_COMPONENTS, 9000, &contract, &[], true, true)
+            .expect("Failed to encode market data request");
+        assert_eq!(message[18], "1", "Regulatory snapshot flag should be set");
+
+        let result = encode_request_market_data(server_versions::REQ_SMART_COMPONENTS - 1, 9000, &contract, &[], true, true);
+        assert!(
+            matches!(result, Err(Error::ServerVersion(server_versions::REQ_SMART_COMPONENTS, _, _))),
+            "regulatory snapshot should require REQ_SMART_COMPONENTS"
+        );
+
+        let message = encode_request_market_data(server_versions::REQ_SMART_COMPONENTS - 1, 9000, &contract, &[], true, false)
+            .expect("Older servers should accept requests without a regulatory snapshot");
+        assert_eq!(message[18], "", "Regulatory snapshot flag should not be sent to older servers");
+    }
+
     #[test]
     fn test_encode_cancel_market_data() {
         let request_id = 9000;
@@ -224,6 +278,22 @@ mod market_depth_tests {
         assert_eq!(message[15], number_of_rows.to_field(), "Unexpected number of rows");
         assert_eq!(message[16], is_smart_depth.to_field(), "Unexpected smart depth flag");
     }
+
+    #[test]
+    fn test_encode_cancel_market_depth() {
+        let request_id = 9000;
+
+        let message = encode_cancel_market_depth(server_versions::SMART_DEPTH, request_id, true).expect("Failed to encode cancel market depth");
+
+        assert_eq!(message[0], OutgoingMessages::CancelMarketDepth.to_field(), "Wrong message type");
+        assert_eq!(message[1], "1", "Wrong version");
+        assert_eq!(message[2], request_id.to_field(), "Wrong request ID");
+        assert_eq!(message[3], true.to_field(), "Wrong smart depth flag");
+        assert_eq!(message.len(), 4, "Unexpected message length");
+
+        let message = encode_cancel_market_depth(server_versions::SMART_DEPTH - 1, request_id, true).expect("Failed to encode cancel market depth");
+        assert_eq!(message.len(), 3, "Smart depth flag should be omitted for older servers");
+    }
 }
 
 #[cfg(test)]
@@ -219,3 +219,174 @@ fn test_market_data_error_handling() {
         _ => panic!("Expected error notice"),
     }
 }
+
+#[test]
+fn test_market_data_snapshot() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "4|2|9001|10167|Requested market data is not subscribed. Displaying delayed market data.|".to_owned(),
+            "1|2|9001|1|185.50|100|".to_owned(),
+            "1|2|9001|2|185.60|200|".to_owned(),
+            "1|2|9001|4|185.55|50|".to_owned(),
+            "2|2|9001|8|12000|".to_owned(),
+            "1|2|9001|9|184.20|0|".to_owned(),
+            "57|1|9001|".to_owned(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    let contract = Contract::stock("AAPL");
+
+    let snapshot = client.market_data_snapshot(&contract, &[]).expect("snapshot request failed");
+
+    assert_eq!(snapshot.bid, Some(185.50), "bid");
+    assert_eq!(snapshot.bid_size, Some(100.0), "bid_size");
+    assert_eq!(snapshot.ask, Some(185.60), "ask");
+    assert_eq!(snapshot.ask_size, Some(200.0), "ask_size");
+    assert_eq!(snapshot.last, Some(185.55), "last");
+    assert_eq!(snapshot.last_size, Some(50.0), "last_size");
+    assert_eq!(snapshot.volume, Some(12000.0), "volume");
+    assert_eq!(snapshot.close, Some(184.20), "close");
+    assert_eq!(snapshot.high, None, "high");
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(
+        request_messages[0][0],
+        OutgoingMessages::RequestMarketData.to_field(),
+        "Wrong message type"
+    );
+    assert_eq!(request_messages[0][17], "1", "Snapshot flag should be set");
+}
+
+#[test]
+fn test_market_data_snapshot_error() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["4|2|9001|354|Requested market data is not subscribed.|".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    let contract = Contract::stock("AAPL");
+
+    let result = client.market_data_snapshot(&contract, &[]);
+
+    match result {
+        Err(Error::TwsError { code, message }) => {
+            assert_eq!(code, 354, "Wrong error code");
+            assert_eq!(message, "Requested market data is not subscribed.", "Wrong error message");
+        }
+        other => panic!("Expected error message, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_regulatory_snapshot() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "1|2|9000|1|185.50|100|".to_owned(),
+            "1|2|9000|2|185.60|200|".to_owned(),
+            "57|1|9000|".to_owned(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    let contract = Contract::stock("AAPL");
+
+    let quote = client.regulatory_snapshot(&contract).expect("regulatory snapshot request failed");
+
+    assert_eq!(quote.bid, Some(185.50), "bid");
+    assert_eq!(quote.ask, Some(185.60), "ask");
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(request_messages[0][17], "1", "Snapshot flag should be set");
+    assert_eq!(request_messages[0][18], "1", "Regulatory snapshot flag should be set");
+}
+
+#[test]
+fn test_regulatory_snapshot_server_version() {
+    let message_bus = Arc::new(MessageBusStub::default());
+
+    let client = Client::stubbed(message_bus, server_versions::REQ_SMART_COMPONENTS - 1);
+    let contract = Contract::stock("AAPL");
+
+    let result = client.regulatory_snapshot(&contract);
+
+    assert!(
+        matches!(result, Err(Error::ServerVersion(server_versions::REQ_SMART_COMPONENTS, _, _))),
+        "older servers should be rejected: {result:?}"
+    );
+    assert!(client.message_bus.request_messages().is_empty(), "request should not be sent");
+}
+
+#[test]
+fn test_market_data_tick_news() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["84|9001|1678740829000|BRFG|BRFG$1a2b3c|Apple unveils new products|A:800015:L:en:K:n/a:C:0.5|".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    let contract = Contract::stock("AAPL");
+
+    let subscription = client
+        .market_data(&contract, &["mdoff", "292"], false, false)
+        .expect("Failed to create market data subscription");
+
+    match subscription.next() {
+        Some(TickTypes::News(article)) => {
+            assert_eq!(article.provider_code, "BRFG", "Wrong provider code");
+            assert_eq!(article.article_id, "BRFG$1a2b3c", "Wrong article id");
+            assert_eq!(article.headline, "Apple unveils new products", "Wrong headline");
+            assert_eq!(article.extra_data, "A:800015:L:en:K:n/a:C:0.5", "Wrong extra data");
+        }
+        other => panic!("Expected news tick, got {other:?}"),
+    }
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(request_messages[0][16], "mdoff,292", "Wrong generic ticks");
+}
+
+#[test]
+fn test_market_data_cancel_is_idempotent() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["1|2|9000|1|185.50|100|7|".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    let contract = Contract::stock("AAPL");
+
+    let subscription = client.market_data(&contract, &[], false, false).expect("market data request failed");
+    subscription.cancel();
+    subscription.cancel();
+    drop(subscription);
+
+    let request_messages = client.message_bus.request_messages();
+    let cancels: Vec<String> = request_messages
+        .iter()
+        .map(|message| message.encode_simple())
+        .filter(|message| message.starts_with("2|"))
+        .collect();
+    assert_eq!(cancels, vec!["2|1|9000|"], "expected a single cancel market data request");
+}
+
+#[test]
+fn test_market_data_reports_market_data_type() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["58|1|9000|3|".to_owned(), "1|2|9000|1|185.50|100|7|".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    let contract = Contract::stock("AAPL");
+
+    let subscription = client.market_data(&contract, &[], false, false).expect("market data request failed");
+
+    match subscription.next() {
+        Some(TickTypes::MarketDataType(market_data_type)) => assert_eq!(market_data_type, MarketDataType::Delayed),
+        other => panic!("expected market data type, got {other:?}"),
+    }
+    assert!(matches!(subscription.next(), Some(TickTypes::PriceSize(_)) | Some(TickTypes::Price(_))));
+}
@@ -74,3 +74,41 @@ fn test_market_depth_exchanges() {
     let request = &request_messages[0];
     assert_eq!(request[0], OutgoingMessages::RequestMktDepthExchanges.to_field(), "Wrong message type");
 }
+
+#[test]
+fn test_market_depth_exchanges_server_version() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["71|1|ISLAND|STK|NASDAQ|DEEP2|1|".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::REQ_MKT_DEPTH_EXCHANGES - 1);
+
+    let result = client.market_depth_exchanges();
+    assert!(
+        matches!(result, Err(Error::ServerVersion(server_versions::REQ_MKT_DEPTH_EXCHANGES, _, _))),
+        "expected server version error, got {result:?}"
+    );
+    assert!(client.message_bus.request_messages().is_empty(), "no request should be sent");
+}
+
+#[test]
+fn test_smart_components() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["82|9000|2|1|ISLAND|Q|2|ARCA|P|".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::REQ_SMART_COMPONENTS);
+
+    let components = client.smart_components("a6").expect("Failed to request smart components");
+    assert_eq!(components.len(), 2);
+    assert_eq!(components[0].exchange, "ISLAND");
+    assert_eq!(components[1].exchange_letter, "P");
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(request_messages[0].encode_simple(), "83|9000|a6|");
+
+    let client = Client::stubbed(Arc::new(MessageBusStub::default()), server_versions::REQ_SMART_COMPONENTS - 1);
+    assert!(matches!(client.smart_components("a6"), Err(Error::ServerVersion(_, _, _))));
+}
@@ -99,3 +99,77 @@ fn test_tick_by_tick_all_last() {
     assert_eq!(request[0], OutgoingMessages::RequestTickByTickData.to_field(), "Wrong message type");
     assert_eq!(request[14], "AllLast", "Wrong tick type");
 }
+
+#[test]
+fn test_dropped_subscriptions_send_cancel() {
+    let message_bus = Arc::new(MessageBusStub::default());
+    let client = Client::stubbed(message_bus, server_versions::SMART_DEPTH);
+    let contract = contract_samples::simple_future();
+
+    // Each case opens a subscription, drops it, and names the cancel message expected for it.
+    let cases: Vec<(&str, Box<dyn Fn()>, OutgoingMessages)> = vec![
+        (
+            "tick by tick",
+            Box::new(|| drop(client.tick_by_tick(&contract, TickByTickType::Last, 0, false).unwrap())),
+            OutgoingMessages::CancelTickByTickData,
+        ),
+        (
+            "tick by tick all last",
+            Box::new(|| drop(client.tick_by_tick_all_last(&contract, 0, false).unwrap())),
+            OutgoingMessages::CancelTickByTickData,
+        ),
+        (
+            "tick by tick last",
+            Box::new(|| drop(client.tick_by_tick_last(&contract, 0, false).unwrap())),
+            OutgoingMessages::CancelTickByTickData,
+        ),
+        (
+            "tick by tick bid ask",
+            Box::new(|| drop(client.tick_by_tick_bid_ask(&contract, 0, false).unwrap())),
+            OutgoingMessages::CancelTickByTickData,
+        ),
+        (
+            "tick by tick midpoint",
+            Box::new(|| drop(client.tick_by_tick_midpoint(&contract, 0, false).unwrap())),
+            OutgoingMessages::CancelTickByTickData,
+        ),
+        (
+            "realtime bars",
+            Box::new(|| drop(client.realtime_bars(&contract, BarSize::Sec5, WhatToShow::Trades, true).unwrap())),
+            OutgoingMessages::CancelRealTimeBars,
+        ),
+        (
+            "market depth",
+            Box::new(|| drop(client.market_depth(&contract, 5, true).unwrap())),
+            OutgoingMessages::CancelMarketDepth,
+        ),
+        (
+            "market data",
+            Box::new(|| drop(client.market_data(&contract, &[], false, false).unwrap())),
+            OutgoingMessages::CancelMarketData,
+        ),
+    ];
+
+    for (i, (name, subscribe_and_drop, expected_cancel)) in cases.into_iter().enumerate() {
+        subscribe_and_drop();
+
+        let request_messages = client.message_bus.request_messages();
+        assert_eq!(request_messages.len(), 2 * (i + 1), "{name}: expected request and cancel messages");
+
+        let request_id = 9000 + i as i32;
+        let cancel = &request_messages[2 * i + 1];
+        assert_eq!(cancel[0], expected_cancel.to_field(), "{name}: wrong cancel message");
+        assert!(
+            cancel.encode_simple().contains(&format!("|{request_id}|")),
+            "{name}: cancel should reference request id {request_id}"
+        );
+    }
+
+    // Market depth cancel carries the smart depth flag from the request.
+    let request_messages = client.message_bus.request_messages();
+    let cancel_market_depth = request_messages
+        .iter()
+        .find(|m| m[0] == OutgoingMessages::CancelMarketDepth.to_field())
+        .unwrap();
+    assert_eq!(cancel_market_depth[3], true.to_field(), "market depth: wrong smart depth flag");
+}
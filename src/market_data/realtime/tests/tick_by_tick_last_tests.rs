@@ -32,3 +32,43 @@ fn test_tick_by_tick_last() {
     let request = &request_messages[0];
     assert_eq!(request[14], "Last", "Wrong tick type");
 }
+
+#[test]
+fn test_tick_by_tick() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "99|9000|2|1678740829|3895.25|7|2|NASDAQ|Regular|".to_owned(),
+            "99|9000|3|1678740829|3895.00|3895.50|5|8|1|".to_owned(),
+            "99|9000|4|1678740829|3895.25|".to_owned(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::TICK_BY_TICK_IGNORE_SIZE);
+    let contract = contract_samples::simple_future();
+
+    let ticks = client
+        .tick_by_tick(&contract, TickByTickType::AllLast, 0, false)
+        .expect("Failed to request tick-by-tick data");
+    let received: Vec<TickByTick> = ticks.iter().take(3).collect();
+
+    assert_eq!(received.len(), 3, "Should receive 3 ticks");
+    assert!(
+        matches!(&received[0], TickByTick::Trade(trade) if trade.tick_type == "2"),
+        "{:?}",
+        received[0]
+    );
+    assert!(
+        matches!(&received[1], TickByTick::BidAsk(bid_ask) if bid_ask.ask_price == 3895.50),
+        "{:?}",
+        received[1]
+    );
+    assert!(
+        matches!(&received[2], TickByTick::MidPoint(mid_point) if mid_point.mid_point == 3895.25),
+        "{:?}",
+        received[2]
+    );
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(request_messages[0][14], "AllLast", "Wrong tick type");
+}
@@ -12,7 +12,7 @@ pub(crate) mod shared_channel_configuration;
 #[cfg(test)]
 mod tests;
 
-const INFINITY_STR: &str = "Infinity";
+pub(crate) const INFINITY_STR: &str = "Infinity";
 const UNSET_DOUBLE: &str = "1.7976931348623157E308";
 const UNSET_INTEGER: &str = "2147483647";
 const UNSET_LONG: &str = "9223372036854775807";
@@ -229,6 +229,7 @@ pub fn request_id_index(kind: IncomingMessages) -> Option<usize> {
         IncomingMessages::HistoricalTickLast => Some(1),
         IncomingMessages::MarketDepth => Some(2),
         IncomingMessages::MarketDepthL2 => Some(2),
+        IncomingMessages::MarketDataType => Some(2),
         IncomingMessages::NewsArticle => Some(1),
         IncomingMessages::OpenOrder => Some(1),
         IncomingMessages::PnL => Some(1),
@@ -237,6 +238,7 @@ pub fn request_id_index(kind: IncomingMessages) -> Option<usize> {
         IncomingMessages::ScannerData => Some(2),
         IncomingMessages::SecurityDefinitionOptionParameter => Some(1),
         IncomingMessages::SecurityDefinitionOptionParameterEnd => Some(1),
+        IncomingMessages::SmartComponents => Some(1),
         IncomingMessages::SymbolSamples => Some(1),
         IncomingMessages::TickByTick => Some(1),
         IncomingMessages::TickEFP => Some(2),
@@ -439,8 +441,8 @@ impl ResponseMessage {
     }
 
     pub fn peek_int(&self, i: usize) -> Result<i32, Error> {
-        if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected int and found end of message".into()));
+        if i >= self.fields.len() {
+            return Err(self.end_of_message(i));
         }
 
         let field = &self.fields[i];
@@ -456,7 +458,7 @@ impl ResponseMessage {
 
     pub fn next_int(&mut self) -> Result<i32, Error> {
         if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected int and found end of message".into()));
+            return Err(self.end_of_message(self.i));
         }
 
         let field = &self.fields[self.i];
@@ -470,7 +472,7 @@ impl ResponseMessage {
 
     pub fn next_optional_int(&mut self) -> Result<Option<i32>, Error> {
         if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected optional int and found end of message".into()));
+            return Err(self.end_of_message(self.i));
         }
 
         let field = &self.fields[self.i];
@@ -488,7 +490,7 @@ impl ResponseMessage {
 
     pub fn next_bool(&mut self) -> Result<bool, Error> {
         if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected bool and found end of message".into()));
+            return Err(self.end_of_message(self.i));
         }
 
         let field = &self.fields[self.i];
@@ -499,7 +501,7 @@ impl ResponseMessage {
 
     pub fn next_long(&mut self) -> Result<i64, Error> {
         if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected long and found end of message".into()));
+            return Err(self.end_of_message(self.i));
         }
 
         let field = &self.fields[self.i];
@@ -513,7 +515,7 @@ impl ResponseMessage {
 
     pub fn next_optional_long(&mut self) -> Result<Option<i64>, Error> {
         if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected optional long and found end of message".into()));
+            return Err(self.end_of_message(self.i));
         }
 
         let field = &self.fields[self.i];
@@ -531,7 +533,7 @@ impl ResponseMessage {
 
     pub fn next_date_time(&mut self) -> Result<OffsetDateTime, Error> {
         if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected datetime and found end of message".into()));
+            return Err(self.end_of_message(self.i));
         }
 
         let field = &self.fields[self.i];
@@ -551,7 +553,7 @@ impl ResponseMessage {
 
     pub fn next_string(&mut self) -> Result<String, Error> {
         if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected string and found end of message".into()));
+            return Err(self.end_of_message(self.i));
         }
 
         let field = &self.fields[self.i];
@@ -559,9 +561,26 @@ impl ResponseMessage {
         Ok(String::from(field))
     }
 
+    // Reads the next field as a string, or an empty string when reading past the end of the message.
+    // Used for optional trailing fields that some gateway builds omit.
+    pub(crate) fn next_string_or_default(&mut self) -> String {
+        if self.i >= self.fields.len() {
+            return String::default();
+        }
+
+        let field = &self.fields[self.i];
+        self.i += 1;
+        String::from(field)
+    }
+
+    // Whether every field of the message has been read.
+    pub(crate) fn is_exhausted(&self) -> bool {
+        self.i >= self.fields.len()
+    }
+
     pub fn next_double(&mut self) -> Result<f64, Error> {
         if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected double and found end of message".into()));
+            return Err(self.end_of_message(self.i));
         }
 
         let field = &self.fields[self.i];
@@ -579,7 +598,7 @@ impl ResponseMessage {
 
     pub fn next_optional_double(&mut self) -> Result<Option<f64>, Error> {
         if self.i >= self.fields.len() {
-            return Err(Error::Simple("expected optional double and found end of message".into()));
+            return Err(self.end_of_message(self.i));
         }
 
         let field = &self.fields[self.i];
@@ -616,6 +635,15 @@ impl ResponseMessage {
         self.i += 1;
     }
 
+    // Error for reading field_index past the last field of the message.
+    fn end_of_message(&self, field_index: usize) -> Error {
+        Error::ParseError {
+            field_index,
+            message_type: self.message_type(),
+            fields_len: self.fields.len(),
+        }
+    }
+
     pub fn encode(&self) -> String {
         let mut data = self.fields.join("\0");
         data.push('\0');
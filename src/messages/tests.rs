@@ -285,3 +285,30 @@ fn test_notice() {
     assert_eq!(notice.message, "HMDS data farm connection is inactive.");
     assert_eq!(format!("{notice}"), "[2107] HMDS data farm connection is inactive.");
 }
+
+#[test]
+fn test_truncated_message() {
+    let mut message = ResponseMessage::from_simple("9|1|90");
+
+    assert_eq!(message.next_int().unwrap(), 9);
+    assert_eq!(message.next_int().unwrap(), 1);
+    assert_eq!(message.next_int().unwrap(), 90);
+
+    match message.next_string() {
+        Err(Error::ParseError {
+            field_index,
+            message_type,
+            fields_len,
+        }) => {
+            assert_eq!(field_index, 3, "field_index");
+            assert_eq!(message_type, IncomingMessages::NextValidId, "message_type");
+            assert_eq!(fields_len, 3, "fields_len");
+        }
+        other => panic!("expected end of message error, got {other:?}"),
+    }
+
+    let error = message.next_double().unwrap_err();
+    assert_eq!(error.to_string(), "parse error: end of message at field 3 of NextValidId with 3 fields");
+
+    assert!(matches!(message.peek_int(5), Err(Error::ParseError { field_index: 5, .. })));
+}
@@ -5,10 +5,11 @@ use crate::{
     messages::{IncomingMessages, OutgoingMessages, RequestMessage, ResponseMessage},
     server_versions, Client, Error,
 };
+use base64::prelude::{Engine, BASE64_STANDARD};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
-mod decoders;
+pub(crate) mod decoders;
 mod encoders;
 
 #[cfg(test)]
@@ -153,6 +154,32 @@ pub struct NewsArticleBody {
     article_text: String,
 }
 
+impl NewsArticleBody {
+    /// Returns the raw bytes of the article body.
+    ///
+    /// [ArticleType::Text] articles are returned as UTF-8 bytes. [ArticleType::Binary] articles (e.g. PDF) are decoded from Base64.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use ibapi::Client;
+    ///
+    /// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+    ///
+    /// let article = client.news_article("DJ-RT", "DJ-RT$1234").expect("request news article failed");
+    /// let bytes = article.decoded_bytes().expect("invalid article body");
+    /// std::fs::write("article.pdf", bytes).expect("failed to save article");
+    /// ```
+    pub fn decoded_bytes(&self) -> Result<Vec<u8>, Error> {
+        match self.article_type {
+            ArticleType::Text => Ok(self.article_text.as_bytes().to_vec()),
+            ArticleType::Binary => BASE64_STANDARD
+                .decode(self.article_text.trim())
+                .map_err(|err| Error::Simple(format!("invalid base64 in binary news article: {err}"))),
+        }
+    }
+}
+
 pub(super) fn news_article(client: &Client, provider_code: &str, article_id: &str) -> Result<NewsArticleBody, Error> {
     client.check_server_version(server_versions::REQ_NEWS_ARTICLE, "It does not support news article requests.")?;
 
@@ -70,7 +70,7 @@ pub(super) fn decode_news_article(mut message: ResponseMessage) -> Result<NewsAr
     })
 }
 
-pub(super) fn decode_tick_news(mut message: ResponseMessage) -> Result<NewsArticle, Error> {
+pub(crate) fn decode_tick_news(mut message: ResponseMessage) -> Result<NewsArticle, Error> {
     message.skip(); // message type
     message.skip(); // request id
 
@@ -1,4 +1,10 @@
-use crate::{contracts::Contract, news::ArticleType, server_versions, stubs::MessageBusStub, Client};
+use crate::{
+    contracts::Contract,
+    news::{ArticleType, NewsArticleBody},
+    server_versions,
+    stubs::MessageBusStub,
+    Client,
+};
 use std::sync::{Arc, RwLock};
 use time::macros::datetime;
 
@@ -92,6 +98,40 @@ fn test_historical_news() {
     }
 }
 
+#[test]
+fn test_historical_news_streams_until_end() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "86|9000|2024-12-23 19:45:00.0|BRFG|BRFG$1|First headline|".to_owned(),
+            "86|9000|2024-12-23 19:50:00.0|DJ-N|DJ-N$2|Second headline|".to_owned(),
+            "86|9000|2024-12-23 19:55:00.0|DJ-RT|DJ-RT$3|Third headline|".to_owned(),
+            "87|9000|0|".to_owned(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let start_time = datetime!(2024-12-23 0:00 UTC);
+    let end_time = datetime!(2024-12-24 0:00 UTC);
+
+    let subscription = client
+        .historical_news(76792991, &["BRFG", "DJ-N", "DJ-RT"], start_time, end_time, 10)
+        .expect("failed to request historical news");
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(
+        request_messages[0].encode_simple(),
+        "86|9000|76792991|BRFG+DJ-N+DJ-RT|20241223 00:00:00 UTC|20241224 00:00:00 UTC|10||"
+    );
+
+    let article_ids: Vec<String> = subscription.iter().map(|article| article.article_id).collect();
+    assert_eq!(article_ids, vec!["BRFG$1", "DJ-N$2", "DJ-RT$3"]);
+
+    assert!(subscription.next().is_none(), "expected stream to end after historical news end");
+    assert!(subscription.error().is_none(), "unexpected error: {:?}", subscription.error());
+}
+
 #[test]
 fn test_news_article() {
     let message_bus = Arc::new(MessageBusStub {
@@ -166,3 +206,26 @@ fn test_broad_tape_news() {
         panic!("Expected news article");
     }
 }
+
+#[test]
+fn test_news_article_decoded_bytes() {
+    let text = NewsArticleBody {
+        article_type: ArticleType::Text,
+        article_text: "<p>Article text</p>".to_owned(),
+    };
+    assert_eq!(text.decoded_bytes().unwrap(), b"<p>Article text</p>");
+
+    // "%PDF-1.4" encoded as base64
+    let binary = NewsArticleBody {
+        article_type: ArticleType::Binary,
+        article_text: "JVBERi0xLjQ=".to_owned(),
+    };
+    assert_eq!(binary.decoded_bytes().unwrap(), b"%PDF-1.4");
+
+    let malformed = NewsArticleBody {
+        article_type: ArticleType::Binary,
+        article_text: "not base64!".to_owned(),
+    };
+    let err = malformed.decoded_bytes().expect_err("expected malformed base64 to fail");
+    assert!(err.to_string().contains("invalid base64"), "unexpected error: {err}");
+}
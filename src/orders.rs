@@ -1,11 +1,17 @@
+use std::collections::{HashMap, HashSet};
 use std::convert::From;
 use std::fmt::Debug;
+use std::time::{Duration, Instant};
 
+use log::warn;
 use serde::{Deserialize, Serialize};
-use time::OffsetDateTime;
+use time::macros::format_description;
+use time::{OffsetDateTime, PrimitiveDateTime};
+use time_tz::{timezones, OffsetDateTimeExt, PrimitiveDateTimeExt, TimeZone, Tz};
 
 use crate::client::{DataStream, ResponseContext, Subscription};
 use crate::contracts::{ComboLeg, ComboLegOpenClose, Contract, DeltaNeutralContract, SecurityType};
+use crate::errors::is_informational_code;
 use crate::messages::{IncomingMessages, Notice, OutgoingMessages};
 use crate::messages::{RequestMessage, ResponseMessage};
 use crate::Client;
@@ -28,6 +34,7 @@ pub use crate::contracts::TagValue;
 const COMPETE_AGAINST_BEST_OFFSET_UP_TO_MID: Option<f64> = Some(f64::INFINITY);
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 /// Order describes the order.
 pub struct Order {
     /// The API client's order id.
@@ -76,6 +83,7 @@ pub struct Order {
     /// 2 - Remaining orders are proportionately reduced in size with block.
     /// 3 - Remaining orders are proportionately reduced in size with no block.
     /// If you use a value "with block" it gives the order overfill protection. This means that only one order in the group will be routed at a time to remove the possibility of an overfill.
+    /// See [Order::oca_type_value] and [Order::set_oca_type] for the typed form.
     pub oca_type: i32,
     /// The order reference.
     /// Intended for institutional customers only, although all customers may use it to identify the API client that sent the order when multiple API clients are running.
@@ -157,6 +165,7 @@ pub struct Order {
     pub designated_location: String,
     /// Only available with IB Execution-Only accounts with applicable securities.
     /// Mark order as exempt from short sale uptick rule.
+    /// Defaults to -1, meaning no exemption; 0 or above is sent as the exemption code. See [OrderBuilder::short_sale_exempt](order_builder::OrderBuilder::short_sale_exempt).
     pub exempt_code: i32,
     /// The amount off the limit price allowed for discretionary orders.
     pub discretionary_amt: f64,
@@ -170,7 +179,8 @@ pub struct Order {
     /// 1 - Match
     /// 2 - Improvement
     /// 3 - Transparent.
-    pub auction_strategy: Option<i32>, // FIXME enum
+    /// See [AuctionStrategy] and [OrderBuilder::auction_strategy](order_builder::OrderBuilder::auction_strategy).
+    pub auction_strategy: Option<i32>,
     /// The auction's starting price. For BOX orders only.
     pub starting_price: Option<f64>,
     /// The stock's reference price.
@@ -190,7 +200,8 @@ pub struct Order {
     /// Values include:
     /// 1 - Daily Volatility
     /// 2 - Annual Volatility.
-    pub volatility_type: Option<i32>, // FIXM enum
+    /// See [VolatilityType] and [OrderBuilder::volatility](order_builder::OrderBuilder::volatility).
+    pub volatility_type: Option<i32>,
     /// Specifies whether TWS will automatically update the limit price of the order as the underlying price moves. VOL orders only.
     pub continuous_update: bool,
     /// Specifies how you want TWS to calculate the limit price for options, and for stock range price monitoring.
@@ -198,6 +209,7 @@ pub struct Order {
     /// Valid values include:
     /// 1 - Average of NBBO
     /// 2 - NBB or the NBO depending on the action and right.
+    /// See [ReferencePriceType] and [OrderBuilder::reference_price_type](order_builder::OrderBuilder::reference_price_type).
     pub reference_price_type: Option<i32>,
     /// Enter an order type to instruct TWS to submit a delta neutral trade on full or partial execution of the VOL order. VOL orders only. For no hedge delta order to be sent, specify NONE.
     pub delta_neutral_order_type: String,
@@ -334,7 +346,8 @@ pub struct Order {
     pub model_code: String,
     /// This is a regulatory attribute that applies to all US Commodity (Futures) Exchanges, provided to allow client to comply with CFTC Tag 50 Rules.
     pub ext_operator: String,
-    /// The native cash quantity.
+    /// The native cash quantity. IB computes the number of shares from it, so `total_quantity` must be zero when it is set.
+    /// Supported for stock, forex, crypto and mutual fund contracts.
     pub cash_qty: Option<f64>,
     /// Identifies a person as the responsible party for investment decisions within the firm. Orders covered by MiFID 2 (Markets in Financial Instruments Directive 2) must include either Mifid2DecisionMaker or Mifid2DecisionAlgo field (but not both). Requires TWS 969+.
     pub mifid2_decision_maker: String,
@@ -578,6 +591,71 @@ impl Order {
             _ => false,
         }
     }
+
+    /// Returns [Order::oca_type] as an [OcaType], or None for orders outside an OCA group and unknown codes.
+    pub fn oca_type_value(&self) -> Option<OcaType> {
+        OcaType::from(self.oca_type)
+    }
+
+    /// Sets [Order::oca_type] from an [OcaType].
+    pub fn set_oca_type(&mut self, oca_type: OcaType) {
+        self.oca_type = oca_type as i32;
+    }
+}
+
+/// [Order] tagged with the version of its serialized form, for orders that are persisted.
+///
+/// Serializes as `{"version": 2, "order": {...}}`. Deserializing also accepts an [Order] serialized without the wrapper,
+/// which is treated as version 1. Fields missing from older versions take their [Order::default] values.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct VersionedOrder {
+    /// Version of the serialized form.
+    pub version: u32,
+    /// The order.
+    pub order: Order,
+}
+
+impl VersionedOrder {
+    /// Version written by this release.
+    pub const CURRENT_VERSION: u32 = 2;
+
+    /// Wraps an order at the current version.
+    pub fn new(order: Order) -> Self {
+        Self {
+            version: Self::CURRENT_VERSION,
+            order,
+        }
+    }
+
+    /// Returns the order migrated to the current version.
+    pub fn into_order(self) -> Order {
+        self.order
+    }
+}
+
+impl From<Order> for VersionedOrder {
+    fn from(order: Order) -> Self {
+        Self::new(order)
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionedOrder {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum SerializedOrder {
+            Versioned { version: u32, order: Order },
+            Unversioned(Order),
+        }
+
+        match SerializedOrder::deserialize(deserializer)? {
+            SerializedOrder::Versioned { version, .. } if version == 0 || version > VersionedOrder::CURRENT_VERSION => Err(serde::de::Error::custom(
+                format!("unsupported order version {version}, expected 1 to {}", VersionedOrder::CURRENT_VERSION),
+            )),
+            // Versions 1 and 2 share the Order layout; missing fields are filled in by serde defaults.
+            SerializedOrder::Versioned { order, .. } | SerializedOrder::Unversioned(order) => Ok(VersionedOrder::new(order)),
+        }
+    }
 }
 
 /// Identifies the side.
@@ -697,10 +775,108 @@ impl Rule80A {
     }
 }
 
+/// How the remaining orders in a One-Cancels-All group are handled when one order or part of an order executes.
+///
+/// A type "with block" gives the orders overfill protection: only one order in the group is routed at a time,
+/// removing the possibility of an overfill.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OcaType {
+    /// Cancel all remaining orders with block.
+    CancelWithBlock = 1,
+    /// Remaining orders are proportionately reduced in size with block.
+    ReduceWithBlock = 2,
+    /// Remaining orders are proportionately reduced in size with no block.
+    ReduceNoBlock = 3,
+}
+
+impl ToField for OcaType {
+    fn to_field(&self) -> String {
+        (*self as i32).to_string()
+    }
+}
+
+impl OcaType {
+    pub fn from(source: i32) -> Option<Self> {
+        match source {
+            1 => Some(OcaType::CancelWithBlock),
+            2 => Some(OcaType::ReduceWithBlock),
+            3 => Some(OcaType::ReduceNoBlock),
+            _ => None,
+        }
+    }
+}
+
+/// Strategy of a BOX price improvement auction order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AuctionStrategy {
-    Match,
-    Improvement,
-    Transparent,
+    Match = 1,
+    Improvement = 2,
+    Transparent = 3,
+}
+
+impl ToField for AuctionStrategy {
+    fn to_field(&self) -> String {
+        (*self as i32).to_string()
+    }
+}
+
+impl AuctionStrategy {
+    pub fn from(value: i32) -> Option<Self> {
+        match value {
+            1 => Some(AuctionStrategy::Match),
+            2 => Some(AuctionStrategy::Improvement),
+            3 => Some(AuctionStrategy::Transparent),
+            _ => None,
+        }
+    }
+}
+
+/// Period over which the volatility of a VOL order is expressed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VolatilityType {
+    Daily = 1,
+    Annual = 2,
+}
+
+impl ToField for VolatilityType {
+    fn to_field(&self) -> String {
+        (*self as i32).to_string()
+    }
+}
+
+impl VolatilityType {
+    pub fn from(value: i32) -> Option<Self> {
+        match value {
+            1 => Some(VolatilityType::Daily),
+            2 => Some(VolatilityType::Annual),
+            _ => None,
+        }
+    }
+}
+
+/// Price TWS uses to calculate the limit price of a VOL order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReferencePriceType {
+    /// Average of the national best bid and offer.
+    Midpoint = 1,
+    /// National best bid or offer, depending on the action and right.
+    BidOrAsk = 2,
+}
+
+impl ToField for ReferencePriceType {
+    fn to_field(&self) -> String {
+        (*self as i32).to_string()
+    }
+}
+
+impl ReferencePriceType {
+    pub fn from(value: i32) -> Option<Self> {
+        match value {
+            1 => Some(ReferencePriceType::Midpoint),
+            2 => Some(ReferencePriceType::BidOrAsk),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
@@ -767,7 +943,7 @@ pub struct OrderData {
 /// Provides an active order's current state.
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct OrderState {
-    /// The order's current status
+    /// The order's current status. See [OrderState::status_value] for the typed value.
     pub status: String,
     /// The account's current initial margin.
     pub initial_margin_before: Option<f64>,
@@ -797,10 +973,94 @@ pub struct OrderState {
     pub commission_currency: String,
     /// If the order is warranted, a descriptive message will be provided.
     pub warning_text: String,
+    /// Time the order completed, e.g. "20230306 12:28:30 America/Los_Angeles". Only set for completed orders.
     pub completed_time: String,
+    /// Completion status of the order, e.g. "Filled Size: 100". Only set for completed orders.
     pub completed_status: String,
 }
 
+impl OrderState {
+    /// The [status](OrderState::status) as an [OrderStatusValue].
+    pub fn status_value(&self) -> OrderStatusValue {
+        OrderStatusValue::from(&self.status)
+    }
+
+    /// Parses [completed_time](OrderState::completed_time) into an [OffsetDateTime].
+    ///
+    /// Returns `None` if the order has no completed time or the value is not in the format `yyyymmdd hh:mm:ss {time zone}`.
+    pub fn parsed_completed_time(&self) -> Option<OffsetDateTime> {
+        let mut parts = self.completed_time.split_whitespace();
+        let (date, time, zone) = (parts.next()?, parts.next()?, parts.next()?);
+
+        let time_zone = timezones::get_by_name(zone).or_else(|| timezones::find_by_name(zone).first().copied())?;
+
+        let format = format_description!("[year][month][day] [hour]:[minute]:[second]");
+        let completed_at = PrimitiveDateTime::parse(&format!("{date} {time}"), format).ok()?;
+
+        completed_at.assume_timezone(time_zone).take_first()
+    }
+}
+
+/// A date and time formatted for TWS, for use as [Order::good_till_date] or [Order::good_after_time].
+///
+/// TWS accepts either local time with a time zone, `yyyyMMdd HH:mm:ss {time zone}`, or UTC, `yyyyMMdd-HH:mm:ss`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerTimestamp(String);
+
+impl ServerTimestamp {
+    /// Formats `datetime` as local time in `time_zone`, e.g. "20240315 16:00:00 America/New_York".
+    pub fn from_datetime(datetime: OffsetDateTime, time_zone: &Tz) -> Self {
+        let local = datetime.to_timezone(time_zone);
+        let format = format_description!("[year][month][day] [hour]:[minute]:[second]");
+        Self(format!("{} {}", local.format(format).unwrap(), time_zone.name()))
+    }
+
+    /// Formats `datetime` as UTC, e.g. "20240315-20:00:00".
+    pub fn utc(datetime: OffsetDateTime) -> Self {
+        let utc = datetime.to_offset(time::UtcOffset::UTC);
+        let format = format_description!("[year][month][day]-[hour]:[minute]:[second]");
+        Self(utc.format(format).unwrap())
+    }
+
+    /// Validates a timestamp string in one of the formats accepted by TWS.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let invalid = || {
+            Error::InvalidArgument(format!(
+                "invalid timestamp {text:?}, expected \"yyyyMMdd HH:mm:ss [time zone]\" or \"yyyyMMdd-HH:mm:ss\""
+            ))
+        };
+
+        if PrimitiveDateTime::parse(text, format_description!("[year][month][day]-[hour]:[minute]:[second]")).is_ok() {
+            return Ok(Self(text.to_owned()));
+        }
+
+        let mut parts = text.splitn(3, ' ');
+        let (date, time) = (parts.next().ok_or_else(invalid)?, parts.next().ok_or_else(invalid)?);
+
+        let format = format_description!("[year][month][day] [hour]:[minute]:[second]");
+        PrimitiveDateTime::parse(&format!("{date} {time}"), format).map_err(|_| invalid())?;
+
+        if let Some(zone) = parts.next() {
+            if timezones::get_by_name(zone).is_none() && timezones::find_by_name(zone).is_empty() {
+                return Err(Error::InvalidArgument(format!("unknown time zone in timestamp {text:?}")));
+            }
+        }
+
+        Ok(Self(text.to_owned()))
+    }
+
+    /// The formatted timestamp.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for ServerTimestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// For institutional customers only. Valid values are O (open) and C (close).
 /// Available for institutional clients to determine if this order is to open or close a position.
 /// When Action = "BUY" and OpenClose = "O" this will open a new position.
@@ -844,6 +1104,46 @@ impl OrderOpenClose {
     }
 }
 
+/// Where execution-only clients want their trades cleared. See [Order::clearing_intent].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClearingIntent {
+    /// Cleared at IB.
+    IB,
+    /// Cleared away from IB.
+    Away,
+    /// Post trade allocation.
+    PTA,
+}
+
+impl ToField for ClearingIntent {
+    fn to_field(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl std::fmt::Display for ClearingIntent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            ClearingIntent::IB => "IB",
+            ClearingIntent::Away => "Away",
+            ClearingIntent::PTA => "PTA",
+        };
+
+        write!(f, "{text}")
+    }
+}
+
+impl ClearingIntent {
+    pub fn from(source: &str) -> Option<Self> {
+        match source {
+            "IB" => Some(ClearingIntent::IB),
+            "Away" => Some(ClearingIntent::Away),
+            "PTA" => Some(ClearingIntent::PTA),
+            _ => None,
+        }
+    }
+}
+
 /// Represents the commission generated by an execution.
 #[derive(Clone, Debug, Default)]
 pub struct CommissionReport {
@@ -861,6 +1161,46 @@ pub struct CommissionReport {
     pub yield_redemption_date: String,
 }
 
+impl CommissionReport {
+    /// Totals the commissions of `reports` in `base_currency`.
+    ///
+    /// # Arguments
+    /// * `reports` - commission reports, e.g. collected from [Client::executions](crate::Client::executions).
+    /// * `base_currency` - currency to total in. Commissions in this currency are added as is.
+    /// * `rates` - exchange rates by currency, in units of `base_currency` per unit of the currency.
+    ///
+    /// Commissions in currencies without a rate are not converted. They are totaled by currency in [CommissionTotal::unconverted].
+    pub fn total<'a>(reports: impl IntoIterator<Item = &'a CommissionReport>, base_currency: &str, rates: &HashMap<String, f64>) -> CommissionTotal {
+        let mut total = CommissionTotal {
+            currency: base_currency.to_owned(),
+            ..Default::default()
+        };
+
+        for report in reports {
+            if report.currency == base_currency {
+                total.commission += report.commission;
+            } else if let Some(rate) = rates.get(&report.currency) {
+                total.commission += report.commission * rate;
+            } else {
+                *total.unconverted.entry(report.currency.clone()).or_default() += report.commission;
+            }
+        }
+
+        total
+    }
+}
+
+/// Commissions totaled in a base currency. See [CommissionReport::total].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommissionTotal {
+    /// The base currency.
+    pub currency: String,
+    /// Total of the commissions converted to the base currency.
+    pub commission: f64,
+    /// Total commissions by currency, for currencies without an exchange rate.
+    pub unconverted: HashMap<String, f64>,
+}
+
 #[derive(Clone, Debug, Default, PartialEq)]
 pub enum Liquidity {
     #[default]
@@ -982,24 +1322,491 @@ pub struct OrderStatus {
     pub market_cap_price: f64,
 }
 
+impl OrderStatus {
+    /// The [status](Self::status) as an [OrderStatusValue].
+    pub fn status_value(&self) -> OrderStatusValue {
+        OrderStatusValue::from(&self.status)
+    }
+
+    /// Returns true if the order is filled, cancelled or inactive and will receive no further updates.
+    pub fn is_terminal(&self) -> bool {
+        self.status_value().is_terminal()
+    }
+}
+
+/// Typed value of [OrderStatus::status] and [OrderState::status]. See [OrderStatus::status] for a description of each status.
+///
+/// Statuses not known to this version of the library are kept in [Other](Self::Other).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OrderStatusValue {
+    ApiPending,
+    PendingSubmit,
+    PendingCancel,
+    PreSubmitted,
+    Submitted,
+    ApiCancelled,
+    Cancelled,
+    Filled,
+    Inactive,
+    Other(String),
+}
+
+impl OrderStatusValue {
+    pub fn from(status: &str) -> Self {
+        match status {
+            "ApiPending" => Self::ApiPending,
+            "PendingSubmit" => Self::PendingSubmit,
+            "PendingCancel" => Self::PendingCancel,
+            "PreSubmitted" => Self::PreSubmitted,
+            "Submitted" => Self::Submitted,
+            "ApiCancelled" => Self::ApiCancelled,
+            "Cancelled" => Self::Cancelled,
+            "Filled" => Self::Filled,
+            "Inactive" => Self::Inactive,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+
+    /// Returns true for Filled, Cancelled, ApiCancelled and Inactive.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Filled | Self::Cancelled | Self::ApiCancelled | Self::Inactive)
+    }
+}
+
+impl std::fmt::Display for OrderStatusValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Self::ApiPending => "ApiPending",
+            Self::PendingSubmit => "PendingSubmit",
+            Self::PendingCancel => "PendingCancel",
+            Self::PreSubmitted => "PreSubmitted",
+            Self::Submitted => "Submitted",
+            Self::ApiCancelled => "ApiCancelled",
+            Self::Cancelled => "Cancelled",
+            Self::Filled => "Filled",
+            Self::Inactive => "Inactive",
+            Self::Other(status) => status,
+        };
+
+        write!(f, "{text}")
+    }
+}
+
+/// Follows an order through its [PlaceOrder] updates, e.g. partial fills followed by a cancel.
+///
+/// Fills are counted from executions, deduplicated by execution id, and from order status updates, whichever
+/// reports more. Commissions are summed from the commission reports of the order's executions. TWS may send a
+/// commission report after the final order status, so tracking continues until every execution has one.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use ibapi::contracts::Contract;
+/// use ibapi::orders::{order_builder, Action, OrderTracker};
+/// use ibapi::Client;
+///
+/// let client = Client::connect("127.0.0.1:4002", 100).expect("connection failed");
+///
+/// let contract = Contract::stock("AAPL");
+/// let order = order_builder::limit_order(Action::Buy, 100.0, 150.0);
+/// let order_id = client.next_order_id();
+/// let subscription = client.place_order(order_id, &contract, &order).expect("place order request failed");
+///
+/// let tracker = OrderTracker::new(order_id, order.total_quantity);
+/// if let Some(summary) = tracker.track_subscription(&subscription, Duration::from_secs(5)) {
+///     println!("{} filled at {} ({} cancelled)", summary.filled, summary.average_price, summary.cancelled);
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct OrderTracker {
+    order_id: i32,
+    total_quantity: f64,
+    status: Option<OrderStatus>,
+    executions: HashMap<String, (f64, f64)>, // execution id -> (shares, price)
+    commission_reports: HashSet<String>,     // execution ids with a commission report
+    commission: f64,
+}
+
+impl OrderTracker {
+    /// Starts tracking the order with the given id and quantity.
+    pub fn new(order_id: i32, total_quantity: f64) -> Self {
+        Self {
+            order_id,
+            total_quantity,
+            ..Default::default()
+        }
+    }
+
+    /// Applies an update. Updates for other orders are ignored.
+    pub fn update(&mut self, update: &PlaceOrder) {
+        match update {
+            PlaceOrder::OrderStatus(status) if status.order_id == self.order_id => self.status = Some(status.clone()),
+            PlaceOrder::ExecutionData(data) if data.execution.order_id == self.order_id => {
+                let execution = &data.execution;
+                self.executions
+                    .insert(execution.execution_id.clone(), (execution.shares, execution.price));
+            }
+            // Counts each execution's report once.
+            PlaceOrder::CommissionReport(report)
+                if self.executions.contains_key(&report.execution_id) && self.commission_reports.insert(report.execution_id.clone()) =>
+            {
+                self.commission += report.commission;
+            }
+            _ => {}
+        }
+    }
+
+    /// Applies updates until the order is done and every execution has a commission report, then returns its summary.
+    /// If the updates end after the order is done but before all commission reports arrived, the summary is returned
+    /// with [OrderSummary::commission_complete] unset. Returns `None` if the updates end before the order is done.
+    ///
+    /// Iterating a [Subscription] blocks until the next update, so a commission report that never arrives blocks forever.
+    /// Use [OrderTracker::track_subscription] to bound the wait.
+    pub fn track(mut self, updates: impl IntoIterator<Item = PlaceOrder>) -> Option<OrderSummary> {
+        for update in updates {
+            self.update(&update);
+            if self.is_done() && self.commission_complete() {
+                break;
+            }
+        }
+        self.summary()
+    }
+
+    /// Like [OrderTracker::track], but waits at most `commission_timeout` for the outstanding commission reports
+    /// once the order is done.
+    pub fn track_subscription(mut self, subscription: &Subscription<PlaceOrder>, commission_timeout: Duration) -> Option<OrderSummary> {
+        while !self.is_done() {
+            self.update(&subscription.next()?);
+        }
+
+        let deadline = Instant::now() + commission_timeout;
+        while !self.commission_complete() {
+            match subscription.next_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Some(update) => self.update(&update),
+                None => break,
+            }
+        }
+
+        self.summary()
+    }
+
+    /// Quantity filled so far.
+    pub fn filled(&self) -> f64 {
+        let executed: f64 = self.executions.values().map(|(shares, _)| shares).sum();
+        let reported = self.status.as_ref().map_or(0.0, |status| status.filled);
+        executed.max(reported)
+    }
+
+    /// Quantity not filled yet.
+    pub fn remaining(&self) -> f64 {
+        (self.total_quantity - self.filled()).max(0.0)
+    }
+
+    /// Average price of the fills so far, or 0 without fills.
+    pub fn average_price(&self) -> f64 {
+        let (shares, notional) = self
+            .executions
+            .values()
+            .fold((0.0, 0.0), |(shares, notional), (qty, price)| (shares + qty, notional + qty * price));
+
+        match &self.status {
+            Some(status) if status.filled > shares => status.average_fill_price,
+            _ if shares > 0.0 => notional / shares,
+            _ => 0.0,
+        }
+    }
+
+    /// Returns true once every execution so far has a commission report.
+    pub fn commission_complete(&self) -> bool {
+        self.executions.keys().all(|execution_id| self.commission_reports.contains(execution_id))
+    }
+
+    /// Returns true once the order is filled, cancelled or inactive.
+    pub fn is_done(&self) -> bool {
+        self.status.as_ref().is_some_and(OrderStatus::is_terminal)
+    }
+
+    /// Returns the summary of a done order, `None` while the order is still working.
+    pub fn summary(&self) -> Option<OrderSummary> {
+        let status = self.status.as_ref().filter(|_| self.is_done())?;
+        let cancelled = if status.status_value() == OrderStatusValue::Filled {
+            0.0
+        } else {
+            self.remaining()
+        };
+
+        Some(OrderSummary {
+            order_id: self.order_id,
+            status: status.status.clone(),
+            filled: self.filled(),
+            average_price: self.average_price(),
+            cancelled,
+            commission: self.commission,
+            commission_complete: self.commission_complete(),
+        })
+    }
+}
+
+/// Final state of an order followed by an [OrderTracker].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OrderSummary {
+    pub order_id: i32,
+    /// Final status: Filled, Cancelled, ApiCancelled or Inactive.
+    pub status: String,
+    /// Total quantity filled.
+    pub filled: f64,
+    /// Average fill price.
+    pub average_price: f64,
+    /// Quantity left unfilled when the order was cancelled or became inactive.
+    pub cancelled: f64,
+    /// Total commissions of the order's executions.
+    pub commission: f64,
+    /// False if some executions had no commission report yet, in which case `commission` is incomplete.
+    pub commission_complete: bool,
+}
+
+/// Order IDs assigned by [Client::place_bracket_order](crate::Client::place_bracket_order).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BracketOrderIds {
+    /// ID of the entry (parent) order.
+    pub parent: i32,
+    /// ID of the take profit order.
+    pub take_profit: i32,
+    /// ID of the stop loss order.
+    pub stop_loss: i32,
+}
+
 // Submits an Order.
 // After the order is submitted correctly, events will be returned concerning the order's activity.
 // https://interactivebrokers.github.io/tws-api/order_submission.html
 pub(crate) fn place_order<'a>(client: &'a Client, order_id: i32, contract: &Contract, order: &Order) -> Result<Subscription<'a, PlaceOrder>, Error> {
+    let request = validate_and_encode_order(client, order_id, contract, order)?;
+    let subscription = client.send_order(order_id, request)?;
+
+    Ok(Subscription::new(client, subscription, ResponseContext::default()))
+}
+
+// Submits an Order without returning the events concerning its activity.
+pub(crate) fn submit_order(client: &Client, order_id: i32, contract: &Contract, order: &Order) -> Result<(), Error> {
+    let request = validate_and_encode_order(client, order_id, contract, order)?;
+    client.send_order(order_id, request)?;
+
+    Ok(())
+}
+
+// Checks the order and contract against the server version and encodes the place order request.
+// Every entry point that submits an order goes through here, so invalid orders are rejected the same way.
+fn validate_and_encode_order(client: &Client, order_id: i32, contract: &Contract, order: &Order) -> Result<RequestMessage, Error> {
     verify_order(client, order, order_id)?;
     verify_order_contract(client, contract, order_id)?;
+    verify_cash_qty(contract, order)?;
+
+    encoders::encode_place_order(client.server_version(), order_id, contract, order)
+}
 
-    let request = encoders::encode_place_order(client.server_version(), order_id, contract, order)?;
+// Places the order and waits up to timeout for the first response. An error notice that TWS sends instead of an order
+// update is returned as Err; any other first response is handed back to the subscription.
+pub(crate) fn try_place_order<'a>(
+    client: &'a Client,
+    order_id: i32,
+    contract: &Contract,
+    order: &Order,
+    timeout: Duration,
+) -> Result<Subscription<'a, PlaceOrder>, Error> {
+    let request = validate_and_encode_order(client, order_id, contract, order)?;
     let subscription = client.send_order(order_id, request)?;
 
+    match subscription.next_timeout(timeout) {
+        Some(Ok(message)) if message.message_type() == IncomingMessages::Error => {
+            let notice = Notice::from(&message);
+            if !is_order_warning(notice.code) {
+                return Err(Error::TwsError {
+                    code: notice.code,
+                    message: notice.message,
+                });
+            }
+            subscription.unread(Ok(message));
+        }
+        Some(Err(e)) => return Err(e),
+        Some(response) => subscription.unread(response),
+        None => {}
+    }
+
     Ok(Subscription::new(client, subscription, ResponseContext::default()))
 }
 
+// Warnings (2100-2199) and order warnings (399) accompany orders that were accepted.
+fn is_order_warning(code: i32) -> bool {
+    is_informational_code(code) || code == 399
+}
+
+// Same request as place_order, but refuses IDs that cannot refer to an existing order.
+pub(crate) fn modify_order<'a>(client: &'a Client, order_id: i32, contract: &Contract, order: &Order) -> Result<Subscription<'a, PlaceOrder>, Error> {
+    if order_id <= 0 {
+        return Err(Error::InvalidArgument(format!(
+            "modify_order requires the id of an existing order, got {order_id}"
+        )));
+    }
+
+    place_order(client, order_id, contract, order)
+}
+
+// Links the take profit and stop loss to the entry under new order IDs and submits all three.
+// Only the stop loss is transmitted, which releases the entry and take profit along with it.
+pub(crate) fn place_bracket_order(
+    client: &Client,
+    contract: &Contract,
+    entry: &Order,
+    take_profit: &Order,
+    stop_loss: &Order,
+) -> Result<BracketOrderIds, Error> {
+    verify_bracket_prices(entry, take_profit, stop_loss)?;
+
+    let ids = BracketOrderIds {
+        parent: client.next_order_id(),
+        take_profit: client.next_order_id(),
+        stop_loss: client.next_order_id(),
+    };
+
+    let legs = [
+        (ids.parent, entry, 0, false),
+        (ids.take_profit, take_profit, ids.parent, false),
+        (ids.stop_loss, stop_loss, ids.parent, true),
+    ];
+
+    // Encode every leg before sending any, so an invalid child never leaves an untransmitted parent behind.
+    let requests = legs
+        .into_iter()
+        .map(|(order_id, leg, parent_id, transmit)| {
+            let order = Order {
+                order_id,
+                parent_id,
+                transmit,
+                ..leg.clone()
+            };
+            validate_and_encode_order(client, order_id, contract, &order).map(|request| (order_id, request))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    for (order_id, request) in requests {
+        client.send_order(order_id, request)?;
+    }
+
+    Ok(ids)
+}
+
+// Stamps the group name and type on every order and submits them under new order IDs.
+pub(crate) fn place_oca_group(client: &Client, group_name: &str, oca_type: OcaType, orders: Vec<(Contract, Order)>) -> Result<Vec<i32>, Error> {
+    if group_name.is_empty() {
+        return Err(Error::InvalidArgument("OCA group name must not be empty".into()));
+    }
+
+    // Encode every order before sending any, so an invalid order never leaves a partial group behind.
+    let requests = orders
+        .into_iter()
+        .map(|(contract, order)| {
+            let order_id = client.next_order_id();
+            let order = Order {
+                order_id,
+                oca_group: group_name.to_owned(),
+                oca_type: oca_type as i32,
+                ..order
+            };
+            validate_and_encode_order(client, order_id, &contract, &order).map(|request| (order_id, request))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let mut order_ids = Vec::with_capacity(requests.len());
+    for (order_id, request) in requests {
+        client.send_order(order_id, request)?;
+        order_ids.push(order_id);
+    }
+
+    Ok(order_ids)
+}
+
+// The take profit must be on the profitable side of the entry and the stop loss on the losing side.
+// A market entry has no price to check against.
+fn verify_bracket_prices(entry: &Order, take_profit: &Order, stop_loss: &Order) -> Result<(), Error> {
+    let Some(take_profit_price) = take_profit.limit_price else {
+        return Err(Error::InvalidArgument("take profit order requires a limit price".into()));
+    };
+    let Some(stop_loss_price) = stop_loss.aux_price.or(stop_loss.limit_price) else {
+        return Err(Error::InvalidArgument("stop loss order requires a stop price".into()));
+    };
+    let Some(entry_price) = entry.limit_price else {
+        return Ok(());
+    };
+
+    let (profit_side, loss_side) = match entry.action {
+        Action::Buy => (take_profit_price > entry_price, stop_loss_price < entry_price),
+        _ => (take_profit_price < entry_price, stop_loss_price > entry_price),
+    };
+
+    if !profit_side {
+        return Err(Error::InvalidArgument(format!(
+            "take profit price {take_profit_price} is on the wrong side of the {} entry price {entry_price}",
+            entry.action
+        )));
+    }
+
+    if !loss_side {
+        return Err(Error::InvalidArgument(format!(
+            "stop loss price {stop_loss_price} is on the wrong side of the {} entry price {entry_price}",
+            entry.action
+        )));
+    }
+
+    Ok(())
+}
+
+// Previews each leg as a standalone what-if order under a fresh order ID and collects the returned order states.
+pub(crate) fn what_if_bracket(client: &Client, contract: &Contract, orders: &[Order]) -> Result<Vec<OrderState>, Error> {
+    client.check_server_version(server_versions::WHAT_IF_ORDERS, "It does not support what-if orders.")?;
+
+    orders.iter().map(|leg| what_if_leg(client, contract, leg)).collect()
+}
+
+fn what_if_leg(client: &Client, contract: &Contract, leg: &Order) -> Result<OrderState, Error> {
+    let order_id = client.next_order_id();
+
+    // Children reference a parent that is never placed, so each leg is checked on its own.
+    let order = Order {
+        order_id,
+        parent_id: 0,
+        what_if: true,
+        transmit: true,
+        ..leg.clone()
+    };
+
+    let subscription = place_order(client, order_id, contract, &order)?;
+    while let Some(event) = subscription.next() {
+        match event {
+            PlaceOrder::OpenOrder(data) if data.order_id == order_id => return Ok(data.order_state),
+            PlaceOrder::Message(notice) if !is_order_warning(notice.code) => {
+                return Err(Error::TwsError {
+                    code: notice.code,
+                    message: notice.message,
+                })
+            }
+            _ => {}
+        }
+    }
+
+    match subscription.error() {
+        Some(e) => Err(e),
+        None => Err(Error::UnexpectedEndOfStream),
+    }
+}
+
 impl DataStream<PlaceOrder> for PlaceOrder {
     fn decode(client: &Client, message: &mut ResponseMessage) -> Result<PlaceOrder, Error> {
         match message.message_type() {
             IncomingMessages::OpenOrder => Ok(PlaceOrder::OpenOrder(decoders::decode_open_order(
                 client.server_version,
+                client.lenient_decoding(),
                 message.clone(),
             )?)),
             IncomingMessages::OrderStatus => Ok(PlaceOrder::OrderStatus(decoders::decode_order_status(client.server_version, message)?)),
@@ -1029,6 +1836,10 @@ fn verify_order(client: &Client, order: &Order, _order_id: i32) -> Result<(), Er
         client.check_server_version(server_versions::WHAT_IF_ORDERS, "It does not support what-if orders.")?
     }
 
+    if order.total_quantity.fract() != 0.0 {
+        client.check_server_version(server_versions::FRACTIONAL_POSITIONS, "It does not support fractional order quantities.")?
+    }
+
     if order.scale_subs_level_size.is_some() {
         client.check_server_version(
             server_versions::SCALE_ORDERS2,
@@ -1184,9 +1995,35 @@ fn verify_order(client: &Client, order: &Order, _order_id: i32) -> Result<(), Er
         )?
     }
 
+    if order.imbalance_only {
+        client.check_server_version(server_versions::IMBALANCE_ONLY, "It does not support imbalance only orders")?
+    }
+
     Ok(())
 }
 
+// With a cash quantity IB computes the number of shares, so the order must not also set a quantity.
+fn verify_cash_qty(contract: &Contract, order: &Order) -> Result<(), Error> {
+    let Some(cash_qty) = order.cash_qty.filter(|cash_qty| *cash_qty != 0.0) else {
+        return Ok(());
+    };
+
+    if order.total_quantity != 0.0 {
+        return Err(Error::InvalidArgument(format!(
+            "cash quantity {cash_qty} and total quantity {} are both set; total quantity must be zero for cash quantity orders",
+            order.total_quantity
+        )));
+    }
+
+    match contract.security_type {
+        SecurityType::Stock | SecurityType::ForexPair | SecurityType::Crypto | SecurityType::MutualFund => Ok(()),
+        _ => Err(Error::InvalidArgument(format!(
+            "cash quantity orders are not supported for {} contracts",
+            contract.security_type
+        ))),
+    }
+}
+
 // Verifies that Contract is properly formed.
 fn verify_order_contract(client: &Client, contract: &Contract, _order_id: i32) -> Result<(), Error> {
     if contract
@@ -1302,17 +2139,34 @@ pub enum Orders {
     OrderData(OrderData),
     OrderStatus(OrderStatus),
     Notice(Notice),
+    /// A completed order that failed to decode and was skipped, with the decode error.
+    /// See [Client::set_strict_completed_orders](crate::Client::set_strict_completed_orders).
+    SkippedOrder(Error),
 }
 
 impl DataStream<Orders> for Orders {
     fn decode(client: &Client, message: &mut ResponseMessage) -> Result<Orders, Error> {
         match message.message_type() {
-            IncomingMessages::CompletedOrder => Ok(Orders::OrderData(decoders::decode_completed_order(
+            IncomingMessages::CompletedOrder => {
+                match decoders::decode_completed_order(client.server_version, client.lenient_decoding(), message.clone()) {
+                    Ok(order_data) => Ok(Orders::OrderData(order_data)),
+                    Err(err) if !client.strict_completed_orders() => {
+                        warn!("skipping completed order that failed to decode: {err}");
+                        Ok(Orders::SkippedOrder(err))
+                    }
+                    Err(err) => Err(err),
+                }
+            }
+            IncomingMessages::CommissionsReport => Ok(Orders::OrderData(decoders::decode_open_order(
                 client.server_version,
+                client.lenient_decoding(),
+                message.clone(),
+            )?)),
+            IncomingMessages::OpenOrder => Ok(Orders::OrderData(decoders::decode_open_order(
+                client.server_version,
+                client.lenient_decoding(),
                 message.clone(),
             )?)),
-            IncomingMessages::CommissionsReport => Ok(Orders::OrderData(decoders::decode_open_order(client.server_version, message.clone())?)),
-            IncomingMessages::OpenOrder => Ok(Orders::OrderData(decoders::decode_open_order(client.server_version, message.clone())?)),
             IncomingMessages::OrderStatus => Ok(Orders::OrderStatus(decoders::decode_order_status(client.server_version, message)?)),
             IncomingMessages::OpenOrderEnd | IncomingMessages::CompletedOrdersEnd => Err(Error::EndOfStream),
             IncomingMessages::Error => Ok(Orders::Notice(Notice::from(message))),
@@ -1434,6 +2288,7 @@ impl DataStream<ExerciseOptions> for ExerciseOptions {
         match message.message_type() {
             IncomingMessages::OpenOrder => Ok(ExerciseOptions::OpenOrder(decoders::decode_open_order(
                 client.server_version,
+                client.lenient_decoding(),
                 message.clone(),
             )?)),
             IncomingMessages::OrderStatus => Ok(ExerciseOptions::OrderStatus(decoders::decode_order_status(
@@ -1455,6 +2310,8 @@ pub(crate) fn exercise_options<'a>(
     ovrd: bool,
     manual_order_time: Option<OffsetDateTime>,
 ) -> Result<Subscription<'a, ExerciseOptions>, Error> {
+    client.check_not_read_only()?;
+
     let request_id = client.next_request_id();
 
     let request = encoders::encode_exercise_options(
@@ -4,6 +4,8 @@ use super::*;
 
 struct OrderDecoder {
     server_version: i32,
+    // Tolerate missing optional trailing fields.
+    lenient: bool,
     message: ResponseMessage,
     order_id: i32,
     contract: Contract,
@@ -12,7 +14,7 @@ struct OrderDecoder {
 }
 
 impl OrderDecoder {
-    fn new(server_version: i32, mut message: ResponseMessage) -> Self {
+    fn new(server_version: i32, lenient: bool, mut message: ResponseMessage) -> Self {
         message.skip(); // message type
 
         if server_version < server_versions::ORDER_CONTAINER {
@@ -21,6 +23,7 @@ impl OrderDecoder {
 
         Self {
             server_version,
+            lenient,
             message,
             order_id: -1,
             contract: Contract::default(),
@@ -29,6 +32,24 @@ impl OrderDecoder {
         }
     }
 
+    // Reads an optional trailing string field, which reads as empty when missing in lenient mode.
+    fn next_trailing_string(&mut self) -> Result<String, Error> {
+        if self.lenient {
+            Ok(self.message.next_string_or_default())
+        } else {
+            self.message.next_string()
+        }
+    }
+
+    // Reads an optional trailing field with `read`, which is left at its default when missing in lenient mode.
+    fn next_trailing<T: Default>(&mut self, read: fn(&mut ResponseMessage) -> Result<T, Error>) -> Result<T, Error> {
+        if self.lenient && self.message.is_exhausted() {
+            Ok(T::default())
+        } else {
+            read(&mut self.message)
+        }
+    }
+
     fn read_order_id(&mut self) -> Result<(), Error> {
         self.order_id = self.message.next_int()?;
         self.order.order_id = self.order_id;
@@ -540,39 +561,39 @@ impl OrderDecoder {
 
     fn read_use_price_mgmt_algo(&mut self) -> Result<(), Error> {
         if self.server_version >= server_versions::PRICE_MGMT_ALGO {
-            self.order.use_price_mgmt_algo = self.message.next_bool()?;
+            self.order.use_price_mgmt_algo = self.next_trailing(ResponseMessage::next_bool)?;
         }
         Ok(())
     }
 
     fn read_duration(&mut self) -> Result<(), Error> {
         if self.server_version >= server_versions::DURATION {
-            self.order.duration = self.message.next_optional_int()?;
+            self.order.duration = self.next_trailing(ResponseMessage::next_optional_int)?;
         }
         Ok(())
     }
 
     fn read_post_to_ats(&mut self) -> Result<(), Error> {
         if self.server_version >= server_versions::POST_TO_ATS {
-            self.order.post_to_ats = self.message.next_optional_int()?;
+            self.order.post_to_ats = self.next_trailing(ResponseMessage::next_optional_int)?;
         }
         Ok(())
     }
 
     fn read_auto_cancel_parent(&mut self) -> Result<(), Error> {
         if self.server_version >= server_versions::AUTO_CANCEL_PARENT {
-            self.order.auto_cancel_parent = self.message.next_bool()?;
+            self.order.auto_cancel_parent = self.next_trailing(ResponseMessage::next_bool)?;
         }
         Ok(())
     }
 
     fn read_peg_best_peg_mid_order_attributes(&mut self) -> Result<(), Error> {
         if self.server_version >= server_versions::PEGBEST_PEGMID_OFFSETS {
-            self.order.min_trade_qty = self.message.next_optional_int()?;
-            self.order.min_compete_size = self.message.next_optional_int()?;
-            self.order.compete_against_best_offset = self.message.next_optional_double()?;
-            self.order.mid_offset_at_whole = self.message.next_optional_double()?;
-            self.order.mid_offset_at_half = self.message.next_optional_double()?;
+            self.order.min_trade_qty = self.next_trailing(ResponseMessage::next_optional_int)?;
+            self.order.min_compete_size = self.next_trailing(ResponseMessage::next_optional_int)?;
+            self.order.compete_against_best_offset = self.next_trailing(ResponseMessage::next_optional_double)?;
+            self.order.mid_offset_at_whole = self.next_trailing(ResponseMessage::next_optional_double)?;
+            self.order.mid_offset_at_half = self.next_trailing(ResponseMessage::next_optional_double)?;
         }
         Ok(())
     }
@@ -604,32 +625,32 @@ impl OrderDecoder {
     }
 
     fn read_shareholder(&mut self) -> Result<(), Error> {
-        self.order.shareholder = self.message.next_string()?;
+        self.order.shareholder = self.next_trailing_string()?;
         Ok(())
     }
 
     fn read_imbalance_only(&mut self) -> Result<(), Error> {
-        self.order.imbalance_only = self.message.next_bool()?;
+        self.order.imbalance_only = self.next_trailing(ResponseMessage::next_bool)?;
         Ok(())
     }
 
     fn read_route_marketable_to_bbo(&mut self) -> Result<(), Error> {
-        self.order.route_marketable_to_bbo = self.message.next_bool()?;
+        self.order.route_marketable_to_bbo = self.next_trailing(ResponseMessage::next_bool)?;
         Ok(())
     }
 
     fn read_parent_perm_id(&mut self) -> Result<(), Error> {
-        self.order.parent_perm_id = self.message.next_optional_long()?;
+        self.order.parent_perm_id = self.next_trailing(ResponseMessage::next_optional_long)?;
         Ok(())
     }
 
     fn read_completed_time(&mut self) -> Result<(), Error> {
-        self.order_state.completed_time = self.message.next_string()?;
+        self.order_state.completed_time = self.next_trailing_string()?;
         Ok(())
     }
 
     fn read_completed_status(&mut self) -> Result<(), Error> {
-        self.order_state.completed_status = self.message.next_string()?;
+        self.order_state.completed_status = self.next_trailing_string()?;
         Ok(())
     }
 
@@ -643,8 +664,8 @@ impl OrderDecoder {
     }
 }
 
-pub(crate) fn decode_open_order(server_version: i32, message: ResponseMessage) -> Result<OrderData, Error> {
-    let mut decoder = OrderDecoder::new(server_version, message);
+pub(crate) fn decode_open_order(server_version: i32, lenient: bool, message: ResponseMessage) -> Result<OrderData, Error> {
+    let mut decoder = OrderDecoder::new(server_version, lenient, message);
 
     // read order id
     decoder.read_order_id()?;
@@ -818,8 +839,8 @@ pub(crate) fn decode_commission_report(_server_version: i32, message: &mut Respo
     })
 }
 
-pub(crate) fn decode_completed_order(server_version: i32, message: ResponseMessage) -> Result<OrderData, Error> {
-    let mut decoder = OrderDecoder::new(server_version, message);
+pub(crate) fn decode_completed_order(server_version: i32, lenient: bool, message: ResponseMessage) -> Result<OrderData, Error> {
+    let mut decoder = OrderDecoder::new(server_version, lenient, message);
 
     // read contract fields
     decoder.read_contract_fields()?;
@@ -6,6 +6,10 @@ use super::*;
 mod tests;
 
 pub(crate) fn encode_place_order(server_version: i32, order_id: i32, contract: &Contract, order: &Order) -> Result<RequestMessage, Error> {
+    if order.tif == "GTD" {
+        ServerTimestamp::parse(&order.good_till_date)?;
+    }
+
     let mut message = RequestMessage::default();
     let message_version = message_version_for(server_version);
 
@@ -1,4 +1,5 @@
-use super::{Action, Order, OrderComboLeg, TagValue};
+use super::{Action, AuctionStrategy, ClearingIntent, OcaType, Order, OrderComboLeg, ReferencePriceType, ServerTimestamp, TagValue, VolatilityType};
+use crate::Error;
 
 /// An auction order is entered into the electronic trading system during the pre-market opening period for execution at the
 /// Calculated Opening Price (COP). If your order is not filled on the open, the order is re-submitted as a limit order with
@@ -368,6 +369,8 @@ pub fn passive_relative(action: Action, quantity: f64, offset: f64) -> Order {
 /// the NBBO midpoint and the order price adjusts automatically to continue to peg the midpoint if the market moves. The price only adjusts
 /// to be more aggressive. If the market moves in the opposite direction, the order will execute.
 /// Products: STK
+///
+/// For orders routed to IBKRATS, set the IBKRATS specific fields with [OrderBuilder::min_trade_qty] and [OrderBuilder::mid_offsets].
 pub fn pegged_to_midpoint(action: Action, quantity: f64, offset: f64, limit_price: f64) -> Order {
     Order {
         action,
@@ -666,10 +669,10 @@ pub fn relative_market_combo(action: Action, quantity: f64, non_guaranteed: bool
 /// Grouping the two orders using an OCA order type offers the investor two chance to enter a similar position, while only running the risk
 /// of taking on a single position.
 /// Products: BOND, CASH, FUT, FOP, STK, OPT, WAR
-pub fn one_cancels_all(oca_group: &str, mut oca_orders: Vec<Order>, oca_type: i32) -> Vec<Order> {
+pub fn one_cancels_all(oca_group: &str, mut oca_orders: Vec<Order>, oca_type: OcaType) -> Vec<Order> {
     for order in &mut oca_orders {
         order.oca_group = oca_group.to_owned();
-        order.oca_type = oca_type;
+        order.set_oca_type(oca_type);
     }
 
     oca_orders
@@ -985,3 +988,440 @@ pub fn peg_mid_order(
         ..Order::default()
     }
 }
+
+/// Offset of a relative (REL) order from the NBBO. See [OrderBuilder::relative].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RelOffset {
+    /// Offset by a fixed amount, sent as the order's [aux_price](Order::aux_price).
+    Amount(f64),
+    /// Offset by a percentage, sent as the order's [percent_offset](Order::percent_offset).
+    Percent(f64),
+}
+
+/// Allocation of a Financial Advisor order across accounts. See [OrderBuilder::financial_advisor].
+#[derive(Clone, Debug, PartialEq)]
+pub enum FaAllocation {
+    /// Allocate across the accounts of a group, using the given method.
+    Group { name: String, method: FaMethod },
+    /// Allocate using an allocation profile. Servers from version 177 merge profiles into groups and ignore this.
+    Profile(String),
+}
+
+/// Method used to allocate a Financial Advisor group order across its accounts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FaMethod {
+    /// Same quantity for each account.
+    EqualQuantity,
+    /// In proportion to each account's net liquidation value.
+    NetLiq,
+    /// In proportion to each account's available equity.
+    AvailableEquity,
+    /// Changes each account's position by the given percentage, e.g. 10.0 to increase positions by 10%.
+    PctChange(f64),
+}
+
+impl std::fmt::Display for FaMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            FaMethod::EqualQuantity => "EqualQuantity",
+            FaMethod::NetLiq => "NetLiq",
+            FaMethod::AvailableEquity => "AvailableEquity",
+            FaMethod::PctChange(_) => "PctChange",
+        };
+
+        write!(f, "{text}")
+    }
+}
+
+/// Refines an [Order] created by one of the order functions in this module with optional attributes.
+///
+/// # Examples
+///
+/// ```no_run
+/// use ibapi::orders::order_builder::{self, OrderBuilder};
+/// use ibapi::orders::Action;
+///
+/// let order = OrderBuilder::new(order_builder::limit_order(Action::Sell, 100.0, 105.0))
+///     .auto_cancel_parent()
+///     .build()
+///     .expect("invalid order");
+/// ```
+#[derive(Clone, Debug)]
+pub struct OrderBuilder {
+    order: Order,
+}
+
+impl OrderBuilder {
+    /// Starts building from the given order.
+    pub fn new(order: Order) -> Self {
+        Self { order }
+    }
+
+    /// Cancels the parent order if this child order is cancelled. Requires server version 162+.
+    pub fn auto_cancel_parent(mut self) -> Self {
+        self.order.auto_cancel_parent = true;
+        self
+    }
+
+    /// Sets the amount off the limit price the order may execute at. Requires a limit price.
+    pub fn discretionary(mut self, amount: f64) -> Self {
+        self.order.discretionary_amt = amount;
+        self
+    }
+
+    /// Makes the order a D-Peg order, allowing discretion up to the limit price. Requires a limit price and server version 148+.
+    pub fn d_peg(mut self) -> Self {
+        self.order.discretionary_up_to_limit_price = true;
+        self
+    }
+
+    /// Overrides TWS precautionary price and size constraints for this order.
+    ///
+    /// **Warning:** the precautionary settings guard against fat-finger errors such as a misplaced decimal point or an
+    /// extra zero in the quantity. With this set, TWS transmits the order even if it would otherwise be rejected or held
+    /// for confirmation, so validate price and size yourself before placing it.
+    pub fn override_constraints(mut self) -> Self {
+        self.order.override_percentage_constraints = true;
+        self
+    }
+
+    /// Sets the person responsible for the investment decision (MiFID 2). Mutually exclusive with [mifid2_decision_algo](Self::mifid2_decision_algo).
+    pub fn mifid2_decision_maker(mut self, decision_maker: &str) -> Self {
+        self.order.mifid2_decision_maker = decision_maker.to_owned();
+        self
+    }
+
+    /// Sets the algorithm responsible for the investment decision (MiFID 2). Mutually exclusive with [mifid2_decision_maker](Self::mifid2_decision_maker).
+    pub fn mifid2_decision_algo(mut self, decision_algo: &str) -> Self {
+        self.order.mifid2_decision_algo = decision_algo.to_owned();
+        self
+    }
+
+    /// Sets the person responsible for executing the order (MiFID 2). Mutually exclusive with [mifid2_execution_algo](Self::mifid2_execution_algo).
+    pub fn mifid2_execution_trader(mut self, execution_trader: &str) -> Self {
+        self.order.mifid2_execution_trader = execution_trader.to_owned();
+        self
+    }
+
+    /// Sets the algorithm responsible for executing the order (MiFID 2). Mutually exclusive with [mifid2_execution_trader](Self::mifid2_execution_trader).
+    pub fn mifid2_execution_algo(mut self, execution_algo: &str) -> Self {
+        self.order.mifid2_execution_algo = execution_algo.to_owned();
+        self
+    }
+
+    /// Sets the account and intent used to clear the order. For execution-only clients.
+    ///
+    /// # Arguments
+    /// * `account` - True beneficiary of the order. Required for [ClearingIntent::Away] and [ClearingIntent::PTA].
+    /// * `intent`  - Where the order should be cleared.
+    pub fn clearing(mut self, account: &str, intent: ClearingIntent) -> Self {
+        self.order.clearing_account = account.to_owned();
+        self.order.clearing_intent = intent.to_string();
+        self
+    }
+
+    /// Keeps the order working until the given time. Sets the time in force to GTD.
+    pub fn good_till_date(mut self, timestamp: ServerTimestamp) -> Self {
+        self.order.tif = "GTD".to_owned();
+        self.order.good_till_date = timestamp.to_string();
+        self
+    }
+
+    /// Activates the order at the given time.
+    pub fn good_after_time(mut self, timestamp: ServerTimestamp) -> Self {
+        self.order.good_after_time = timestamp.to_string();
+        self
+    }
+
+    /// Marks a short sale as exempt from the short sale uptick rule by setting [exempt_code](Order::exempt_code) to 0.
+    ///
+    /// Orders default to an exempt code of -1, which means no exemption. Only valid for [Action::Sell] and [Action::SellShort].
+    pub fn short_sale_exempt(mut self) -> Self {
+        self.order.exempt_code = 0;
+        self
+    }
+
+    /// Sets the strategy of a BOX price improvement auction order.
+    pub fn auction_strategy(mut self, strategy: AuctionStrategy) -> Self {
+        self.order.auction_strategy = Some(strategy as i32);
+        self
+    }
+
+    /// Makes the order a relative (REL) order, pegged to the primary quote by `offset`.
+    ///
+    /// The amount and percent offsets are mutually exclusive, so setting one clears the other.
+    /// Set a limit price on the order to cap the price it may execute at.
+    pub fn relative(mut self, offset: RelOffset) -> Self {
+        self.order.order_type = "REL".to_owned();
+        match offset {
+            RelOffset::Amount(amount) => {
+                self.order.aux_price = Some(amount);
+                self.order.percent_offset = None;
+            }
+            RelOffset::Percent(percent) => {
+                self.order.aux_price = None;
+                self.order.percent_offset = Some(percent);
+            }
+        }
+        self
+    }
+
+    /// Makes the order a stop (STP) order, sent to the market once `stop_price` is traded at or through.
+    pub fn stop(mut self, stop_price: f64) -> Self {
+        self.order.order_type = "STP".to_owned();
+        self.order.aux_price = Some(stop_price);
+        self.order.limit_price = None;
+        self
+    }
+
+    /// Makes the order a stop-limit (STP LMT) order, sent as a limit order at `limit_price` once `stop_price` is traded at or through.
+    ///
+    /// The limit must not be worse than the stop: at or above it for buys, at or below it for sells.
+    pub fn stop_limit(mut self, stop_price: f64, limit_price: f64) -> Self {
+        self.order.order_type = "STP LMT".to_owned();
+        self.order.aux_price = Some(stop_price);
+        self.order.limit_price = Some(limit_price);
+        self
+    }
+
+    /// Allocates the order across Financial Advisor accounts, setting the order's FA group, method, percentage and profile.
+    pub fn financial_advisor(mut self, allocation: FaAllocation) -> Self {
+        self.order.fa_group = "".to_owned();
+        self.order.fa_method = "".to_owned();
+        self.order.fa_percentage = "".to_owned();
+        self.order.fa_profile = "".to_owned();
+
+        match allocation {
+            FaAllocation::Group { name, method } => {
+                self.order.fa_group = name;
+                self.order.fa_method = method.to_string();
+                if let FaMethod::PctChange(percentage) = method {
+                    self.order.fa_percentage = percentage.to_string();
+                }
+            }
+            FaAllocation::Profile(name) => self.order.fa_profile = name,
+        }
+        self
+    }
+
+    /// Makes the order a VOL order, priced at the given volatility percent, e.g. 40.0 for 40%.
+    pub fn volatility(mut self, volatility_percent: f64, volatility_type: VolatilityType) -> Self {
+        self.order.order_type = "VOL".to_owned();
+        self.order.volatility = Some(volatility_percent);
+        self.order.volatility_type = Some(volatility_type as i32);
+        self
+    }
+
+    /// Sets the price TWS uses to calculate the limit price of a VOL order.
+    pub fn reference_price_type(mut self, reference_price_type: ReferencePriceType) -> Self {
+        self.order.reference_price_type = Some(reference_price_type as i32);
+        self
+    }
+
+    /// Minimum quantity per fill. For IBKRATS orders.
+    pub fn min_trade_qty(mut self, quantity: i32) -> Self {
+        self.order.min_trade_qty = Some(quantity);
+        self
+    }
+
+    /// Minimum size of the orders a PEG BEST order competes against. For IBKRATS orders.
+    pub fn min_compete_size(mut self, size: i32) -> Self {
+        self.order.min_compete_size = Some(size);
+        self
+    }
+
+    /// Offset from the best price that a PEG BEST order competes against. Must be in whole-penny increments. For IBKRATS orders.
+    pub fn compete_against_best_offset(mut self, offset: f64) -> Self {
+        self.order.compete_against_best_offset = Some(offset);
+        self
+    }
+
+    /// Lets a PEG BEST order compete against the best price up to the midpoint.
+    /// The midpoint offsets are then sent as well, see [OrderBuilder::mid_offsets]. For IBKRATS orders.
+    pub fn compete_against_best_up_to_mid(mut self) -> Self {
+        self.order.compete_against_best_offset = super::COMPETE_AGAINST_BEST_OFFSET_UP_TO_MID;
+        self
+    }
+
+    /// Offsets from the midpoint for PEG MID orders and PEG BEST orders competing up to the midpoint. For IBKRATS orders.
+    ///
+    /// # Arguments
+    /// * `at_whole` - applied when the spread is an even number of cents wide. Must be zero or in whole-penny increments.
+    /// * `at_half` - applied when the spread is an odd number of cents wide. Must be in half-penny increments.
+    pub fn mid_offsets(mut self, at_whole: f64, at_half: f64) -> Self {
+        self.order.mid_offset_at_whole = Some(at_whole);
+        self.order.mid_offset_at_half = Some(at_half);
+        self
+    }
+
+    /// Restricts an auction order (MOC, LOC, MOO or LOO) to offsetting the published imbalance.
+    ///
+    /// Requires server version 187, so placing the order fails with [Error::ServerVersion] at the server versions this library negotiates.
+    pub fn imbalance_only(mut self) -> Self {
+        self.order.imbalance_only = true;
+        self
+    }
+
+    /// Validates the configured attributes and returns the order.
+    pub fn build(self) -> Result<Order, Error> {
+        if self.order.discretionary_amt < 0.0 {
+            return Err(Error::InvalidArgument(format!(
+                "discretionary amount must not be negative: {}",
+                self.order.discretionary_amt
+            )));
+        }
+
+        if (self.order.discretionary_amt != 0.0 || self.order.discretionary_up_to_limit_price) && self.order.limit_price.is_none() {
+            return Err(Error::InvalidArgument("discretionary orders require a limit price".into()));
+        }
+
+        validate_mifid2(&self.order)?;
+        validate_clearing(&self.order)?;
+        validate_exempt_code(&self.order)?;
+        validate_ibkrats_offsets(&self.order)?;
+        validate_stop(&self.order)?;
+
+        Ok(self.order)
+    }
+}
+
+const PENNY: f64 = 0.01;
+const HALF_PENNY: f64 = 0.005;
+
+fn validate_ibkrats_offsets(order: &Order) -> Result<(), Error> {
+    if let Some(offset) = order.compete_against_best_offset {
+        if order.compete_against_best_offset != super::COMPETE_AGAINST_BEST_OFFSET_UP_TO_MID && !is_increment_of(offset, PENNY) {
+            return Err(Error::InvalidArgument(format!(
+                "compete against best offset must be in whole-penny increments: {offset}"
+            )));
+        }
+    }
+
+    if let Some(offset) = order.mid_offset_at_whole {
+        if !is_increment_of(offset, PENNY) {
+            return Err(Error::InvalidArgument(format!(
+                "mid offset at whole must be zero or in whole-penny increments: {offset}"
+            )));
+        }
+    }
+
+    if let Some(offset) = order.mid_offset_at_half {
+        if !is_increment_of(offset, HALF_PENNY) {
+            return Err(Error::InvalidArgument(format!(
+                "mid offset at half must be in half-penny increments: {offset}"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// Tolerates the rounding error of decimal prices stored as f64.
+fn is_increment_of(value: f64, increment: f64) -> bool {
+    let steps = value / increment;
+    steps.is_finite() && (steps - steps.round()).abs() < 1e-6
+}
+
+// MiFID 2 orders need exactly one decision party and exactly one execution party.
+fn validate_mifid2(order: &Order) -> Result<(), Error> {
+    let decision = [&order.mifid2_decision_maker, &order.mifid2_decision_algo];
+    let execution = [&order.mifid2_execution_trader, &order.mifid2_execution_algo];
+
+    let decision_count = decision.iter().filter(|value| !value.is_empty()).count();
+    let execution_count = execution.iter().filter(|value| !value.is_empty()).count();
+
+    if decision_count == 0 && execution_count == 0 {
+        return Ok(());
+    }
+
+    if decision_count != 1 {
+        return Err(Error::InvalidArgument(
+            "MiFID 2 orders require exactly one of decision maker or decision algo".into(),
+        ));
+    }
+
+    if execution_count != 1 {
+        return Err(Error::InvalidArgument(
+            "MiFID 2 orders require exactly one of execution trader or execution algo".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_clearing(order: &Order) -> Result<(), Error> {
+    if order.clearing_intent.is_empty() {
+        return Ok(());
+    }
+
+    match ClearingIntent::from(&order.clearing_intent) {
+        Some(ClearingIntent::IB) => Ok(()),
+        Some(_) if order.clearing_account.is_empty() => Err(Error::InvalidArgument(format!(
+            "clearing intent {} requires a clearing account",
+            order.clearing_intent
+        ))),
+        Some(_) => Ok(()),
+        None => Err(Error::InvalidArgument(format!(
+            "invalid clearing intent: {} (expected IB, Away or PTA)",
+            order.clearing_intent
+        ))),
+    }
+}
+
+// Only sells can open a short position, so only they can be exempt from the uptick rule.
+fn validate_exempt_code(order: &Order) -> Result<(), Error> {
+    if order.exempt_code == -1 || matches!(order.action, Action::Sell | Action::SellShort) {
+        return Ok(());
+    }
+
+    Err(Error::InvalidArgument(format!(
+        "short sale exempt code {} is only valid for short sales, order action is {}",
+        order.exempt_code, order.action
+    )))
+}
+
+// Stop orders need a stop price, and the limit of a stop-limit order must not be worse than its stop.
+// Zero and negative stop prices are valid, e.g. for spreads on BAG contracts.
+fn validate_stop(order: &Order) -> Result<(), Error> {
+    if order.order_type != "STP" && order.order_type != "STP LMT" {
+        return Ok(());
+    }
+
+    let stop_price = match order.aux_price {
+        Some(price) if price.is_finite() => price,
+        _ => {
+            return Err(Error::InvalidArgument(format!(
+                "stop price must be a finite number: {:?}",
+                order.aux_price
+            )))
+        }
+    };
+
+    if order.order_type == "STP" {
+        return Ok(());
+    }
+
+    let Some(limit_price) = order.limit_price else {
+        return Err(Error::InvalidArgument("stop-limit orders require a limit price".into()));
+    };
+
+    let consistent = match order.action {
+        Action::Buy => limit_price >= stop_price,
+        Action::Sell | Action::SellShort | Action::SellLong => limit_price <= stop_price,
+    };
+
+    if !consistent {
+        return Err(Error::InvalidArgument(format!(
+            "{} stop-limit limit price {limit_price} is on the wrong side of stop price {stop_price}",
+            order.action
+        )));
+    }
+
+    Ok(())
+}
+
+impl From<Order> for OrderBuilder {
+    fn from(order: Order) -> Self {
+        Self::new(order)
+    }
+}
@@ -1,7 +1,9 @@
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use crate::contracts::{contract_samples, Contract, SecurityType};
 use crate::stubs::MessageBusStub;
+use crate::transport::SubscriptionBuilder;
 
 use super::order_builder::*;
 use super::*;
@@ -512,6 +514,117 @@ fn completed_orders() {
     }
 }
 
+// Completed order as sent by a gateway build that omits the trailing PEG BEST / PEG MID fields.
+const COMPLETED_ORDER_WITHOUT_PEG_OFFSETS: &str = "101|265598|AAPL|STK||0|?||SMART|USD|AAPL|NMS|BUY|0|MKT|0.0|0.0|DAY||DU1234567||0||1824933227|0|0|0|||||||||||0||-1||||||2147483647|0|0||3|0||0|None||0|0|0||0|0||||0|0|0|2147483647|2147483647||||IB|0|0||0|Filled|0|0|0|1.7976931348623157E308|1.7976931348623157E308|0|1|0||100|2147483647|0|Not an insider or substantial shareholder|0|0|9223372036854775807|20230306 12:28:30 America/Los_Angeles|Filled Size: 100|";
+
+#[test]
+fn completed_orders_lenient_decoding() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![COMPLETED_ORDER_WITHOUT_PEG_OFFSETS.to_owned(), "102|".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::PEGBEST_PEGMID_OFFSETS);
+    assert!(!client.lenient_decoding(), "decoding should be strict by default");
+    client.set_lenient_decoding(true);
+
+    let results = super::completed_orders(&client, true).expect("failed to request completed orders");
+
+    if let Some(Orders::OrderData(order_data)) = results.next() {
+        assert_eq!(order_data.contract.symbol, "AAPL", "contract.symbol");
+        assert_eq!(order_data.order.parent_perm_id, None, "order.parent_perm_id");
+        assert_eq!(order_data.order.min_trade_qty, None, "order.min_trade_qty");
+        assert_eq!(order_data.order.mid_offset_at_half, None, "order.mid_offset_at_half");
+        assert_eq!(
+            order_data.order_state.completed_status, "Filled Size: 100",
+            "order_state.completed_status"
+        );
+    } else {
+        panic!("expected order data, got error: {:?}", results.error());
+    }
+}
+
+#[test]
+fn completed_orders_skips_malformed_order() {
+    let malformed = COMPLETED_ORDER_WITHOUT_PEG_OFFSETS.replacen("265598", "bad", 1);
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            malformed.clone(),
+            COMPLETED_ORDER_WITHOUT_PEG_OFFSETS.to_owned(),
+            COMPLETED_ORDER_WITHOUT_PEG_OFFSETS.to_owned(),
+            "102|".to_owned(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    assert!(!client.strict_completed_orders(), "malformed orders should be skipped by default");
+
+    let results: Vec<Orders> = super::completed_orders(&client, true)
+        .expect("failed to request completed orders")
+        .into_iter()
+        .collect();
+
+    assert_eq!(results.len(), 3, "skipped order and two orders expected: {results:?}");
+    assert!(matches!(&results[0], Orders::SkippedOrder(Error::Parse(..))), "{:?}", results[0]);
+    for result in &results[1..] {
+        assert!(
+            matches!(result, Orders::OrderData(order_data) if order_data.contract.symbol == "AAPL"),
+            "{result:?}"
+        );
+    }
+
+    // Strict mode fails fast at the malformed order.
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            malformed,
+            COMPLETED_ORDER_WITHOUT_PEG_OFFSETS.to_owned(),
+            COMPLETED_ORDER_WITHOUT_PEG_OFFSETS.to_owned(),
+            "102|".to_owned(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    client.set_strict_completed_orders(true);
+
+    let subscription = super::completed_orders(&client, true).expect("failed to request completed orders");
+    assert!(subscription.next().is_none(), "strict stream should end at the malformed order");
+    assert!(matches!(subscription.error(), Some(Error::Parse(..))), "{:?}", subscription.error());
+}
+
+#[test]
+fn decode_completed_order_trailing_fields() {
+    let server_version = server_versions::PEGBEST_PEGMID_OFFSETS;
+
+    // Missing optional tail fails strict decoding, naming the missing field.
+    let message = ResponseMessage::from_simple(COMPLETED_ORDER_WITHOUT_PEG_OFFSETS);
+    let fields_len = message.fields.len();
+    match decoders::decode_completed_order(server_version, false, message) {
+        Err(Error::ParseError { field_index, .. }) => assert_eq!(field_index, fields_len, "field_index"),
+        other => panic!("expected parse error, got {other:?}"),
+    }
+
+    let message = ResponseMessage::from_simple(COMPLETED_ORDER_WITHOUT_PEG_OFFSETS);
+    let order_data = decoders::decode_completed_order(server_version, true, message).expect("lenient decode failed");
+    assert_eq!(order_data.order_state.completed_time, "20230306 12:28:30 America/Los_Angeles");
+    assert_eq!(order_data.order.compete_against_best_offset, None, "order.compete_against_best_offset");
+
+    // Extra fields appended by newer builds are ignored in both modes.
+    let longer = format!("{COMPLETED_ORDER_WITHOUT_PEG_OFFSETS}1|2|0.5|0.25|0.75|EXTRA|42|");
+    for lenient in [false, true] {
+        let order_data = decoders::decode_completed_order(server_version, lenient, ResponseMessage::from_simple(&longer))
+            .unwrap_or_else(|e| panic!("decode failed (lenient: {lenient}): {e}"));
+        assert_eq!(order_data.order.min_trade_qty, Some(1), "order.min_trade_qty");
+        assert_eq!(order_data.order.min_compete_size, Some(2), "order.min_compete_size");
+        assert_eq!(order_data.order.mid_offset_at_half, Some(0.75), "order.mid_offset_at_half");
+        assert_eq!(
+            order_data.order_state.completed_status, "Filled Size: 100",
+            "order_state.completed_status"
+        );
+    }
+}
+
 #[test]
 fn open_orders() {
     let message_bus = Arc::new(MessageBusStub {
@@ -647,3 +760,857 @@ fn encode_combo_market_order() {
 
     assert!(results.is_ok(), "failed to place order: {}", results.err().unwrap());
 }
+
+#[test]
+fn test_order_state_parsed_completed_time() {
+    let order_state = OrderState {
+        completed_time: "20230306 12:28:30 America/Los_Angeles".to_owned(),
+        ..OrderState::default()
+    };
+
+    let completed_time = order_state.parsed_completed_time().expect("expected completed time");
+    assert_eq!(completed_time, time::macros::datetime!(2023-03-06 12:28:30 -8));
+
+    let order_state = OrderState {
+        completed_time: "20230306 12:28:30 Mars/Olympus_Mons".to_owned(),
+        ..OrderState::default()
+    };
+    assert_eq!(order_state.parsed_completed_time(), None, "unknown time zone");
+
+    assert_eq!(OrderState::default().parsed_completed_time(), None, "empty completed time");
+}
+
+#[test]
+fn test_server_timestamp_formats() {
+    let datetime = time::macros::datetime!(2024-03-15 20:00:00 UTC);
+
+    let new_york = time_tz::timezones::get_by_name("America/New_York").unwrap();
+    let local = ServerTimestamp::from_datetime(datetime, new_york);
+    assert_eq!(local.as_str(), "20240315 16:00:00 America/New_York");
+
+    let utc = ServerTimestamp::utc(datetime.to_offset(time::macros::offset!(-4)));
+    assert_eq!(utc.as_str(), "20240315-20:00:00");
+
+    assert_eq!(ServerTimestamp::parse("20240315 16:00:00 America/New_York").unwrap(), local);
+    assert_eq!(ServerTimestamp::parse("20240315-20:00:00").unwrap(), utc);
+    assert!(ServerTimestamp::parse("20240315 16:00:00").is_ok(), "time zone is optional");
+
+    assert!(ServerTimestamp::parse("2024-03-15 16:00:00").is_err());
+    assert!(ServerTimestamp::parse("20240315 16:00:00 Mars/Olympus_Mons").is_err());
+    assert!(ServerTimestamp::parse("").is_err());
+}
+
+#[test]
+fn test_place_order_rejects_gtd_without_valid_date() {
+    let contract = Contract::stock("AAPL");
+
+    let mut order = order_builder::limit_order(Action::Buy, 100.0, 50.0);
+    order.tif = "GTD".to_owned();
+    order.good_till_date = "15/03/2024".to_owned();
+
+    let result = encoders::encode_place_order(server_versions::SIZE_RULES, 7, &contract, &order);
+    assert!(matches!(result, Err(Error::InvalidArgument(_))), "expected invalid argument");
+
+    order.good_till_date = "20240315-20:00:00".to_owned();
+    assert!(encoders::encode_place_order(server_versions::SIZE_RULES, 7, &contract, &order).is_ok());
+}
+
+#[test]
+fn test_order_entry_points_reject_invalid_orders_identically() {
+    let contract = Contract::stock("AAPL");
+
+    let mut gtd_order = order_builder::limit_order(Action::Buy, 100.0, 50.0);
+    gtd_order.tif = "GTD".to_owned();
+    gtd_order.good_till_date = "15/03/2024".to_owned();
+
+    let fractional_order = order_builder::limit_order(Action::Buy, 0.5, 50.0);
+
+    let cases = [
+        (&gtd_order, server_versions::SIZE_RULES),
+        (&fractional_order, server_versions::FRACTIONAL_POSITIONS - 1),
+    ];
+
+    for (order, server_version) in cases {
+        let message_bus = Arc::new(MessageBusStub::default());
+        let client = Client::stubbed(message_bus, server_version);
+
+        let placed = client.place_order(7, &contract, order).err().expect("place_order should reject order");
+        let tried = client
+            .try_place_order(7, &contract, order, Duration::from_millis(10))
+            .err()
+            .expect("try_place_order should reject order");
+        let submitted = client.submit_order(7, &contract, order).expect_err("submit_order should reject order");
+
+        assert_eq!(placed.to_string(), submitted.to_string(), "place_order and submit_order errors");
+        assert_eq!(tried.to_string(), submitted.to_string(), "try_place_order and submit_order errors");
+        assert!(client.message_bus.request_messages().is_empty(), "rejected orders should not be sent");
+    }
+}
+
+#[test]
+fn test_cash_qty_order() {
+    let message_bus = Arc::new(MessageBusStub::default());
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let contract = Contract::forex("EUR", "USD");
+    let order = order_builder::limit_order_with_cash_qty(Action::Buy, 1.08, 10000.0);
+
+    client
+        .submit_order(13, &contract, &order)
+        .expect("cash quantity order should be accepted");
+    assert_eq!(client.message_bus.request_messages().len(), 1);
+}
+
+#[test]
+fn test_cash_qty_order_rejected() {
+    let message_bus = Arc::new(MessageBusStub::default());
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let mut both_set = order_builder::limit_order_with_cash_qty(Action::Buy, 1.08, 10000.0);
+    both_set.total_quantity = 100.0;
+
+    let cases = [
+        ("both quantities set", Contract::forex("EUR", "USD"), both_set),
+        (
+            "unsupported contract",
+            Contract::option("AAPL", "20250620", 240.0, "C"),
+            order_builder::limit_order_with_cash_qty(Action::Buy, 5.0, 1000.0),
+        ),
+    ];
+
+    for (name, contract, order) in cases {
+        let result = client.submit_order(13, &contract, &order);
+        assert!(matches!(result, Err(Error::InvalidArgument(_))), "{name}: {result:?}");
+    }
+
+    assert!(client.message_bus.request_messages().is_empty(), "rejected orders should not be sent");
+}
+
+#[test]
+fn test_submit_order() {
+    let message_bus = Arc::new(MessageBusStub::default());
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let contract = Contract::stock("TSLA");
+    let order = order_builder::limit_order(Action::Buy, 100.0, 200.0);
+
+    client.submit_order(13, &contract, &order).expect("submit order failed");
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(request_messages.len(), 1);
+    assert_eq!(
+        request_messages[0].encode_simple(),
+        encoders::encode_place_order(server_versions::SIZE_RULES, 13, &contract, &order)
+            .unwrap()
+            .encode_simple(),
+        "submit_order should send the place order request"
+    );
+}
+
+#[test]
+fn test_place_bracket_order() {
+    let message_bus = Arc::new(MessageBusStub::default());
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    client.set_next_order_id(40);
+
+    let contract = Contract::stock("AAPL");
+    let entry = order_builder::limit_order(Action::Buy, 100.0, 185.0);
+    let take_profit = order_builder::limit_order(Action::Sell, 100.0, 190.0);
+    let stop_loss = order_builder::stop(Action::Sell, 100.0, 180.0);
+
+    let ids = client
+        .place_bracket_order(&contract, &entry, &take_profit, &stop_loss)
+        .expect("place bracket order failed");
+
+    assert_eq!(
+        ids,
+        BracketOrderIds {
+            parent: 40,
+            take_profit: 41,
+            stop_loss: 42
+        }
+    );
+
+    let expected = [(40, entry, 0, false), (41, take_profit, 40, false), (42, stop_loss, 40, true)];
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(request_messages.len(), 3);
+    for (request, (order_id, leg, parent_id, transmit)) in request_messages.iter().zip(expected) {
+        let order = Order {
+            order_id,
+            parent_id,
+            transmit,
+            ..leg
+        };
+        assert_eq!(
+            request.encode_simple(),
+            encoders::encode_place_order(server_versions::SIZE_RULES, order_id, &contract, &order)
+                .unwrap()
+                .encode_simple(),
+            "order {order_id} should have parent {parent_id} and transmit {transmit}"
+        );
+    }
+}
+
+#[test]
+fn test_oca_type() {
+    let oca_types = [(OcaType::CancelWithBlock, 1), (OcaType::ReduceWithBlock, 2), (OcaType::ReduceNoBlock, 3)];
+
+    for (oca_type, value) in oca_types {
+        assert_eq!(oca_type.to_field(), value.to_string(), "{oca_type:?}.to_field()");
+        assert_eq!(OcaType::from(value), Some(oca_type), "OcaType::from({value})");
+    }
+
+    assert_eq!(OcaType::from(0), None);
+
+    let mut order = Order::default();
+    assert_eq!(order.oca_type, 0, "orders outside a group are sent as 0");
+    assert_eq!(order.oca_type_value(), None);
+
+    order.set_oca_type(OcaType::ReduceNoBlock);
+    assert_eq!(order.oca_type, 3);
+    assert_eq!(order.oca_type_value(), Some(OcaType::ReduceNoBlock));
+
+    order.oca_type = 7;
+    assert_eq!(order.oca_type_value(), None, "unknown codes are kept in the raw field");
+}
+
+#[test]
+fn test_place_oca_group() {
+    let message_bus = Arc::new(MessageBusStub::default());
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    client.set_next_order_id(70);
+
+    let orders = vec![
+        (Contract::stock("AAPL"), order_builder::limit_order(Action::Sell, 100.0, 190.0)),
+        (Contract::stock("MSFT"), order_builder::limit_order(Action::Sell, 50.0, 420.0)),
+        (Contract::stock("NVDA"), order_builder::stop(Action::Sell, 20.0, 110.0)),
+    ];
+
+    let order_ids = client
+        .place_oca_group("exit-1", OcaType::ReduceWithBlock, orders.clone())
+        .expect("place OCA group failed");
+    assert_eq!(order_ids, vec![70, 71, 72]);
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(request_messages.len(), 3);
+    for ((request, order_id), (contract, order)) in request_messages.iter().zip(order_ids).zip(orders) {
+        let order = Order {
+            order_id,
+            oca_group: "exit-1".to_owned(),
+            oca_type: 2,
+            ..order
+        };
+        assert_eq!(
+            request.encode_simple(),
+            encoders::encode_place_order(server_versions::SIZE_RULES, order_id, &contract, &order)
+                .unwrap()
+                .encode_simple(),
+            "order {order_id} should carry the group and type"
+        );
+        assert!(
+            request.encode_simple().contains("|exit-1|"),
+            "order {order_id} should carry the group name"
+        );
+    }
+}
+
+#[test]
+fn test_place_bracket_order_rejects_inverted_prices() {
+    let message_bus = Arc::new(MessageBusStub::default());
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    client.set_next_order_id(40);
+
+    let contract = Contract::stock("AAPL");
+    let cases = [
+        (
+            "take profit below buy entry",
+            order_builder::limit_order(Action::Buy, 100.0, 185.0),
+            order_builder::limit_order(Action::Sell, 100.0, 180.0),
+            order_builder::stop(Action::Sell, 100.0, 175.0),
+        ),
+        (
+            "stop loss above buy entry",
+            order_builder::limit_order(Action::Buy, 100.0, 185.0),
+            order_builder::limit_order(Action::Sell, 100.0, 190.0),
+            order_builder::stop(Action::Sell, 100.0, 186.0),
+        ),
+        (
+            "take profit above sell entry",
+            order_builder::limit_order(Action::Sell, 100.0, 185.0),
+            order_builder::limit_order(Action::Buy, 100.0, 190.0),
+            order_builder::stop(Action::Buy, 100.0, 195.0),
+        ),
+        (
+            "stop loss without a price",
+            order_builder::limit_order(Action::Buy, 100.0, 185.0),
+            order_builder::limit_order(Action::Sell, 100.0, 190.0),
+            order_builder::market_order(Action::Sell, 100.0),
+        ),
+    ];
+
+    for (name, entry, take_profit, stop_loss) in cases {
+        let result = client.place_bracket_order(&contract, &entry, &take_profit, &stop_loss);
+        assert!(matches!(result, Err(Error::InvalidArgument(_))), "{name}: {result:?}");
+    }
+
+    assert!(client.message_bus.request_messages().is_empty(), "no order should be sent");
+    assert_eq!(client.next_order_id(), 40, "no order ids should be allocated");
+}
+
+#[test]
+fn test_modify_order() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["3|13|Submitted|0|100|0|1376327563|0|0|100||0||".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let contract = Contract::stock("TSLA");
+    let order = order_builder::limit_order(Action::Buy, 100.0, 200.0);
+
+    let result = client.modify_order(0, &contract, &order);
+    assert!(matches!(result, Err(Error::InvalidArgument(_))), "zero order id should be rejected");
+    assert!(
+        client.message_bus.request_messages().is_empty(),
+        "no message should be sent for a zero order id"
+    );
+
+    let events = client.modify_order(13, &contract, &order).expect("modify order failed");
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(request_messages.len(), 1);
+    assert_eq!(
+        request_messages[0].encode_simple(),
+        encoders::encode_place_order(server_versions::SIZE_RULES, 13, &contract, &order)
+            .unwrap()
+            .encode_simple(),
+        "modify_order should send the place order request"
+    );
+
+    assert!(matches!(events.next(), Some(PlaceOrder::OrderStatus(_))));
+}
+
+#[test]
+fn test_try_place_order_immediate_rejection() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["4|2|13|200|No security definition has been found for the request||".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let contract = Contract::stock("NOTASYMBOL");
+    let order = order_builder::market_order(Action::Buy, 100.0);
+
+    let result = client.try_place_order(13, &contract, &order, Duration::from_millis(10));
+    match result {
+        Err(Error::TwsError { code, message }) => {
+            assert_eq!(code, 200);
+            assert_eq!(message, "No security definition has been found for the request");
+        }
+        Err(e) => panic!("unexpected error: {e}"),
+        Ok(_) => panic!("expected immediate rejection"),
+    }
+    assert_eq!(client.message_bus.request_messages().len(), 1, "order should be sent");
+}
+
+#[test]
+fn test_try_place_order_accepted() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "4|2|13|399|Order Message: Warning: your order will not be placed at the exchange until 2023-03-06 09:30:00 US/Eastern||".to_owned(),
+            "3|13|PreSubmitted|0|100|0|1376327563|0|0|100||0||".to_owned(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let contract = Contract::stock("TSLA");
+    let order = order_builder::market_order(Action::Buy, 100.0);
+
+    let events = client
+        .try_place_order(13, &contract, &order, Duration::from_millis(10))
+        .expect("order should be accepted");
+
+    match events.next() {
+        Some(PlaceOrder::Message(notice)) => assert_eq!(notice.code, 399, "peeked warning should be returned first"),
+        other => panic!("expected warning notice, got {other:?}"),
+    }
+    assert!(matches!(events.next(), Some(PlaceOrder::OrderStatus(_))));
+    assert!(events.next().is_none());
+}
+
+#[test]
+fn test_try_place_order_times_out() {
+    let message_bus = Arc::new(MessageBusStub::default());
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let contract = Contract::stock("TSLA");
+    let order = order_builder::market_order(Action::Buy, 100.0);
+
+    let events = client
+        .try_place_order(13, &contract, &order, Duration::from_millis(10))
+        .expect("order without a response should be returned");
+    assert!(events.try_next().is_none());
+}
+
+#[test]
+fn test_try_place_order_multiplexed() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "3|13|PreSubmitted|0|100|0|1376327563|0|0|100||0||".to_owned(),
+            "3|13|Filled|100|0|196.52|1376327563|0|196.52|100||0||".to_owned(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let contract = Contract::stock("TSLA");
+    let order = order_builder::market_order(Action::Buy, 100.0);
+
+    let events = client
+        .try_place_order(13, &contract, &order, Duration::from_millis(10))
+        .expect("order should be accepted");
+
+    let statuses: Vec<String> = crate::client::multiplex(vec![events])
+        .map(|event| match event {
+            Ok(PlaceOrder::OrderStatus(status)) => status.status,
+            other => panic!("expected order status, got {other:?}"),
+        })
+        .collect();
+    assert_eq!(statuses, vec!["PreSubmitted", "Filled"], "peeked status should not be lost");
+}
+
+#[test]
+fn test_place_order_fractional_crypto_quantity() {
+    let message_bus = Arc::new(MessageBusStub::default());
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let contract = Contract::crypto_on("BTC", "ZEROHASH");
+    let order = order_builder::limit_order(Action::Buy, 0.0125, 60000.0);
+
+    client.place_order(13, &contract, &order).expect("place order failed");
+
+    let request = client.message_bus.request_messages()[0].encode_simple();
+    let fields: Vec<&str> = request.split('|').collect();
+    assert_eq!(&fields[..11], &["3", "13", "0", "BTC", "CRYPTO", "", "0", "", "", "ZEROHASH", ""]);
+    assert_eq!(fields[16], "BUY");
+    assert_eq!(fields[17], "0.0125", "fractional quantity should be encoded as a decimal");
+
+    let message_bus = Arc::new(MessageBusStub::default());
+    let client = Client::stubbed(message_bus, server_versions::FRACTIONAL_POSITIONS - 1);
+
+    let result = client.place_order(13, &contract, &order);
+    assert!(
+        matches!(result, Err(Error::ServerVersion(_, _, _))),
+        "older servers cannot take fractional quantities"
+    );
+}
+
+#[test]
+fn test_place_order_imbalance_only_requires_server_version() {
+    let message_bus = Arc::new(MessageBusStub::default());
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let order = OrderBuilder::new(order_builder::market_on_close(Action::Buy, 100.0))
+        .imbalance_only()
+        .build()
+        .unwrap();
+
+    let result = client.place_order(13, &Contract::stock("AAPL"), &order);
+    assert!(
+        matches!(
+            result,
+            Err(Error::ServerVersion(server_versions::IMBALANCE_ONLY, server_versions::SIZE_RULES, _))
+        ),
+        "imbalance_only cannot be sent yet: {result:?}"
+    );
+    assert!(client.message_bus.request_messages().is_empty(), "nothing should be sent");
+}
+
+fn what_if_open_order(order_id: i32, initial_margin_change: f64, commission: f64) -> String {
+    format!(
+        "5|{order_id}|76792991|TSLA|STK||0|?||SMART|USD|TSLA|NMS|BUY|100|LMT|185.0|0.0|DAY||DU1234567||0||100|1376327563|0|0|0||1376327563.0/DU1234567/100||||||||||0||-1|0||||||2147483647|0|0|0||3|0|0||0|0||0|None||0||||?|0|0||0|0||||||0|0|0|2147483647|2147483647|||0||IB|0|0||0|1|PreSubmitted||||{initial_margin_change}||||||{commission}|||USD||0|0|0|None|1.7976931348623157E308|1.7976931348623157E308|1.7976931348623157E308|1.7976931348623157E308|1.7976931348623157E308|1.7976931348623157E308|0||||0|1|0|0|0|||0||"
+    )
+}
+
+#[test]
+fn test_what_if_bracket() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            what_if_open_order(100, 18500.0, 1.0),
+            what_if_open_order(101, 0.0, 1.05),
+            what_if_open_order(102, 0.0, 1.1),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    client.set_next_order_id(100);
+
+    let contract = Contract::stock("TSLA");
+    let bracket = bracket_order(1, Action::Buy, 100.0, 185.0, 190.0, 180.0);
+
+    let previews = client.what_if_bracket(&contract, &bracket).expect("what-if bracket failed");
+
+    assert_eq!(previews.len(), 3, "one preview per leg");
+    assert_eq!(previews[0].initial_margin_change, Some(18500.0), "previews[0].initial_margin_change");
+    assert_eq!(previews[0].commission, Some(1.0), "previews[0].commission");
+    assert_eq!(previews[1].commission, Some(1.05), "previews[1].commission");
+    assert_eq!(previews[2].commission, Some(1.1), "previews[2].commission");
+    assert_eq!(previews[2].commission_currency, "USD", "previews[2].commission_currency");
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(request_messages.len(), 3, "each leg should be sent");
+    for (i, request) in request_messages.iter().enumerate() {
+        assert_eq!(request[1], (100 + i as i32).to_string(), "request[{i}].order_id");
+    }
+}
+
+#[test]
+fn test_what_if_bracket_rejected_leg() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["4|2|100|201|Order rejected - reason:Insufficient margin||".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    client.set_next_order_id(100);
+
+    let contract = Contract::stock("TSLA");
+    let bracket = bracket_order(1, Action::Buy, 100.0, 185.0, 190.0, 180.0);
+
+    let result = client.what_if_bracket(&contract, &bracket);
+    assert!(matches!(result, Err(Error::TwsError { code: 201, .. })), "{result:?}");
+    assert_eq!(client.message_bus.request_messages().len(), 1, "remaining legs should not be sent");
+}
+
+#[test]
+fn test_versioned_order_round_trip() {
+    let order = order_builder::limit_order(Action::Buy, 100.0, 185.0);
+
+    let json = serde_json::to_string(&VersionedOrder::new(order.clone())).expect("serialize versioned order");
+    assert!(json.starts_with("{\"version\":2,\"order\":{"), "{json}");
+
+    let restored: VersionedOrder = serde_json::from_str(&json).expect("deserialize versioned order");
+    assert_eq!(restored.version, VersionedOrder::CURRENT_VERSION);
+    assert_eq!(restored.into_order(), order);
+}
+
+#[test]
+fn test_versioned_order_from_v1() {
+    // Version 1 orders were persisted as a bare Order and may predate fields added since.
+    let json = r#"{"order_id":13,"action":"Buy","total_quantity":100.0,"order_type":"LMT","limit_price":185.0,"tif":"DAY","transmit":true}"#;
+
+    let restored: VersionedOrder = serde_json::from_str(json).expect("deserialize v1 order");
+    assert_eq!(restored.version, VersionedOrder::CURRENT_VERSION);
+
+    let order = restored.into_order();
+    assert_eq!(order.order_id, 13);
+    assert_eq!(order.action, Action::Buy);
+    assert_eq!(order.total_quantity, 100.0);
+    assert_eq!(order.order_type, "LMT");
+    assert_eq!(order.limit_price, Some(185.0));
+    assert_eq!(order.account, Order::default().account, "missing fields should take defaults");
+    assert_eq!(order.what_if, false);
+}
+
+#[test]
+fn test_versioned_order_from_v2() {
+    // Complete order written at version 2, when oca_type was stored as its integer code.
+    let json = include_str!("../../tests/data/order-v2.json");
+
+    let restored: VersionedOrder = serde_json::from_str(json).expect("deserialize v2 order");
+    assert_eq!(restored.version, VersionedOrder::CURRENT_VERSION);
+
+    let order = restored.into_order();
+    assert_eq!(order.order_id, 42);
+    assert_eq!(order.action, Action::Sell);
+    assert_eq!(order.limit_price, Some(190.0));
+    assert_eq!(order.oca_group, "exit-1");
+    assert_eq!(order.oca_type, 2);
+    assert_eq!(order.oca_type_value(), Some(OcaType::ReduceWithBlock));
+}
+
+#[test]
+fn test_versioned_order_rejects_newer_version() {
+    let json = r#"{"version":99,"order":{"order_id":13}}"#;
+
+    let result = serde_json::from_str::<VersionedOrder>(json);
+    assert!(result.is_err(), "future versions should be rejected");
+    assert!(result.unwrap_err().to_string().contains("unsupported order version 99"));
+}
+
+#[test]
+fn test_commission_total() {
+    let report = |currency: &str, commission: f64| CommissionReport {
+        currency: currency.to_owned(),
+        commission,
+        ..Default::default()
+    };
+    let reports = [
+        report("USD", 1.0),
+        report("EUR", 2.0),
+        report("USD", 0.5),
+        report("JPY", 300.0),
+        report("JPY", 200.0),
+    ];
+    let rates = HashMap::from([("EUR".to_owned(), 1.25)]);
+
+    let total = CommissionReport::total(&reports, "USD", &rates);
+
+    assert_eq!(total.currency, "USD");
+    assert_eq!(total.commission, 4.0, "1.0 + 0.5 USD plus 2.0 EUR at 1.25");
+    assert_eq!(
+        total.unconverted,
+        HashMap::from([("JPY".to_owned(), 500.0)]),
+        "currencies without a rate are grouped"
+    );
+
+    let total = CommissionReport::total(&[], "USD", &rates);
+    assert_eq!(total.commission, 0.0);
+    assert!(total.unconverted.is_empty());
+}
+
+#[test]
+fn test_order_tracker_partial_fill_then_cancel() {
+    let execution = |execution_id: &str, shares: f64, price: f64| {
+        PlaceOrder::ExecutionData(ExecutionData {
+            execution: Execution {
+                order_id: 13,
+                execution_id: execution_id.to_owned(),
+                shares,
+                price,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    };
+    let status = |status: &str, filled: f64, remaining: f64, average_fill_price: f64| {
+        PlaceOrder::OrderStatus(OrderStatus {
+            order_id: 13,
+            status: status.to_owned(),
+            filled,
+            remaining,
+            average_fill_price,
+            ..Default::default()
+        })
+    };
+    let commission = |execution_id: &str, commission: f64| {
+        PlaceOrder::CommissionReport(CommissionReport {
+            execution_id: execution_id.to_owned(),
+            commission,
+            ..Default::default()
+        })
+    };
+
+    let updates = vec![
+        status("Submitted", 0.0, 100.0, 0.0),
+        execution("e1", 40.0, 10.0),
+        commission("e1", 1.0),
+        status("Submitted", 40.0, 60.0, 10.0),
+        execution("e1", 40.0, 10.0), // duplicate execution is counted once
+        execution("e2", 20.0, 11.0),
+        commission("e2", 0.5),
+        commission("other", 9.0), // commission of another order
+        PlaceOrder::OrderStatus(OrderStatus {
+            order_id: 14,
+            status: "Filled".to_owned(),
+            ..Default::default()
+        }),
+        status("Cancelled", 60.0, 40.0, 620.0 / 60.0),
+        status("Submitted", 0.0, 0.0, 0.0), // ignored, tracking stops at the final status
+    ];
+
+    let mut tracker = OrderTracker::new(13, 100.0);
+    for update in &updates[..5] {
+        tracker.update(update);
+    }
+    assert_eq!(tracker.filled(), 40.0);
+    assert_eq!(tracker.remaining(), 60.0);
+    assert!(!tracker.is_done());
+    assert_eq!(tracker.summary(), None, "no summary while the order is working");
+
+    let summary = OrderTracker::new(13, 100.0).track(updates).expect("order should be done");
+
+    assert_eq!(summary.order_id, 13);
+    assert_eq!(summary.status, "Cancelled");
+    assert_eq!(summary.filled, 60.0);
+    assert!(
+        (summary.average_price - 620.0 / 60.0).abs() < 1e-9,
+        "average price {}",
+        summary.average_price
+    );
+    assert_eq!(summary.cancelled, 40.0);
+    assert_eq!(summary.commission, 1.5);
+    assert!(summary.commission_complete);
+}
+
+#[test]
+fn test_order_tracker_waits_for_late_commission() {
+    let execution = |execution_id: &str| {
+        PlaceOrder::ExecutionData(ExecutionData {
+            execution: Execution {
+                order_id: 13,
+                execution_id: execution_id.to_owned(),
+                shares: 50.0,
+                price: 10.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    };
+    let commission = |execution_id: &str| {
+        PlaceOrder::CommissionReport(CommissionReport {
+            execution_id: execution_id.to_owned(),
+            commission: 1.0,
+            ..Default::default()
+        })
+    };
+    let filled = PlaceOrder::OrderStatus(OrderStatus {
+        order_id: 13,
+        status: "Filled".to_owned(),
+        filled: 100.0,
+        average_fill_price: 10.0,
+        ..Default::default()
+    });
+
+    let updates = vec![
+        execution("e1"),
+        commission("e1"),
+        execution("e2"),
+        filled.clone(),
+        commission("e2"),
+        commission("e2"), // duplicate report is counted once
+    ];
+    let summary = OrderTracker::new(13, 100.0).track(updates).expect("order should be done");
+    assert_eq!(summary.commission, 2.0, "commission reported after the final status");
+    assert!(summary.commission_complete);
+
+    let updates = vec![execution("e1"), commission("e1"), execution("e2"), filled];
+    let summary = OrderTracker::new(13, 100.0).track(updates).expect("order should be done");
+    assert_eq!(summary.commission, 1.0);
+    assert!(!summary.commission_complete, "e2 has no commission report");
+}
+
+#[test]
+fn test_order_tracker_track_subscription() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![
+            "3|13|PreSubmitted|0|100|0|1376327563|0|0|100||0||".to_owned(),
+            "11|-1|13|76792991|TSLA|STK||0.0|||ISLAND|USD|TSLA|NMS|00025b46.63f8f39c.01.01|20230224  12:04:56|DU1234567|ISLAND|BOT|100|196.52|1376327563|100|0|100|196.52|||||2||".to_owned(),
+            "3|13|Filled|100|0|196.52|1376327563|0|196.52|100||0||".to_owned(),
+            "59|1|00025b46.63f8f39c.01.01|1.0|USD|1.7976931348623157E308|1.7976931348623157E308|||".to_owned(),
+        ],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    let order = order_builder::market_order(Action::Buy, 100.0);
+    let subscription = client
+        .place_order(13, &Contract::stock("TSLA"), &order)
+        .expect("place order request failed");
+
+    let summary = OrderTracker::new(13, 100.0)
+        .track_subscription(&subscription, Duration::from_millis(100))
+        .expect("order should be done");
+
+    assert_eq!(summary.status, "Filled");
+    assert_eq!(summary.filled, 100.0);
+    assert_eq!(summary.average_price, 196.52);
+    assert_eq!(summary.commission, 1.0, "commission report after the Filled status");
+    assert!(summary.commission_complete);
+}
+
+#[test]
+fn open_orders_ignores_messages_after_end() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["53|1|".to_owned(), "9|1|43||".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+
+    let subscription = client.all_open_orders().expect("request failed");
+
+    assert!(subscription.next().is_none(), "expected end of stream");
+    assert!(subscription.next_timeout(Duration::from_millis(10)).is_none());
+    assert!(subscription.error().is_none(), "unexpected error: {:?}", subscription.error());
+    assert!(subscription.try_next().is_none());
+    assert!(subscription.next().is_none());
+}
+
+#[test]
+fn open_orders_continue_after_connection_reset() {
+    let open_order = "5|13|76792991|TSLA|STK||0|?||SMART|USD|TSLA|NMS|BUY|100|MKT|0.0|0.0|DAY||DU1234567||0||100|1376327563|0|0|0||1376327563.0/DU1234567/100||||||||||0||-1|0||||||2147483647|0|0|0||3|0|0||0|0||0|None||0||||?|0|0||0|0||||||0|0|0|2147483647|2147483647|||0||IB|0|0||0|0|PreSubmitted|1.7976931348623157E308|1.7976931348623157E308|1.7976931348623157E308|1.7976931348623157E308|1.7976931348623157E308|1.7976931348623157E308|1.7976931348623157E308|1.7976931348623157E308|1.7976931348623157E308||||||0|0|0|None|1.7976931348623157E308|1.7976931348623157E308|1.7976931348623157E308|1.7976931348623157E308|1.7976931348623157E308|1.7976931348623157E308|0||||0|1|0|0|0|||0||";
+    let message = |text: &str| Ok(ResponseMessage::from(&text.replace('|', "\0")));
+
+    let (sender, receiver) = crossbeam::channel::unbounded();
+    sender.send(message("53|1|")).unwrap();
+    sender.send(Err(Error::ConnectionReset)).unwrap();
+    sender.send(message(open_order)).unwrap();
+    sender.send(message("53|1|")).unwrap();
+
+    let message_bus = Arc::new(MessageBusStub::default());
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    let internal = SubscriptionBuilder::new()
+        .shared_receiver(Arc::new(receiver))
+        .message_type(OutgoingMessages::RequestAllOpenOrders)
+        .build();
+    let subscription: Subscription<Orders> = Subscription::new(&client, internal, ResponseContext::default());
+
+    assert!(subscription.next().is_none(), "expected end of stream");
+    assert!(subscription.next().is_none(), "expected the connection reset");
+    assert!(matches!(subscription.error(), Some(Error::ConnectionReset)), "{:?}", subscription.error());
+
+    match subscription.next() {
+        Some(Orders::OrderData(data)) => assert_eq!(data.order_id, 13),
+        other => panic!("expected the re-sent open order, got {other:?}"),
+    }
+    assert!(subscription.next().is_none(), "expected end of the re-sent stream");
+    assert!(subscription.error().is_none());
+}
+
+#[test]
+fn test_order_status_value() {
+    let statuses = [
+        ("ApiPending", OrderStatusValue::ApiPending, false),
+        ("PendingSubmit", OrderStatusValue::PendingSubmit, false),
+        ("PendingCancel", OrderStatusValue::PendingCancel, false),
+        ("PreSubmitted", OrderStatusValue::PreSubmitted, false),
+        ("Submitted", OrderStatusValue::Submitted, false),
+        ("ApiCancelled", OrderStatusValue::ApiCancelled, true),
+        ("Cancelled", OrderStatusValue::Cancelled, true),
+        ("Filled", OrderStatusValue::Filled, true),
+        ("Inactive", OrderStatusValue::Inactive, true),
+    ];
+
+    for (text, expected, terminal) in statuses {
+        let value = OrderStatusValue::from(text);
+        assert_eq!(value, expected, "status {text}");
+        assert_eq!(value.to_string(), text, "display of {text}");
+        assert_eq!(value.is_terminal(), terminal, "is_terminal for {text}");
+
+        let status = OrderStatus {
+            status: text.to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(status.status_value(), expected);
+        assert_eq!(status.is_terminal(), terminal);
+    }
+
+    let unknown = OrderStatusValue::from("Unknown");
+    assert_eq!(unknown, OrderStatusValue::Other("Unknown".to_owned()));
+    assert_eq!(unknown.to_string(), "Unknown");
+    assert!(!unknown.is_terminal());
+
+    let state = OrderState {
+        status: "Filled".to_owned(),
+        ..Default::default()
+    };
+    assert_eq!(state.status_value(), OrderStatusValue::Filled);
+}
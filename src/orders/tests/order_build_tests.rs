@@ -169,11 +169,12 @@ mod complex_order_tests {
     fn test_one_cancels_all() {
         let order1 = limit_order(Action::Buy, 100.0, 50.0);
         let order2 = limit_order(Action::Sell, 100.0, 52.0);
-        let orders = one_cancels_all("TestOCA", vec![order1, order2], 2);
+        let orders = one_cancels_all("TestOCA", vec![order1, order2], OcaType::ReduceWithBlock);
 
         for order in &orders {
             assert_eq!(order.oca_group, "TestOCA");
             assert_eq!(order.oca_type, 2);
+            assert_eq!(order.oca_type_value(), Some(OcaType::ReduceWithBlock));
         }
 
         assert_eq!(orders[0].action, Action::Buy);
@@ -732,3 +733,524 @@ mod additional_specialized_order_tests {
         assert!(order.what_if);
     }
 }
+
+#[cfg(test)]
+mod order_builder_tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_cancel_parent() {
+        let order = OrderBuilder::new(limit_order(Action::Sell, 100.0, 55.0))
+            .auto_cancel_parent()
+            .build()
+            .unwrap();
+
+        assert!(order.auto_cancel_parent);
+        assert_eq!(order.auto_cancel_date, "");
+
+        let contract = Contract::stock("AAPL");
+        let message = encoders::encode_place_order(server_versions::AUTO_CANCEL_PARENT, 7, &contract, &order).unwrap();
+        let fields = message.encode_simple();
+        assert!(fields.ends_with("|1|"), "auto_cancel_parent should be the last field: {fields}");
+
+        let older = encoders::encode_place_order(server_versions::AUTO_CANCEL_PARENT - 1, 7, &contract, &order).unwrap();
+        assert_eq!(
+            format!("{}1|", older.encode_simple()),
+            fields,
+            "older servers should omit auto_cancel_parent"
+        );
+    }
+
+    #[test]
+    fn test_discretionary() {
+        let plain = limit_order(Action::Buy, 100.0, 50.0);
+        let order = OrderBuilder::new(plain.clone()).discretionary(0.5).d_peg().build().unwrap();
+
+        assert_eq!(order.discretionary_amt, 0.5);
+        assert!(order.discretionary_up_to_limit_price);
+
+        let contract = Contract::stock("AAPL");
+        let plain = encoders::encode_place_order(server_versions::D_PEG_ORDERS, 7, &contract, &plain)
+            .unwrap()
+            .encode_simple();
+        let encoded = encoders::encode_place_order(server_versions::D_PEG_ORDERS, 7, &contract, &order)
+            .unwrap()
+            .encode_simple();
+
+        let changed: Vec<(&str, &str)> = plain.split('|').zip(encoded.split('|')).filter(|(a, b)| a != b).collect();
+        assert_eq!(changed, vec![("0", "0.5"), ("0", "1")], "encoded: {encoded}");
+    }
+
+    #[test]
+    fn test_discretionary_requires_limit_price() {
+        let result = OrderBuilder::new(market_order(Action::Buy, 100.0)).discretionary(0.5).build();
+        assert!(matches!(result, Err(Error::InvalidArgument(_))), "discretionary: {result:?}");
+
+        let result = OrderBuilder::new(market_order(Action::Buy, 100.0)).d_peg().build();
+        assert!(matches!(result, Err(Error::InvalidArgument(_))), "d_peg: {result:?}");
+
+        let result = OrderBuilder::new(limit_order(Action::Buy, 100.0, 50.0)).discretionary(-0.5).build();
+        assert!(matches!(result, Err(Error::InvalidArgument(_))), "negative amount: {result:?}");
+    }
+
+    #[test]
+    fn test_override_constraints() {
+        let plain = limit_order(Action::Buy, 100.0, 50.0);
+        let order = OrderBuilder::new(plain.clone()).override_constraints().build().unwrap();
+
+        assert!(order.override_percentage_constraints);
+
+        let contract = Contract::stock("AAPL");
+        let plain = encoders::encode_place_order(server_versions::SIZE_RULES, 7, &contract, &plain)
+            .unwrap()
+            .encode_simple();
+        let encoded = encoders::encode_place_order(server_versions::SIZE_RULES, 7, &contract, &order)
+            .unwrap()
+            .encode_simple();
+
+        let changed: Vec<(usize, &str, &str)> = plain
+            .split('|')
+            .zip(encoded.split('|'))
+            .enumerate()
+            .filter(|(_, (a, b))| a != b)
+            .map(|(i, (a, b))| (i, a, b))
+            .collect();
+        assert_eq!(changed, vec![(62, "0", "1")], "encoded: {encoded}");
+    }
+
+    #[test]
+    fn test_mifid2_valid_combinations() {
+        let order = OrderBuilder::new(limit_order(Action::Buy, 100.0, 10.0))
+            .mifid2_decision_maker("DM1")
+            .mifid2_execution_algo("ALGO1")
+            .build()
+            .unwrap();
+        assert_eq!(order.mifid2_decision_maker, "DM1");
+        assert_eq!(order.mifid2_decision_algo, "");
+        assert_eq!(order.mifid2_execution_trader, "");
+        assert_eq!(order.mifid2_execution_algo, "ALGO1");
+
+        let order = OrderBuilder::new(limit_order(Action::Buy, 100.0, 10.0))
+            .mifid2_decision_algo("ALGO2")
+            .mifid2_execution_trader("TRADER1")
+            .build()
+            .unwrap();
+        assert_eq!(order.mifid2_decision_algo, "ALGO2");
+        assert_eq!(order.mifid2_execution_trader, "TRADER1");
+
+        assert!(
+            OrderBuilder::new(limit_order(Action::Buy, 100.0, 10.0)).build().is_ok(),
+            "non-MiFID orders are valid"
+        );
+    }
+
+    #[test]
+    fn test_mifid2_invalid_combinations() {
+        let cases = [
+            ("DM1", "ALGO1", "TRADER1", ""),
+            ("", "", "TRADER1", ""),
+            ("DM1", "", "TRADER1", "ALGO1"),
+            ("DM1", "", "", ""),
+        ];
+
+        for (decision_maker, decision_algo, execution_trader, execution_algo) in cases {
+            let result = OrderBuilder::new(limit_order(Action::Buy, 100.0, 10.0))
+                .mifid2_decision_maker(decision_maker)
+                .mifid2_decision_algo(decision_algo)
+                .mifid2_execution_trader(execution_trader)
+                .mifid2_execution_algo(execution_algo)
+                .build();
+            assert!(
+                matches!(result, Err(Error::InvalidArgument(_))),
+                "expected invalid argument for {decision_maker:?}/{decision_algo:?}/{execution_trader:?}/{execution_algo:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_clearing() {
+        let plain = limit_order(Action::Buy, 100.0, 50.0);
+        let order = OrderBuilder::new(plain.clone())
+            .clearing("U1234567", ClearingIntent::Away)
+            .build()
+            .unwrap();
+
+        assert_eq!(order.clearing_account, "U1234567");
+        assert_eq!(order.clearing_intent, "Away");
+
+        let contract = Contract::stock("AAPL");
+        let plain = encoders::encode_place_order(server_versions::SIZE_RULES, 7, &contract, &plain)
+            .unwrap()
+            .encode_simple();
+        let encoded = encoders::encode_place_order(server_versions::SIZE_RULES, 7, &contract, &order)
+            .unwrap()
+            .encode_simple();
+
+        let changed: Vec<&str> = plain.split('|').zip(encoded.split('|')).filter(|(a, b)| a != b).map(|(_, b)| b).collect();
+        assert_eq!(changed, vec!["U1234567", "Away"], "encoded: {encoded}");
+    }
+
+    #[test]
+    fn test_clearing_validation() {
+        let order = OrderBuilder::new(limit_order(Action::Buy, 100.0, 50.0))
+            .clearing("", ClearingIntent::IB)
+            .build()
+            .unwrap();
+        assert_eq!(order.clearing_intent, "IB");
+
+        let result = OrderBuilder::new(limit_order(Action::Buy, 100.0, 50.0))
+            .clearing("", ClearingIntent::PTA)
+            .build();
+        assert!(matches!(result, Err(Error::InvalidArgument(_))), "PTA requires a clearing account");
+
+        let mut order = limit_order(Action::Buy, 100.0, 50.0);
+        order.clearing_intent = "Elsewhere".to_owned();
+        let result = OrderBuilder::new(order).build();
+        assert!(matches!(result, Err(Error::InvalidArgument(_))), "unknown clearing intent");
+
+        assert_eq!(ClearingIntent::from("PTA"), Some(ClearingIntent::PTA));
+        assert_eq!(ClearingIntent::from("away"), None);
+    }
+
+    #[test]
+    fn test_good_till_date_and_good_after_time() {
+        let start = time::macros::datetime!(2024-03-15 13:30:00 UTC);
+        let end = time::macros::datetime!(2024-03-15 20:00:00 UTC);
+
+        let order = OrderBuilder::new(limit_order(Action::Buy, 100.0, 50.0))
+            .good_after_time(ServerTimestamp::utc(start))
+            .good_till_date(ServerTimestamp::utc(end))
+            .build()
+            .unwrap();
+
+        assert_eq!(order.tif, "GTD");
+        assert_eq!(order.good_after_time, "20240315-13:30:00");
+        assert_eq!(order.good_till_date, "20240315-20:00:00");
+
+        let contract = Contract::stock("AAPL");
+        let encoded = encoders::encode_place_order(server_versions::SIZE_RULES, 7, &contract, &order)
+            .unwrap()
+            .encode_simple();
+        assert!(encoded.contains("|20240315-13:30:00|20240315-20:00:00|"), "encoded: {encoded}");
+    }
+
+    #[test]
+    fn test_short_sale_exempt() {
+        assert_eq!(Order::default().exempt_code, -1, "orders default to no exemption");
+
+        let mut order = limit_order(Action::SellShort, 100.0, 50.0);
+        order.designated_location = "LOCATION".to_owned();
+
+        let order = OrderBuilder::new(order).short_sale_exempt().build().unwrap();
+        assert_eq!(order.exempt_code, 0);
+
+        let contract = Contract::stock("AAPL");
+        let message = encoders::encode_place_order(server_versions::SIZE_RULES, 7, &contract, &order).unwrap();
+        let location = (0..message.len())
+            .find(|&i| message[i] == "LOCATION")
+            .expect("designated location should be encoded");
+        assert_eq!(message[location + 1], "0", "exempt code follows designated location");
+
+        let order = OrderBuilder::new(limit_order(Action::Sell, 100.0, 50.0)).short_sale_exempt().build();
+        assert!(order.is_ok(), "sells may be short sales");
+    }
+
+    #[test]
+    fn test_short_sale_exempt_requires_short_sale() {
+        for action in [Action::Buy, Action::SellLong] {
+            let result = OrderBuilder::new(limit_order(action, 100.0, 50.0)).short_sale_exempt().build();
+            assert!(matches!(result, Err(Error::InvalidArgument(_))), "{action}: {result:?}");
+        }
+
+        let order = OrderBuilder::new(limit_order(Action::Buy, 100.0, 50.0)).build().unwrap();
+        assert_eq!(order.exempt_code, -1, "default exempt code is not validated");
+    }
+
+    #[test]
+    fn test_auction_strategy() {
+        let strategies = [
+            (AuctionStrategy::Match, 1),
+            (AuctionStrategy::Improvement, 2),
+            (AuctionStrategy::Transparent, 3),
+        ];
+
+        for (strategy, value) in strategies {
+            assert_eq!(strategy.to_field(), value.to_string(), "{strategy:?}.to_field()");
+            assert_eq!(AuctionStrategy::from(value), Some(strategy), "AuctionStrategy::from({value})");
+
+            let order = OrderBuilder::new(limit_order(Action::Buy, 10.0, 1.25))
+                .auction_strategy(strategy)
+                .build()
+                .unwrap();
+            assert_eq!(order.auction_strategy, Some(value), "{strategy:?}");
+        }
+
+        assert_eq!(AuctionStrategy::from(0), None);
+        assert_eq!(AuctionStrategy::from(4), None);
+    }
+
+    #[test]
+    fn test_volatility_enums() {
+        assert_eq!(VolatilityType::Daily.to_field(), "1");
+        assert_eq!(VolatilityType::Annual.to_field(), "2");
+        assert_eq!(VolatilityType::from(1), Some(VolatilityType::Daily));
+        assert_eq!(VolatilityType::from(2), Some(VolatilityType::Annual));
+        assert_eq!(VolatilityType::from(0), None);
+
+        assert_eq!(ReferencePriceType::Midpoint.to_field(), "1");
+        assert_eq!(ReferencePriceType::BidOrAsk.to_field(), "2");
+        assert_eq!(ReferencePriceType::from(1), Some(ReferencePriceType::Midpoint));
+        assert_eq!(ReferencePriceType::from(2), Some(ReferencePriceType::BidOrAsk));
+        assert_eq!(ReferencePriceType::from(3), None);
+    }
+
+    #[test]
+    fn test_volatility_order() {
+        let order = Order {
+            action: Action::Buy,
+            total_quantity: 1.0,
+            ..Order::default()
+        };
+
+        let order = OrderBuilder::new(order)
+            .volatility(40.0, VolatilityType::Annual)
+            .reference_price_type(ReferencePriceType::BidOrAsk)
+            .build()
+            .unwrap();
+
+        assert_eq!(order.order_type, "VOL");
+        assert_eq!(order.volatility, Some(40.0));
+        assert_eq!(order.volatility_type, Some(2));
+        assert_eq!(order.reference_price_type, Some(2));
+
+        let contract = Contract::option("AAPL", "20240621", 180.0, "C");
+        let encoded = encoders::encode_place_order(server_versions::SIZE_RULES, 7, &contract, &order)
+            .unwrap()
+            .encode_simple();
+        assert!(encoded.contains("|40|2|"), "volatility and volatility type should be encoded: {encoded}");
+    }
+
+    #[test]
+    fn test_relative_amount_offset() {
+        let order = OrderBuilder::new(limit_order(Action::Buy, 100.0, 50.0))
+            .relative(RelOffset::Amount(0.02))
+            .build()
+            .unwrap();
+
+        assert_eq!(order.order_type, "REL");
+        assert_eq!(order.aux_price, Some(0.02));
+        assert_eq!(order.percent_offset, None);
+        assert_eq!(order.limit_price, Some(50.0), "limit price acts as a cap");
+    }
+
+    #[test]
+    fn test_financial_advisor_allocation() {
+        let fa_fields = |allocation: FaAllocation| {
+            let order = OrderBuilder::new(market_order(Action::Buy, 100.0))
+                .financial_advisor(allocation)
+                .build()
+                .unwrap();
+            (order.fa_group, order.fa_method, order.fa_percentage, order.fa_profile)
+        };
+        let group = |method: FaMethod| FaAllocation::Group {
+            name: "Growth".to_owned(),
+            method,
+        };
+        let fields = |group: &str, method: &str, percentage: &str, profile: &str| {
+            (group.to_owned(), method.to_owned(), percentage.to_owned(), profile.to_owned())
+        };
+
+        assert_eq!(fa_fields(group(FaMethod::EqualQuantity)), fields("Growth", "EqualQuantity", "", ""));
+        assert_eq!(fa_fields(group(FaMethod::NetLiq)), fields("Growth", "NetLiq", "", ""));
+        assert_eq!(fa_fields(group(FaMethod::AvailableEquity)), fields("Growth", "AvailableEquity", "", ""));
+        assert_eq!(fa_fields(group(FaMethod::PctChange(-25.5))), fields("Growth", "PctChange", "-25.5", ""));
+        assert_eq!(fa_fields(FaAllocation::Profile("Balanced".to_owned())), fields("", "", "", "Balanced"));
+
+        // A later allocation replaces an earlier one.
+        let order = OrderBuilder::new(market_order(Action::Buy, 100.0))
+            .financial_advisor(group(FaMethod::PctChange(10.0)))
+            .financial_advisor(FaAllocation::Profile("Balanced".to_owned()))
+            .build()
+            .unwrap();
+        assert_eq!(order.fa_group, "");
+        assert_eq!(order.fa_percentage, "");
+    }
+
+    #[test]
+    fn test_relative_percent_offset() {
+        let order = OrderBuilder::new(limit_order(Action::Sell, 100.0, 50.0))
+            .relative(RelOffset::Amount(0.02))
+            .relative(RelOffset::Percent(0.1))
+            .build()
+            .unwrap();
+
+        assert_eq!(order.order_type, "REL");
+        assert_eq!(order.percent_offset, Some(0.1));
+        assert_eq!(order.aux_price, None, "amount offset should be cleared");
+
+        let contract = Contract::stock("AAPL");
+        let encoded = encoders::encode_place_order(server_versions::SIZE_RULES, 7, &contract, &order)
+            .unwrap()
+            .encode_simple();
+        assert!(encoded.contains("|REL|50|||"), "aux price should be empty: {encoded}");
+    }
+
+    #[test]
+    fn test_imbalance_only() {
+        let order = OrderBuilder::new(market_on_close(Action::Buy, 100.0)).imbalance_only().build().unwrap();
+        assert!(order.imbalance_only, "order.imbalance_only");
+
+        let order = OrderBuilder::new(market_on_close(Action::Buy, 100.0)).build().unwrap();
+        assert!(!order.imbalance_only, "imbalance_only is off by default");
+    }
+
+    #[test]
+    fn test_ibkrats_pegged_to_midpoint() {
+        let order = OrderBuilder::new(pegged_to_midpoint(Action::Buy, 100.0, 0.0, 50.0))
+            .min_trade_qty(20)
+            .mid_offsets(0.01, 0.005)
+            .build()
+            .unwrap();
+
+        assert_eq!(order.order_type, "PEG MID");
+        assert_eq!(order.min_trade_qty, Some(20));
+        assert_eq!(order.mid_offset_at_whole, Some(0.01));
+        assert_eq!(order.mid_offset_at_half, Some(0.005));
+
+        let mut contract = Contract::stock("AAPL");
+        contract.exchange = "IBKRATS".to_owned();
+        let encoded = encoders::encode_place_order(server_versions::PEGBEST_PEGMID_OFFSETS, 7, &contract, &order)
+            .unwrap()
+            .encode_simple();
+        assert!(encoded.ends_with("|20|0.01|0.005|"), "IBKRATS fields should be encoded: {encoded}");
+    }
+
+    #[test]
+    fn test_ibkrats_peg_best_up_to_mid() {
+        let order = OrderBuilder::new(peg_best_order(Action::Sell, 100.0, 50.0, 10, 50, 0.02))
+            .compete_against_best_up_to_mid()
+            .mid_offsets(0.0, 0.015)
+            .build()
+            .unwrap();
+
+        assert_eq!(order.compete_against_best_offset, Some(f64::INFINITY));
+
+        let mut contract = Contract::stock("AAPL");
+        contract.exchange = "IBKRATS".to_owned();
+        let encoded = encoders::encode_place_order(server_versions::PEGBEST_PEGMID_OFFSETS, 7, &contract, &order)
+            .unwrap()
+            .encode_simple();
+        assert!(
+            encoded.ends_with("|10|50|Infinity|0|0.015|"),
+            "IBKRATS fields should be encoded: {encoded}"
+        );
+    }
+
+    #[test]
+    fn test_ibkrats_offset_increments() {
+        let peg_mid = || OrderBuilder::new(pegged_to_midpoint(Action::Buy, 100.0, 0.0, 50.0));
+        let peg_best = || OrderBuilder::new(peg_best_order(Action::Buy, 100.0, 50.0, 10, 50, 0.02));
+
+        assert!(peg_mid().mid_offsets(0.03, 0.025).build().is_ok());
+        assert!(peg_best().compete_against_best_offset(0.07).build().is_ok());
+
+        let invalid = [
+            ("mid offset at whole", peg_mid().mid_offsets(0.005, 0.005).build()),
+            ("mid offset at half", peg_mid().mid_offsets(0.01, 0.0025).build()),
+            ("compete against best offset", peg_best().compete_against_best_offset(0.015).build()),
+        ];
+        for (field, result) in invalid {
+            assert!(matches!(result, Err(Error::InvalidArgument(_))), "{field} should be rejected: {result:?}");
+        }
+    }
+
+    #[test]
+    fn test_builder_stop() {
+        let order = OrderBuilder::new(limit_order(Action::Sell, 100.0, 50.0)).stop(48.5).build().unwrap();
+
+        assert_eq!(order.order_type, "STP");
+        assert_eq!(order.aux_price, Some(48.5));
+        assert_eq!(order.limit_price, None);
+
+        // Spreads on BAG contracts can have zero or negative stop prices.
+        let order = OrderBuilder::new(market_order(Action::Sell, 1.0)).stop(-1.25).build().unwrap();
+        assert_eq!(order.aux_price, Some(-1.25));
+        assert!(OrderBuilder::new(market_order(Action::Sell, 1.0)).stop(0.0).build().is_ok());
+
+        let combo_stop = Order {
+            order_type: "STP".to_owned(),
+            aux_price: Some(-0.5),
+            ..market_order(Action::Buy, 1.0)
+        };
+        assert!(
+            OrderBuilder::new(combo_stop).build().is_ok(),
+            "existing negative stop orders pass through"
+        );
+
+        for stop_price in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let result = OrderBuilder::new(market_order(Action::Sell, 100.0)).stop(stop_price).build();
+            assert!(matches!(result, Err(Error::InvalidArgument(_))), "{stop_price} stop price: {result:?}");
+        }
+
+        let missing_stop = Order {
+            order_type: "STP".to_owned(),
+            ..market_order(Action::Sell, 100.0)
+        };
+        let result = OrderBuilder::new(missing_stop).build();
+        assert!(matches!(result, Err(Error::InvalidArgument(_))), "missing stop price: {result:?}");
+    }
+
+    #[test]
+    fn test_builder_stop_limit() {
+        let order = OrderBuilder::new(market_order(Action::Buy, 100.0))
+            .stop_limit(50.0, 50.25)
+            .build()
+            .unwrap();
+
+        assert_eq!(order.order_type, "STP LMT");
+        assert_eq!(order.aux_price, Some(50.0));
+        assert_eq!(order.limit_price, Some(50.25));
+
+        let order = OrderBuilder::new(market_order(Action::Sell, 100.0))
+            .stop_limit(50.0, 49.75)
+            .build()
+            .unwrap();
+        assert_eq!(order.limit_price, Some(49.75));
+
+        // spread stop-limit with negative prices
+        let order = OrderBuilder::new(market_order(Action::Buy, 1.0)).stop_limit(-1.0, -0.75).build().unwrap();
+        assert_eq!(order.aux_price, Some(-1.0));
+
+        // limit equal to stop is accepted on both sides
+        assert!(OrderBuilder::new(market_order(Action::Buy, 100.0)).stop_limit(50.0, 50.0).build().is_ok());
+        assert!(OrderBuilder::new(market_order(Action::Sell, 100.0))
+            .stop_limit(50.0, 50.0)
+            .build()
+            .is_ok());
+
+        let invalid = [
+            (
+                "buy limit below stop",
+                OrderBuilder::new(market_order(Action::Buy, 100.0)).stop_limit(50.0, 49.0).build(),
+            ),
+            (
+                "sell limit above stop",
+                OrderBuilder::new(market_order(Action::Sell, 100.0)).stop_limit(50.0, 51.0).build(),
+            ),
+            (
+                "short sell limit above stop",
+                OrderBuilder::new(market_order(Action::SellShort, 100.0)).stop_limit(50.0, 51.0).build(),
+            ),
+            (
+                "infinite stop",
+                OrderBuilder::new(market_order(Action::Buy, 100.0))
+                    .stop_limit(f64::INFINITY, 51.0)
+                    .build(),
+            ),
+        ];
+
+        for (case, result) in invalid {
+            assert!(matches!(result, Err(Error::InvalidArgument(_))), "{case} should be rejected: {result:?}");
+        }
+    }
+}
@@ -24,6 +24,179 @@ pub(super) fn scanner_parameters(client: &Client) -> Result<String, Error> {
     }
 }
 
+// Requests the scanner parameters and parses them into a [ScannerParameters].
+pub(super) fn scanner_parameters_parsed(client: &Client) -> Result<ScannerParameters, Error> {
+    ScannerParameters::parse(&scanner_parameters(client)?)
+}
+
+/// Scanner parameters parsed from the XML returned by [scanner_parameters](crate::Client::scanner_parameters).
+///
+/// Lists the scan codes, instruments, locations and filters that can be used to build a [ScannerSubscription].
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ScannerParameters {
+    /// Available scan types, e.g. TOP_PERC_GAIN.
+    pub scan_codes: Vec<ScanCode>,
+    /// Available instruments, e.g. STK.
+    pub instruments: Vec<ScannerInstrument>,
+    /// Location tree, e.g. STK.US > STK.US.MAJOR.
+    pub locations: Vec<ScannerLocation>,
+    /// Filters that can be passed as [TagValue]s with a subscription.
+    pub filters: Vec<ScannerFilter>,
+}
+
+/// A scan type that can be used as [ScannerSubscription::scan_code].
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ScanCode {
+    /// Value for [ScannerSubscription::scan_code].
+    pub code: String,
+    /// Name shown in TWS.
+    pub display_name: String,
+    /// Instruments the scan supports.
+    pub instruments: Vec<String>,
+}
+
+/// An instrument that can be used as [ScannerSubscription::instrument].
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ScannerInstrument {
+    /// Name shown in TWS.
+    pub name: String,
+    /// Value for [ScannerSubscription::instrument].
+    pub instrument_type: String,
+    /// Ids of the filters supported by the instrument.
+    pub filters: Vec<String>,
+}
+
+/// A node of the location tree. Its code can be used as [ScannerSubscription::location_code].
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ScannerLocation {
+    /// Name shown in TWS.
+    pub display_name: String,
+    /// Value for [ScannerSubscription::location_code].
+    pub location_code: String,
+    /// Instruments available at the location.
+    pub instruments: Vec<String>,
+    /// Nested locations.
+    pub locations: Vec<ScannerLocation>,
+}
+
+/// A scanner filter definition.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ScannerFilter {
+    /// Filter id, e.g. PRICE.
+    pub id: String,
+    /// Category shown in TWS.
+    pub category: String,
+    /// Kind of filter, e.g. RangeFilter or SimpleFilter.
+    pub kind: String,
+    /// Fields accepted by the filter.
+    pub fields: Vec<ScannerFilterField>,
+}
+
+/// A field of a [ScannerFilter]. Its code is used as the [TagValue] tag.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ScannerFilterField {
+    /// Tag for the filter, e.g. priceAbove.
+    pub code: String,
+    /// Name shown in TWS.
+    pub display_name: String,
+    /// Value type, e.g. DoubleField.
+    pub field_type: String,
+}
+
+impl ScannerParameters {
+    /// Parses the XML returned by [scanner_parameters](crate::Client::scanner_parameters). Unknown elements are ignored.
+    pub fn parse(xml: &str) -> Result<ScannerParameters, Error> {
+        let document = roxmltree::Document::parse(xml).map_err(|err| Error::Simple(format!("invalid scanner parameters XML: {err}")))?;
+        let root = document.root_element();
+
+        let mut parameters = ScannerParameters::default();
+
+        for node in root.children().filter(|node| node.is_element()) {
+            match node.tag_name().name() {
+                "ScanTypeList" => parameters.scan_codes = xml::elements(node, "ScanType").map(xml::scan_code).collect(),
+                "InstrumentList" => parameters.instruments = xml::elements(node, "Instrument").map(xml::instrument).collect(),
+                "LocationTree" => parameters.locations = xml::locations(node),
+                "FilterList" => parameters.filters = xml::elements(node, "").map(xml::filter).collect(),
+                _ => {}
+            }
+        }
+
+        Ok(parameters)
+    }
+}
+
+mod xml {
+    use roxmltree::Node;
+
+    use super::{ScanCode, ScannerFilter, ScannerFilterField, ScannerInstrument, ScannerLocation};
+
+    // Child elements with the given tag name, or all child elements when the name is empty.
+    pub(super) fn elements<'a, 'input: 'a>(node: Node<'a, 'input>, name: &'a str) -> impl Iterator<Item = Node<'a, 'input>> + 'a {
+        node.children()
+            .filter(move |child| child.is_element() && (name.is_empty() || child.tag_name().name() == name))
+    }
+
+    fn text(node: Node, name: &str) -> String {
+        elements(node, name)
+            .next()
+            .and_then(|child| child.text())
+            .unwrap_or_default()
+            .trim()
+            .to_owned()
+    }
+
+    fn list(node: Node, name: &str) -> Vec<String> {
+        text(node, name)
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_owned)
+            .collect()
+    }
+
+    pub(super) fn scan_code(node: Node) -> ScanCode {
+        ScanCode {
+            code: text(node, "scanCode"),
+            display_name: text(node, "displayName"),
+            instruments: list(node, "instruments"),
+        }
+    }
+
+    pub(super) fn instrument(node: Node) -> ScannerInstrument {
+        ScannerInstrument {
+            name: text(node, "name"),
+            instrument_type: text(node, "type"),
+            filters: list(node, "filters"),
+        }
+    }
+
+    pub(super) fn locations(tree: Node) -> Vec<ScannerLocation> {
+        elements(tree, "Location")
+            .map(|node| ScannerLocation {
+                display_name: text(node, "displayName"),
+                location_code: text(node, "locationCode"),
+                instruments: list(node, "instruments"),
+                locations: elements(node, "LocationTree").flat_map(locations).collect(),
+            })
+            .collect()
+    }
+
+    pub(super) fn filter(node: Node) -> ScannerFilter {
+        ScannerFilter {
+            id: text(node, "id"),
+            category: text(node, "category"),
+            kind: node.tag_name().name().to_owned(),
+            fields: elements(node, "AbstractField")
+                .map(|field| ScannerFilterField {
+                    code: text(field, "code"),
+                    display_name: text(field, "displayName"),
+                    field_type: field.attribute("type").unwrap_or_default().to_owned(),
+                })
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 /// Scanner subscription parameters.
 pub struct ScannerSubscription {
@@ -127,7 +300,7 @@ pub struct ScannerData {
 pub(super) fn scanner_subscription<'a>(
     client: &'a Client,
     subscription: &ScannerSubscription,
-    filter: &Vec<TagValue>,
+    filter: &[TagValue],
 ) -> Result<Subscription<'a, Vec<ScannerData>>, Error> {
     if !filter.is_empty() {
         client.check_server_version(
@@ -167,7 +340,7 @@ mod encoders {
         request_id: i32,
         server_version: i32,
         subscription: &ScannerSubscription,
-        filter: &Vec<TagValue>,
+        filter: &[TagValue],
     ) -> Result<RequestMessage, Error> {
         const VERSION: i32 = 4;
 
@@ -202,7 +375,7 @@ mod encoders {
         message.push_field(&subscription.stock_type_filter);
 
         if server_version >= server_versions::SCANNER_GENERIC_OPTS {
-            message.push_field(filter);
+            message.push_field(&filter.to_vec());
         }
         if server_version >= server_versions::LINKING {
             message.push_field(&""); // ignore subscription options
@@ -125,3 +125,189 @@ fn test_scanner_subscription() {
     // Verify cancel request was sent
     assert_eq!(request_messages[1].encode_simple(), "23|1|9000|");
 }
+
+#[test]
+fn test_encode_scanner_subscription_filter_options() {
+    let subscription = ScannerSubscription {
+        number_of_rows: 5,
+        instrument: Some("STK".to_string()),
+        location_code: Some("STK.US.MAJOR".to_string()),
+        scan_code: Some("TOP_PERC_GAIN".to_string()),
+        ..ScannerSubscription::default()
+    };
+    let filter = vec![
+        TagValue {
+            tag: "changePercAbove".to_string(),
+            value: "5".to_string(),
+        },
+        TagValue {
+            tag: "priceAbove".to_string(),
+            value: "10".to_string(),
+        },
+    ];
+
+    let message = encoders::encode_scanner_subscription(9000, server_versions::SCANNER_GENERIC_OPTS, &subscription, &filter).unwrap();
+    assert_eq!(
+        message.encode_simple(),
+        "22|9000|5|STK|STK.US.MAJOR|TOP_PERC_GAIN||||||||||||||0||||changePercAbove=5;priceAbove=10;||"
+    );
+
+    let message = encoders::encode_scanner_subscription(9000, server_versions::SCANNER_GENERIC_OPTS, &subscription, &[]).unwrap();
+    assert_eq!(
+        message.encode_simple(),
+        "22|9000|5|STK|STK.US.MAJOR|TOP_PERC_GAIN||||||||||||||0||||||",
+        "empty filter options"
+    );
+
+    let message = encoders::encode_scanner_subscription(9000, server_versions::SCANNER_GENERIC_OPTS - 1, &subscription, &filter).unwrap();
+    assert!(
+        !message.encode_simple().contains("changePercAbove"),
+        "filter options require SCANNER_GENERIC_OPTS: {}",
+        message.encode_simple()
+    );
+}
+
+#[test]
+fn test_scanner_subscription_filter_options_server_version() {
+    let message_bus = Arc::new(MessageBusStub::default());
+    let client = Client::stubbed(message_bus, server_versions::SCANNER_GENERIC_OPTS - 1);
+
+    let filter = vec![TagValue {
+        tag: "changePercAbove".to_string(),
+        value: "5".to_string(),
+    }];
+
+    let result = client.scanner_subscription(&ScannerSubscription::default(), &filter);
+    assert!(matches!(result, Err(Error::ServerVersion(_, _, _))), "expected server version error");
+}
+
+const SCANNER_PARAMETERS_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ScanParameterResponse>
+  <InstrumentList varName="instrumentList">
+    <Instrument>
+      <name>US Stocks</name>
+      <type>STK</type>
+      <filters>AFTERHRSCHANGEPERC,AVGOPTVOLUME,PRICE</filters>
+      <group>STK.GLOBAL</group>
+      <shortName>US</shortName>
+    </Instrument>
+    <Instrument>
+      <name>US Futures</name>
+      <type>FUT.US</type>
+      <filters>PRICE</filters>
+    </Instrument>
+  </InstrumentList>
+  <LocationTree varName="locationTree">
+    <Location>
+      <displayName>US Stocks</displayName>
+      <locationCode>STK.US</locationCode>
+      <instruments>STK</instruments>
+      <routeExchange>SMART</routeExchange>
+      <LocationTree>
+        <Location>
+          <displayName>Listed/NASDAQ</displayName>
+          <locationCode>STK.US.MAJOR</locationCode>
+          <instruments>STK</instruments>
+        </Location>
+      </LocationTree>
+    </Location>
+  </LocationTree>
+  <FilterList varName="filterList">
+    <RangeFilter>
+      <id>PRICE</id>
+      <category>Price</category>
+      <histogram>false</histogram>
+      <AbstractField type="DoubleField">
+        <code>priceAbove</code>
+        <displayName>Price Above</displayName>
+      </AbstractField>
+      <AbstractField type="DoubleField">
+        <code>priceBelow</code>
+        <displayName>Price Below</displayName>
+      </AbstractField>
+    </RangeFilter>
+    <SimpleFilter>
+      <id>EXCLUDE_CONVERTIBLE</id>
+      <category>Bond</category>
+      <AbstractField type="BooleanField">
+        <code>excludeConvertible</code>
+        <displayName>Exclude Convertible</displayName>
+      </AbstractField>
+    </SimpleFilter>
+  </FilterList>
+  <ScanTypeList varName="scanTypeList">
+    <ScanType>
+      <displayName>Top % Gainers</displayName>
+      <scanCode>TOP_PERC_GAIN</scanCode>
+      <instruments>STK,STOCK.NA,STOCK.EU</instruments>
+      <absoluteColumns>false</absoluteColumns>
+      <Columns varName="columns"><Column><colId>390</colId></Column></Columns>
+    </ScanType>
+    <ScanType>
+      <displayName>Most Active</displayName>
+      <scanCode>MOST_ACTIVE</scanCode>
+      <instruments>STK,FUT.US</instruments>
+    </ScanType>
+  </ScanTypeList>
+  <UnknownSection><Something>ignored</Something></UnknownSection>
+</ScanParameterResponse>"#;
+
+#[test]
+fn test_parse_scanner_parameters() {
+    let parameters = ScannerParameters::parse(SCANNER_PARAMETERS_XML).expect("failed to parse scanner parameters");
+
+    let scan_codes: Vec<&str> = parameters.scan_codes.iter().map(|scan| scan.code.as_str()).collect();
+    assert_eq!(scan_codes, vec!["TOP_PERC_GAIN", "MOST_ACTIVE"]);
+    assert_eq!(parameters.scan_codes[0].display_name, "Top % Gainers");
+    assert_eq!(parameters.scan_codes[0].instruments, vec!["STK", "STOCK.NA", "STOCK.EU"]);
+
+    assert_eq!(parameters.instruments.len(), 2);
+    assert_eq!(parameters.instruments[0].name, "US Stocks");
+    assert_eq!(parameters.instruments[0].instrument_type, "STK");
+    assert_eq!(parameters.instruments[0].filters, vec!["AFTERHRSCHANGEPERC", "AVGOPTVOLUME", "PRICE"]);
+    assert_eq!(parameters.instruments[1].instrument_type, "FUT.US");
+
+    assert_eq!(parameters.locations.len(), 1);
+    let us_stocks = &parameters.locations[0];
+    assert_eq!(us_stocks.location_code, "STK.US");
+    assert_eq!(us_stocks.instruments, vec!["STK"]);
+    assert_eq!(us_stocks.locations.len(), 1);
+    assert_eq!(us_stocks.locations[0].location_code, "STK.US.MAJOR");
+    assert_eq!(us_stocks.locations[0].display_name, "Listed/NASDAQ");
+    assert!(us_stocks.locations[0].locations.is_empty());
+
+    assert_eq!(parameters.filters.len(), 2);
+    let price = &parameters.filters[0];
+    assert_eq!(price.id, "PRICE");
+    assert_eq!(price.kind, "RangeFilter");
+    assert_eq!(price.category, "Price");
+    let codes: Vec<&str> = price.fields.iter().map(|field| field.code.as_str()).collect();
+    assert_eq!(codes, vec!["priceAbove", "priceBelow"]);
+    assert_eq!(price.fields[0].field_type, "DoubleField");
+    assert_eq!(parameters.filters[1].kind, "SimpleFilter");
+    assert_eq!(parameters.filters[1].fields[0].field_type, "BooleanField");
+}
+
+#[test]
+fn test_parse_scanner_parameters_invalid_xml() {
+    let result = ScannerParameters::parse("<ScanParameterResponse><InstrumentList>");
+    assert!(matches!(result, Err(Error::Simple(_))), "expected error for malformed XML: {result:?}");
+}
+
+#[test]
+fn test_scanner_parameters_parsed() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![format!("19|2|{SCANNER_PARAMETERS_XML}|")],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SCANNER_GENERIC_OPTS);
+
+    let parameters = client.scanner_parameters_parsed().expect("failed to request scanner parameters");
+
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(request_messages[0].encode_simple(), "24|1|");
+
+    assert_eq!(parameters.scan_codes.len(), 2);
+    assert_eq!(parameters.instruments.len(), 2);
+}
@@ -123,3 +123,4 @@ pub const INSTRUMENT_TIMEZONE: i32 = 174;
 pub const HMDS_MARKET_DATA_IN_SHARES: i32 = 175;
 pub const BOND_ISSUERID: i32 = 176;
 pub const FA_PROFILE_DESUPPORT: i32 = 177;
+pub const IMBALANCE_ONLY: i32 = 187;
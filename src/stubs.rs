@@ -1,9 +1,11 @@
+use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
 use crossbeam::channel;
 
+use crate::client::ConnectionState;
 use crate::messages::{OutgoingMessages, RequestMessage, ResponseMessage};
-use crate::transport::{InternalSubscription, MessageBus, SubscriptionBuilder};
+use crate::transport::{InternalSubscription, MessageBus, ReconnectHook, SubscriptionBuilder};
 use crate::Error;
 
 pub(crate) struct MessageBusStub {
@@ -55,6 +57,15 @@ impl MessageBus for MessageBusStub {
         Ok(())
     }
 
+    fn release_shared_subscription(&self, _message_type: OutgoingMessages) {}
+
+    // The stub never reconnects, so hooks are never run.
+    fn add_reconnect_hook(&self, _hook: ReconnectHook) {}
+
+    fn connection_state(&self) -> ConnectionState {
+        ConnectionState::Connected
+    }
+
     fn ensure_shutdown(&self) {}
 
     // fn process_messages(&mut self, _server_version: i32) -> Result<(), Error> {
@@ -68,12 +79,23 @@ fn mock_request(
     message_type: Option<OutgoingMessages>,
     message: &RequestMessage,
 ) -> InternalSubscription {
-    stub.request_messages.write().unwrap().push(message.clone());
+    respond(&stub.request_messages, &stub.response_messages, request_id, message_type, message)
+}
+
+// Records the request and creates a subscription that receives the given responses.
+fn respond(
+    request_messages: &RwLock<Vec<RequestMessage>>,
+    response_messages: &[String],
+    request_id: Option<i32>,
+    message_type: Option<OutgoingMessages>,
+    message: &RequestMessage,
+) -> InternalSubscription {
+    request_messages.write().unwrap().push(message.clone());
 
     let (sender, receiver) = channel::unbounded();
     let (s1, _r1) = channel::unbounded();
 
-    for message in &stub.response_messages {
+    for message in response_messages {
         let message = ResponseMessage::from(&message.replace('|', "\0"));
         sender.send(Ok(message)).unwrap();
     }
@@ -87,3 +109,61 @@ fn mock_request(
 
     subscription.build()
 }
+
+// Unlike MessageBusStub, delivers to each request only the responses routed to it.
+// Routes are keyed by the request in its simple encoding, e.g. "94|9001|DU1234567||76792991|". Other requests receive nothing.
+#[derive(Default)]
+pub(crate) struct RoutedMessageBusStub {
+    pub request_messages: RwLock<Vec<RequestMessage>>,
+    pub routes: HashMap<String, Vec<String>>,
+}
+
+impl RoutedMessageBusStub {
+    fn route(&self, request_id: Option<i32>, message_type: Option<OutgoingMessages>, message: &RequestMessage) -> InternalSubscription {
+        let responses = self.routes.get(&message.encode_simple()).map(Vec::as_slice).unwrap_or_default();
+        respond(&self.request_messages, responses, request_id, message_type, message)
+    }
+}
+
+impl MessageBus for RoutedMessageBusStub {
+    fn request_messages(&self) -> Vec<RequestMessage> {
+        self.request_messages.read().unwrap().clone()
+    }
+
+    fn send_request(&self, request_id: i32, message: &RequestMessage) -> Result<InternalSubscription, Error> {
+        Ok(self.route(Some(request_id), None, message))
+    }
+
+    fn cancel_subscription(&self, request_id: i32, packet: &RequestMessage) -> Result<(), Error> {
+        self.route(Some(request_id), None, packet);
+        Ok(())
+    }
+
+    fn send_order_request(&self, request_id: i32, message: &RequestMessage) -> Result<InternalSubscription, Error> {
+        Ok(self.route(Some(request_id), None, message))
+    }
+
+    fn cancel_order_subscription(&self, request_id: i32, packet: &RequestMessage) -> Result<(), Error> {
+        self.route(Some(request_id), None, packet);
+        Ok(())
+    }
+
+    fn send_shared_request(&self, message_type: OutgoingMessages, message: &RequestMessage) -> Result<InternalSubscription, Error> {
+        Ok(self.route(None, Some(message_type), message))
+    }
+
+    fn cancel_shared_subscription(&self, message_type: OutgoingMessages, packet: &RequestMessage) -> Result<(), Error> {
+        self.route(None, Some(message_type), packet);
+        Ok(())
+    }
+
+    fn release_shared_subscription(&self, _message_type: OutgoingMessages) {}
+
+    fn add_reconnect_hook(&self, _hook: ReconnectHook) {}
+
+    fn connection_state(&self) -> ConnectionState {
+        ConnectionState::Connected
+    }
+
+    fn ensure_shutdown(&self) {}
+}
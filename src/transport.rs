@@ -2,6 +2,7 @@
 //! It provides functionality for routing requests from the Client to TWS,
 //! and responses from TWS back to the Client.
 
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::io::{prelude::*, Cursor, ErrorKind};
 use std::net::TcpStream;
@@ -11,21 +12,23 @@ use std::thread::{self, JoinHandle};
 use std::time::Duration;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use crossbeam::channel::{self, Receiver, Sender};
+use crossbeam::channel::{self, Receiver, SendError, Sender, TryRecvError, TrySendError};
 use log::{debug, error, info, warn};
 use time::macros::format_description;
 use time::OffsetDateTime;
 use time_tz::{timezones, OffsetResult, PrimitiveDateTimeExt, Tz};
 
+use crate::client::{ConnectionConfig, ConnectionState, OverflowPolicy, SubscriptionBuffer};
 use crate::messages::{shared_channel_configuration, IncomingMessages, OutgoingMessages, RequestMessage, ResponseMessage};
 use crate::{server_versions, Error};
 use recorder::MessageRecorder;
+pub(crate) use replay::ReplayMessageBus;
 
 mod recorder;
+mod replay;
 
 const MIN_SERVER_VERSION: i32 = 100;
 const MAX_SERVER_VERSION: i32 = server_versions::HISTORICAL_SCHEDULE;
-const MAX_RETRIES: i32 = 20;
 const TWS_READ_TIMEOUT: Duration = Duration::from_secs(1);
 
 pub(crate) trait MessageBus: Send + Sync {
@@ -46,6 +49,14 @@ pub(crate) trait MessageBus: Send + Sync {
 
     fn cancel_order_subscription(&self, request_id: i32, packet: &RequestMessage) -> Result<(), Error>;
 
+    // Called when a shared subscription is dropped, so it is no longer re-requested after a reconnect.
+    fn release_shared_subscription(&self, message_type: OutgoingMessages);
+
+    // Registers a hook to run after a reconnect, once shared subscriptions have been re-requested.
+    fn add_reconnect_hook(&self, hook: ReconnectHook);
+
+    fn connection_state(&self) -> ConnectionState;
+
     fn ensure_shutdown(&self);
 
     // Testing interface. Tracks requests sent messages when Bus is stubbed.
@@ -57,6 +68,99 @@ pub(crate) trait MessageBus: Send + Sync {
 
 pub(crate) type Response = Result<ResponseMessage, Error>;
 
+pub(crate) type ReconnectHook = Arc<dyn Fn() + Send + Sync>;
+
+// Shared requests that start a stream TWS forgets when the connection drops.
+const RESYNC_REQUESTS: &[OutgoingMessages] = &[
+    OutgoingMessages::RequestOpenOrders,
+    OutgoingMessages::RequestAllOpenOrders,
+    OutgoingMessages::RequestAutoOpenOrders,
+    OutgoingMessages::RequestPositions,
+];
+
+// Tracks the connection state. Disconnected is final, a shut down bus does not come back.
+#[derive(Debug)]
+struct ConnectionStatus(Mutex<ConnectionState>);
+
+impl Default for ConnectionStatus {
+    fn default() -> Self {
+        Self(Mutex::new(ConnectionState::Connected))
+    }
+}
+
+impl ConnectionStatus {
+    fn get(&self) -> ConnectionState {
+        *self.0.lock().unwrap()
+    }
+
+    fn set(&self, state: ConnectionState) {
+        let mut current = self.0.lock().unwrap();
+        if *current != ConnectionState::Disconnected {
+            debug!("connection state {:?} -> {:?}", *current, state);
+            *current = state;
+        }
+    }
+}
+
+// Tracks active shared subscriptions so they can be re-requested after a reconnect.
+#[derive(Default)]
+struct Resync {
+    // Number of active subscriptions and the latest request for each message type.
+    requests: Mutex<HashMap<OutgoingMessages, (usize, RequestMessage)>>,
+    hooks: Mutex<Vec<ReconnectHook>>,
+}
+
+impl std::fmt::Debug for Resync {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Resync")
+            .field("requests", &self.requests)
+            .field("hooks", &self.hooks.lock().map(|hooks| hooks.len()).unwrap_or_default())
+            .finish()
+    }
+}
+
+impl Resync {
+    fn track(&self, message_type: OutgoingMessages, packet: &RequestMessage) {
+        if !RESYNC_REQUESTS.contains(&message_type) {
+            return;
+        }
+
+        let mut requests = self.requests.lock().unwrap();
+        let entry = requests.entry(message_type).or_insert_with(|| (0, packet.clone()));
+        entry.0 += 1;
+        entry.1 = packet.clone();
+    }
+
+    fn release(&self, message_type: OutgoingMessages) {
+        let mut requests = self.requests.lock().unwrap();
+        if let Some(entry) = requests.get_mut(&message_type) {
+            entry.0 -= 1;
+            if entry.0 == 0 {
+                requests.remove(&message_type);
+            }
+        }
+    }
+
+    fn add_hook(&self, hook: ReconnectHook) {
+        self.hooks.lock().unwrap().push(hook);
+    }
+
+    // Re-sends tracked requests with `write`, then runs the registered hooks.
+    fn replay(&self, write: impl Fn(&RequestMessage) -> Result<(), Error>) {
+        let requests: Vec<RequestMessage> = self.requests.lock().unwrap().values().map(|(_, packet)| packet.clone()).collect();
+        for packet in &requests {
+            if let Err(e) = write(packet) {
+                warn!("error re-sending request after reconnect: {e}");
+            }
+        }
+
+        let hooks: Vec<ReconnectHook> = self.hooks.lock().unwrap().clone();
+        for hook in hooks {
+            hook();
+        }
+    }
+}
+
 // For requests without an identifier, shared channels are created
 // to route request/response pairs based on message type.
 #[derive(Debug)]
@@ -147,13 +251,15 @@ pub enum Signal {
 pub struct TcpMessageBus {
     connection: Connection,
     handles: Mutex<Vec<JoinHandle<()>>>,
-    requests: SenderHash<i32, Response>,
-    orders: SenderHash<i32, Response>,
-    executions: SenderHash<String, Response>,
+    requests: SenderHash<i32>,
+    orders: SenderHash<i32>,
+    executions: SenderHash<String>,
     shared_channels: SharedChannels,
     signals_send: Sender<Signal>,
     signals_recv: Receiver<Signal>,
     shutdown_requested: AtomicBool,
+    resync: Resync,
+    status: ConnectionStatus,
 }
 
 impl TcpMessageBus {
@@ -170,6 +276,8 @@ impl TcpMessageBus {
             signals_send,
             signals_recv,
             shutdown_requested: AtomicBool::new(false),
+            resync: Resync::default(),
+            status: ConnectionStatus::default(),
         })
     }
 
@@ -189,6 +297,7 @@ impl TcpMessageBus {
         self.executions.clear();
 
         self.shutdown_requested.store(true, Ordering::Relaxed);
+        self.status.set(ConnectionState::Disconnected);
     }
 
     fn reset(&self) {
@@ -241,6 +350,7 @@ impl TcpMessageBus {
                         error!("error reading next message (will attempt reconnect): {:?}", e);
 
                         // Attempt to reconnect to TWS.
+                        message_bus.status.set(ConnectionState::Reconnecting);
                         if let Err(e) = message_bus.connection.reconnect() {
                             error!("failed to reconnect to TWS/Gateway: {:?}", e);
                             message_bus.request_shutdown();
@@ -248,7 +358,9 @@ impl TcpMessageBus {
                         }
 
                         info!("successfully reconnected to TWS/Gateway");
+                        message_bus.status.set(ConnectionState::Connected);
                         message_bus.reset();
+                        message_bus.start_resync_thread();
                         continue;
                     }
                     Err(err) => {
@@ -308,7 +420,7 @@ impl TcpMessageBus {
                         }
                     }
                     (_, Some(request_id)) if self.requests.contains(&request_id) => {
-                        if let Some(sender) = self.requests.copy_sender(request_id) {
+                        if let Some(sender) = self.requests.copy_sender(&request_id) {
                             if let Some(execution_id) = message.execution_id() {
                                 self.executions.insert(execution_id, sender);
                             }
@@ -366,6 +478,16 @@ impl TcpMessageBus {
         }
     }
 
+    // Re-requests shared subscriptions and runs reconnect hooks off the dispatcher thread,
+    // so hooks can make requests that wait on responses.
+    fn start_resync_thread(self: &Arc<Self>) {
+        let message_bus = Arc::clone(self);
+
+        thread::spawn(move || {
+            message_bus.resync.replay(|packet| message_bus.connection.write_message(packet));
+        });
+    }
+
     // The cleanup thread receives signals as subscribers are dropped and
     // releases the sender channels
     fn start_cleanup_thread(self: &Arc<Self>) -> JoinHandle<()> {
@@ -424,8 +546,8 @@ const UNSPECIFIED_REQUEST_ID: i32 = -1;
 
 impl MessageBus for TcpMessageBus {
     fn send_request(&self, request_id: i32, packet: &RequestMessage) -> Result<InternalSubscription, Error> {
-        let (sender, receiver) = channel::unbounded();
-        let sender_copy = sender.clone();
+        let (sender, receiver) = response_channel(subscription_buffer(self.connection.config.subscription_buffer));
+        let sender_copy = sender.sender.clone();
 
         self.requests.insert(request_id, sender);
 
@@ -444,7 +566,7 @@ impl MessageBus for TcpMessageBus {
     fn cancel_subscription(&self, request_id: i32, message: &RequestMessage) -> Result<(), Error> {
         self.connection.write_message(message)?;
 
-        if let Err(e) = self.requests.send(&request_id, Err(Error::Cancelled)) {
+        if let Err(e) = self.requests.send_notice(&request_id, Err(Error::Cancelled)) {
             info!("error sending cancel notification: {e}");
         }
 
@@ -454,8 +576,8 @@ impl MessageBus for TcpMessageBus {
     }
 
     fn send_order_request(&self, order_id: i32, message: &RequestMessage) -> Result<InternalSubscription, Error> {
-        let (sender, receiver) = channel::unbounded();
-        let sender_copy = sender.clone();
+        let (sender, receiver) = response_channel(subscription_buffer(self.connection.config.subscription_buffer));
+        let sender_copy = sender.sender.clone();
 
         self.orders.insert(order_id, sender);
 
@@ -474,7 +596,7 @@ impl MessageBus for TcpMessageBus {
     fn cancel_order_subscription(&self, request_id: i32, message: &RequestMessage) -> Result<(), Error> {
         self.connection.write_message(message)?;
 
-        if let Err(e) = self.orders.send(&request_id, Err(Error::Cancelled)) {
+        if let Err(e) = self.orders.send_notice(&request_id, Err(Error::Cancelled)) {
             info!("error sending cancel notification: {e}");
         }
 
@@ -485,6 +607,7 @@ impl MessageBus for TcpMessageBus {
 
     fn send_shared_request(&self, message_type: OutgoingMessages, message: &RequestMessage) -> Result<InternalSubscription, Error> {
         self.connection.write_message(message)?;
+        self.resync.track(message_type, message);
 
         let shared_receiver = self.shared_channels.get_receiver(message_type);
 
@@ -502,6 +625,18 @@ impl MessageBus for TcpMessageBus {
         Ok(())
     }
 
+    fn release_shared_subscription(&self, message_type: OutgoingMessages) {
+        self.resync.release(message_type);
+    }
+
+    fn add_reconnect_hook(&self, hook: ReconnectHook) {
+        self.resync.add_hook(hook);
+    }
+
+    fn connection_state(&self) -> ConnectionState {
+        self.status.get()
+    }
+
     fn ensure_shutdown(&self) {
         self.request_shutdown();
         self.join();
@@ -546,22 +681,113 @@ fn error_event(server_version: i32, mut packet: ResponseMessage) -> Result<(), E
     }
 }
 
+thread_local! {
+    // Overrides ConnectionConfig::subscription_buffer for subscriptions created on this thread. See Client::with_subscription_buffer.
+    static SUBSCRIPTION_BUFFER: Cell<Option<Option<SubscriptionBuffer>>> = const { Cell::new(None) };
+}
+
+// Runs f with buffer used for the subscriptions it creates on this thread.
+pub(crate) fn with_subscription_buffer<R>(buffer: Option<SubscriptionBuffer>, f: impl FnOnce() -> R) -> R {
+    // Restores the previous override, even if f panics.
+    struct Restore(Option<Option<SubscriptionBuffer>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SUBSCRIPTION_BUFFER.with(|cell| cell.set(self.0));
+        }
+    }
+
+    let _restore = Restore(SUBSCRIPTION_BUFFER.with(|cell| cell.replace(Some(buffer))));
+    f()
+}
+
+// Buffer for a subscription created on this thread: the override if one is set, otherwise the connection default.
+fn subscription_buffer(default: Option<SubscriptionBuffer>) -> Option<SubscriptionBuffer> {
+    SUBSCRIPTION_BUFFER.with(|cell| cell.get()).unwrap_or(default)
+}
+
+// Sending half of a subscription's response channel.
+// When a bounded channel is full, sending either blocks or discards the oldest response, per the overflow policy.
+#[derive(Clone, Debug)]
+struct ResponseSender {
+    sender: Sender<Response>,
+    oldest: Option<Receiver<Response>>, // set for bounded channels, to discard the oldest response on overflow
+    overflow: OverflowPolicy,
+}
+
+impl ResponseSender {
+    fn send(&self, response: Response) -> Result<(), SendError<Response>> {
+        match self.overflow {
+            OverflowPolicy::Block => self.sender.send(response),
+            OverflowPolicy::DropOldest => force_send(&self.sender, self.oldest.as_ref(), response),
+        }
+    }
+
+    // Sends a notice ending the subscription, e.g. Cancelled or Shutdown. Never blocks, whatever the overflow policy,
+    // so the consumer receives it even if it stopped reading.
+    fn send_notice(&self, notice: Response) -> Result<(), SendError<Response>> {
+        force_send(&self.sender, self.oldest.as_ref(), notice)
+    }
+}
+
+// Sends without blocking on a full bounded channel, discarding the oldest responses until there is room.
+fn force_send(sender: &Sender<Response>, oldest: Option<&Receiver<Response>>, response: Response) -> Result<(), SendError<Response>> {
+    let Some(oldest) = oldest else {
+        return sender.send(response);
+    };
+
+    let mut response = response;
+    loop {
+        match sender.try_send(response) {
+            Ok(()) => return Ok(()),
+            Err(TrySendError::Full(rejected)) => {
+                if let Ok(dropped) = oldest.try_recv() {
+                    debug!("subscription buffer full, dropped {dropped:?}");
+                }
+                response = rejected;
+            }
+            Err(TrySendError::Disconnected(rejected)) => return Err(SendError(rejected)),
+        }
+    }
+}
+
+// Creates the channel responses for a subscription are routed through.
+fn response_channel(buffer: Option<SubscriptionBuffer>) -> (ResponseSender, Receiver<Response>) {
+    let Some(buffer) = buffer else {
+        let (sender, receiver) = channel::unbounded();
+        let sender = ResponseSender {
+            sender,
+            oldest: None,
+            overflow: OverflowPolicy::Block,
+        };
+        return (sender, receiver);
+    };
+
+    let (sender, receiver) = channel::bounded(buffer.capacity.max(1));
+    let sender = ResponseSender {
+        sender,
+        oldest: Some(receiver.clone()),
+        overflow: buffer.overflow,
+    };
+
+    (sender, receiver)
+}
+
 #[derive(Debug)]
-struct SenderHash<K, V> {
-    senders: RwLock<HashMap<K, Sender<V>>>,
+struct SenderHash<K> {
+    senders: RwLock<HashMap<K, ResponseSender>>,
 }
 
-impl<K: std::hash::Hash + Eq + std::fmt::Debug, V: std::fmt::Debug + Clone> SenderHash<K, V> {
+impl<K: std::hash::Hash + Eq + std::fmt::Debug> SenderHash<K> {
     pub fn new() -> Self {
         Self {
             senders: RwLock::new(HashMap::new()),
         }
     }
 
-    pub fn send(&self, id: &K, message: V) -> Result<(), Error> {
-        let senders = self.senders.read().unwrap();
-        debug!("senders: {senders:?}");
-        if let Some(sender) = senders.get(id) {
+    // The sender is cloned out of the map before sending, as a blocked send must not hold the lock.
+    pub fn send(&self, id: &K, message: Response) -> Result<(), Error> {
+        if let Some(sender) = self.copy_sender(id) {
             if let Err(err) = sender.send(message) {
                 warn!("error sending: {id:?}, {err}")
             }
@@ -571,17 +797,29 @@ impl<K: std::hash::Hash + Eq + std::fmt::Debug, V: std::fmt::Debug + Clone> Send
         Ok(())
     }
 
-    pub fn copy_sender(&self, id: K) -> Option<Sender<V>> {
+    // Sends a notice ending the subscription. See ResponseSender::send_notice.
+    pub fn send_notice(&self, id: &K, notice: Response) -> Result<(), Error> {
+        if let Some(sender) = self.copy_sender(id) {
+            if let Err(err) = sender.send_notice(notice) {
+                warn!("error sending: {id:?}, {err}")
+            }
+        } else {
+            warn!("no recipient found for: {id:?}, {notice:?}")
+        }
+        Ok(())
+    }
+
+    pub fn copy_sender(&self, id: &K) -> Option<ResponseSender> {
         let senders = self.senders.read().unwrap();
-        senders.get(&id).cloned()
+        senders.get(id).cloned()
     }
 
-    pub fn insert(&self, id: K, message: Sender<V>) -> Option<Sender<V>> {
+    pub fn insert(&self, id: K, message: ResponseSender) -> Option<ResponseSender> {
         let mut senders = self.senders.write().unwrap();
         senders.insert(id, message)
     }
 
-    pub fn remove(&self, id: &K) -> Option<Sender<V>> {
+    pub fn remove(&self, id: &K) -> Option<ResponseSender> {
         let mut senders = self.senders.write().unwrap();
         senders.remove(id)
     }
@@ -601,10 +839,10 @@ impl<K: std::hash::Hash + Eq + std::fmt::Debug, V: std::fmt::Debug + Clone> Send
         senders.clear();
     }
 
-    pub fn notify_all(&self, message: &V) {
-        let senders = self.senders.read().unwrap();
-        for sender in senders.values() {
-            if let Err(e) = sender.send(message.clone()) {
+    pub fn notify_all(&self, message: &Response) {
+        let senders: Vec<ResponseSender> = self.senders.read().unwrap().values().cloned().collect();
+        for sender in senders {
+            if let Err(e) = sender.send_notice(message.clone()) {
                 warn!("error sending notification: {e}");
             }
         }
@@ -621,11 +859,16 @@ pub(crate) struct InternalSubscription {
     pub(crate) request_id: Option<i32>,                // initiating request id
     pub(crate) order_id: Option<i32>,                  // initiating order id
     pub(crate) message_type: Option<OutgoingMessages>, // initiating message type
+    pending: Mutex<Option<Response>>,                  // response returned to the subscription with unread
 }
 
 impl InternalSubscription {
     // Blocks until next message become available.
     pub(crate) fn next(&self) -> Option<Response> {
+        if let Some(response) = self.take_pending() {
+            return Some(response);
+        }
+
         if let Some(receiver) = &self.receiver {
             Self::receive(receiver)
         } else if let Some(receiver) = &self.shared_receiver {
@@ -637,6 +880,10 @@ impl InternalSubscription {
 
     // Returns message if available or immediately returns None.
     pub(crate) fn try_next(&self) -> Option<Response> {
+        if let Some(response) = self.take_pending() {
+            return Some(response);
+        }
+
         if let Some(receiver) = &self.receiver {
             Self::try_receive(receiver)
         } else if let Some(receiver) = &self.shared_receiver {
@@ -648,6 +895,10 @@ impl InternalSubscription {
 
     // Waits for next message until specified timeout.
     pub(crate) fn next_timeout(&self, timeout: Duration) -> Option<Response> {
+        if let Some(response) = self.take_pending() {
+            return Some(response);
+        }
+
         if let Some(receiver) = &self.receiver {
             Self::timeout_receive(receiver, timeout)
         } else if let Some(receiver) = &self.shared_receiver {
@@ -657,9 +908,38 @@ impl InternalSubscription {
         }
     }
 
+    // Returns a response so it is the next one yielded by next, try_next or next_timeout.
+    pub(crate) fn unread(&self, response: Response) {
+        let mut pending = self.pending.lock().unwrap();
+        *pending = Some(response);
+    }
+
+    fn take_pending(&self) -> Option<Response> {
+        self.pending.lock().unwrap().take()
+    }
+
+    // Like try_next, but tells an empty channel apart from a disconnected one. Used with receiver when selecting.
+    pub(crate) fn try_receive_response(&self) -> Result<Response, TryRecvError> {
+        if let Some(response) = self.take_pending() {
+            return Ok(response);
+        }
+
+        match self.receiver() {
+            Some(receiver) => receiver.try_recv(),
+            None => Err(TryRecvError::Disconnected),
+        }
+    }
+
+    // Channel responses are received on, for callers that need to select across subscriptions.
+    // A response returned with unread is not in the channel; read with try_receive_response before selecting.
+    pub(crate) fn receiver(&self) -> Option<&Receiver<Response>> {
+        self.receiver.as_ref().or(self.shared_receiver.as_deref())
+    }
+
     pub(crate) fn cancel(&self) {
         if let Some(sender) = &self.sender {
-            if let Err(e) = sender.send(Err(Error::Cancelled)) {
+            // Never block the consumer on its own full buffer; make room by discarding the oldest response instead.
+            if let Err(e) = force_send(sender, self.receiver.as_ref(), Err(Error::Cancelled)) {
                 warn!("error sending cancel notification: {e}")
             }
         }
@@ -761,6 +1041,7 @@ impl SubscriptionBuilder {
                 request_id: self.request_id,
                 order_id: self.order_id,
                 message_type: self.message_type,
+                pending: Mutex::new(None),
             }
         } else if let Some(receiver) = self.shared_receiver {
             InternalSubscription {
@@ -771,6 +1052,7 @@ impl SubscriptionBuilder {
                 request_id: self.request_id,
                 order_id: self.order_id,
                 message_type: self.message_type,
+                pending: Mutex::new(None),
             }
         } else {
             panic!("bad configuration");
@@ -795,12 +1077,12 @@ pub(crate) struct Connection {
     reader: Mutex<TcpStream>,
     writer: Mutex<TcpStream>,
     connection_metadata: Mutex<ConnectionMetadata>,
-    max_retries: i32,
+    config: ConnectionConfig,
     recorder: MessageRecorder,
 }
 
 impl Connection {
-    pub fn connect(client_id: i32, connection_url: &str) -> Result<Self, Error> {
+    pub fn connect(client_id: i32, connection_url: &str, config: ConnectionConfig) -> Result<Self, Error> {
         let reader = TcpStream::connect(connection_url)?;
         let writer = reader.try_clone()?;
 
@@ -812,7 +1094,7 @@ impl Connection {
             reader: Mutex::new(reader),
             writer: Mutex::new(writer),
             connection_metadata: Mutex::new(ConnectionMetadata::default()),
-            max_retries: MAX_RETRIES,
+            config,
             recorder: MessageRecorder::new(),
         };
 
@@ -827,10 +1109,10 @@ impl Connection {
     }
 
     pub fn reconnect(&self) -> Result<(), Error> {
-        let mut backoff = FibonacciBackoff::new(30);
+        let mut backoff = ExponentialBackoff::new(&self.config);
 
-        for i in 0..self.max_retries {
-            let next_delay = backoff.next_delay();
+        for i in 0..self.config.max_retries {
+            let next_delay = backoff.next_delay(jitter_sample());
             info!("next reconnection attempt in {next_delay:#?}");
 
             thread::sleep(next_delay);
@@ -853,7 +1135,7 @@ impl Connection {
                     return Ok(());
                 }
                 Err(e) => {
-                    error!("reconnection attempt {i} of {} failed: {e}", self.max_retries);
+                    error!("reconnection attempt {i} of {} failed: {e}", self.config.max_retries);
                 }
             }
         }
@@ -1009,34 +1291,45 @@ impl Connection {
     }
 }
 
-struct FibonacciBackoff {
-    previous: u64,
-    current: u64,
-    max: u64,
+struct ExponentialBackoff {
+    attempt: u32,
+    initial: Duration,
+    max: Duration,
+    jitter: f64,
 }
 
-impl FibonacciBackoff {
-    fn new(max: u64) -> Self {
-        FibonacciBackoff {
-            previous: 0,
-            current: 1,
-            max,
+impl ExponentialBackoff {
+    fn new(config: &ConnectionConfig) -> Self {
+        ExponentialBackoff {
+            attempt: 0,
+            initial: config.initial_backoff,
+            max: config.max_backoff,
+            jitter: config.jitter.clamp(0.0, 1.0),
         }
     }
 
-    fn next_delay(&mut self) -> Duration {
-        let next = self.previous + self.current;
-        self.previous = self.current;
-        self.current = next;
+    // Returns the delay before the next attempt. `sample` in [0, 1) selects where within the jitter range the delay falls;
+    // 0.5 yields the unjittered delay.
+    fn next_delay(&mut self, sample: f64) -> Duration {
+        let factor = 2u32.saturating_pow(self.attempt);
+        self.attempt = self.attempt.saturating_add(1);
 
-        if next > self.max {
-            Duration::from_secs(self.max)
-        } else {
-            Duration::from_secs(next)
-        }
+        let delay = self.initial.saturating_mul(factor).min(self.max);
+        let spread = 1.0 + self.jitter * (2.0 * sample - 1.0);
+
+        delay.mul_f64(spread.max(0.0)).min(self.max)
     }
 }
 
+// Cheap source of randomness for backoff jitter.
+fn jitter_sample() -> f64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.subsec_nanos())
+        .unwrap_or_default();
+    f64::from(nanos) / 1_000_000_000.0
+}
+
 // Parses following format: 20230405 22:20:39 PST
 fn parse_connection_time(connection_time: &str) -> (Option<OffsetDateTime>, Option<&'static Tz>) {
     let parts: Vec<&str> = connection_time.split(' ').collect();
@@ -0,0 +1,257 @@
+//! The ReplayMessageBus drives a Client from a session captured by the
+//! [MessageRecorder](super::recorder::MessageRecorder).
+//! Each recorded request is paired with the responses recorded after it, up
+//! to the next request. When the Client sends a request matching a recorded
+//! one, the paired responses are delivered on the new subscription.
+
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+
+use crossbeam::channel;
+use log::debug;
+
+use super::{parse_connection_time, ConnectionMetadata, InternalSubscription, MessageBus, ReconnectHook, Signal, SubscriptionBuilder};
+use crate::client::ConnectionState;
+use crate::messages::{IncomingMessages, OutgoingMessages, RequestMessage, ResponseMessage};
+use crate::Error;
+
+#[derive(Debug)]
+struct Interaction {
+    request: String,
+    responses: Vec<String>,
+    replayed: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct ReplayMessageBus {
+    interactions: Mutex<Vec<Interaction>>,
+    request_messages: RwLock<Vec<RequestMessage>>,
+    // Subscriptions signal when dropped. Nothing needs cleaning up in a replay, but the receiver must
+    // stay alive for the signals to be sent.
+    signals_send: channel::Sender<Signal>,
+    signals_recv: channel::Receiver<Signal>,
+}
+
+enum Record {
+    Request(String),
+    Response(String),
+}
+
+impl ReplayMessageBus {
+    // Loads a recording directory and returns the bus with the connection metadata captured during the handshake.
+    pub(crate) fn load(recording_dir: &Path) -> Result<(ReplayMessageBus, ConnectionMetadata), Error> {
+        let records = read_records(recording_dir)?;
+
+        let mut metadata = ConnectionMetadata::default();
+        let mut interactions: Vec<Interaction> = Vec::new();
+        let mut handshake = true;
+
+        for record in records {
+            match record {
+                Record::Response(response) if handshake => read_handshake_response(&mut metadata, &response)?,
+                Record::Request(request) if handshake && request.starts_with(&format!("{}|", OutgoingMessages::StartApi as i32)) => {
+                    // StartApi|version|client_id|...
+                    if let Some(client_id) = request.split('|').nth(2) {
+                        metadata.client_id = client_id.parse().unwrap_or_default();
+                    }
+                }
+                Record::Request(request) => {
+                    handshake = false;
+                    interactions.push(Interaction {
+                        request,
+                        responses: Vec::new(),
+                        replayed: false,
+                    });
+                }
+                Record::Response(response) => {
+                    if let Some(interaction) = interactions.last_mut() {
+                        interaction.responses.push(response);
+                    }
+                }
+            }
+        }
+
+        if metadata.server_version == 0 {
+            return Err(Error::Simple(format!(
+                "recording {} does not contain the connection handshake",
+                recording_dir.display()
+            )));
+        }
+
+        let (signals_send, signals_recv) = channel::unbounded();
+        let bus = ReplayMessageBus {
+            interactions: Mutex::new(interactions),
+            request_messages: RwLock::new(Vec::new()),
+            signals_send,
+            signals_recv,
+        };
+
+        Ok((bus, metadata))
+    }
+
+    // Finds the first recorded request matching message that has not been replayed yet and returns its responses.
+    fn replay(&self, message: &RequestMessage) -> Option<Vec<String>> {
+        self.request_messages.write().unwrap().push(message.clone());
+        self.signals_recv.try_iter().for_each(drop);
+
+        let encoded = message.encode().replace('\0', "|");
+        let mut interactions = self.interactions.lock().unwrap();
+
+        let interaction = interactions
+            .iter_mut()
+            .find(|interaction| !interaction.replayed && interaction.request == encoded)?;
+        interaction.replayed = true;
+
+        Some(interaction.responses.clone())
+    }
+
+    fn replay_request(&self, message: &RequestMessage) -> Result<channel::Receiver<Result<ResponseMessage, Error>>, Error> {
+        let responses = self
+            .replay(message)
+            .ok_or_else(|| Error::Simple(format!("request not found in recording: {}", message.encode().replace('\0', "|"))))?;
+
+        let (sender, receiver) = channel::unbounded();
+        for response in responses {
+            sender.send(Ok(ResponseMessage::from(&response.replace('|', "\0")))).unwrap();
+        }
+
+        Ok(receiver)
+    }
+
+    fn replay_cancel(&self, message: &RequestMessage) {
+        if self.replay(message).is_none() {
+            debug!("cancel not found in recording: {}", message.encode().replace('\0', "|"));
+        }
+    }
+}
+
+// The handshake ack is `server_version|connection_time|`, followed by the account information TWS sends on connect.
+fn read_handshake_response(metadata: &mut ConnectionMetadata, response: &str) -> Result<(), Error> {
+    let mut message = ResponseMessage::from(&response.replace('|', "\0"));
+
+    if metadata.server_version == 0 {
+        metadata.server_version = message.next_int()?;
+        (metadata.connection_time, metadata.time_zone) = parse_connection_time(&message.next_string()?);
+        return Ok(());
+    }
+
+    match message.message_type() {
+        IncomingMessages::NextValidId => {
+            message.skip(); // message type
+            message.skip(); // message version
+            metadata.next_order_id = message.next_int()?;
+        }
+        IncomingMessages::ManagedAccounts => {
+            message.skip(); // message type
+            message.skip(); // message version
+            metadata.managed_accounts = message.next_string()?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+// Reads `NNNN-request.msg` and `NNNN-response.msg` files in sequence order.
+fn read_records(recording_dir: &Path) -> Result<Vec<Record>, Error> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(recording_dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some((sequence, kind)) = name.split_once('-') else {
+            continue;
+        };
+        let Ok(sequence) = sequence.parse::<usize>() else {
+            continue;
+        };
+
+        match kind {
+            "request.msg" | "response.msg" => files.push((sequence, kind == "request.msg", path)),
+            _ => continue,
+        }
+    }
+
+    files.sort_by_key(|(sequence, _, _)| *sequence);
+
+    files
+        .into_iter()
+        .map(|(_, is_request, path)| {
+            let content = fs::read_to_string(path)?;
+            Ok(if is_request {
+                Record::Request(content)
+            } else {
+                Record::Response(content)
+            })
+        })
+        .collect()
+}
+
+impl MessageBus for ReplayMessageBus {
+    fn send_request(&self, request_id: i32, message: &RequestMessage) -> Result<InternalSubscription, Error> {
+        let receiver = self.replay_request(message)?;
+
+        Ok(SubscriptionBuilder::new()
+            .receiver(receiver)
+            .signaler(self.signals_send.clone())
+            .request_id(request_id)
+            .build())
+    }
+
+    fn cancel_subscription(&self, _request_id: i32, message: &RequestMessage) -> Result<(), Error> {
+        self.replay_cancel(message);
+        Ok(())
+    }
+
+    fn send_shared_request(&self, message_type: OutgoingMessages, message: &RequestMessage) -> Result<InternalSubscription, Error> {
+        let receiver = self.replay_request(message)?;
+
+        Ok(SubscriptionBuilder::new()
+            .shared_receiver(Arc::new(receiver))
+            .message_type(message_type)
+            .build())
+    }
+
+    fn cancel_shared_subscription(&self, _message_type: OutgoingMessages, message: &RequestMessage) -> Result<(), Error> {
+        self.replay_cancel(message);
+        Ok(())
+    }
+
+    fn send_order_request(&self, order_id: i32, message: &RequestMessage) -> Result<InternalSubscription, Error> {
+        let receiver = self.replay_request(message)?;
+
+        Ok(SubscriptionBuilder::new()
+            .receiver(receiver)
+            .signaler(self.signals_send.clone())
+            .order_id(order_id)
+            .build())
+    }
+
+    fn cancel_order_subscription(&self, _order_id: i32, message: &RequestMessage) -> Result<(), Error> {
+        self.replay_cancel(message);
+        Ok(())
+    }
+
+    fn release_shared_subscription(&self, _message_type: OutgoingMessages) {}
+
+    // A replayed session never reconnects, so hooks are never run.
+    fn add_reconnect_hook(&self, _hook: ReconnectHook) {}
+
+    // A replayed session is never disconnected.
+    fn connection_state(&self) -> ConnectionState {
+        ConnectionState::Connected
+    }
+
+    fn ensure_shutdown(&self) {}
+
+    #[cfg(test)]
+    fn request_messages(&self) -> Vec<RequestMessage> {
+        self.request_messages.read().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests;
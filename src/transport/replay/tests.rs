@@ -0,0 +1,144 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use tempfile::TempDir;
+
+use crate::contracts::Contract;
+use crate::market_data::historical::WhatToShow;
+use crate::orders::{order_builder, Action, PlaceOrder};
+use crate::server_versions;
+use crate::stubs::MessageBusStub;
+use crate::testdata::responses::{MANAGED_ACCOUNT, MARKET_RULE};
+use crate::transport::recorder::MessageRecorder;
+use crate::Client;
+
+use super::*;
+
+const HEAD_TIMESTAMP: &str = "88|9000|1678323335|";
+const SERVER_TIME: &str = "49|1|1678890000|";
+const ORDER_STATUS: &str = "3|90|PreSubmitted|0|100|0|1376327563|0|0|100||0||";
+
+// Runs the exchange against a stub, returning the requests it sent.
+fn stubbed_exchange(responses: &[&str], mut exchange: impl FnMut(&Client)) -> Vec<RequestMessage> {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: responses.iter().map(|response| response.to_string()).collect(),
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::SIZE_RULES);
+    exchange(&client);
+    client.message_bus.request_messages()
+}
+
+// Records a session the way a live connection would: handshake, then each request followed by its responses.
+fn record_session(temp_dir: &TempDir, exchanges: &[(Vec<RequestMessage>, &str)]) -> PathBuf {
+    let temp_path = temp_dir.path().to_str().unwrap();
+
+    temp_env::with_var("IBAPI_RECORDING_DIR", Some(temp_path), || {
+        let recorder = MessageRecorder::new();
+
+        recorder.record_response(&ResponseMessage::from_simple(&format!(
+            "{}|20230405 22:20:39 PST|",
+            server_versions::SIZE_RULES
+        )));
+
+        let mut start_api = RequestMessage::new();
+        start_api.push_field(&OutgoingMessages::StartApi);
+        start_api.push_field(&2);
+        start_api.push_field(&100);
+        start_api.push_field(&"");
+        recorder.record_request(&start_api);
+
+        recorder.record_response(&ResponseMessage::from_simple(MANAGED_ACCOUNT));
+        recorder.record_response(&ResponseMessage::from_simple("9|1|90|"));
+
+        for (requests, response) in exchanges {
+            for request in requests {
+                recorder.record_request(request);
+            }
+            recorder.record_response(&ResponseMessage::from_simple(response));
+        }
+    });
+
+    let mut dirs = fs::read_dir(temp_dir.path()).unwrap().map(|entry| entry.unwrap().path());
+    dirs.next().expect("recording directory should be created")
+}
+
+#[test]
+fn test_replay_recorded_session() {
+    let contract = Contract::stock("MSFT");
+
+    let mut market_rule = None;
+    let market_rule_requests = stubbed_exchange(&[MARKET_RULE], |client| market_rule = Some(client.market_rule(26).unwrap()));
+    let mut server_time = None;
+    let server_time_requests = stubbed_exchange(&[SERVER_TIME], |client| server_time = Some(client.server_time().unwrap()));
+    let mut head_timestamp = None;
+    let head_timestamp_requests = stubbed_exchange(&[HEAD_TIMESTAMP], |client| {
+        head_timestamp = Some(client.head_timestamp(&contract, WhatToShow::Trades, true).unwrap())
+    });
+
+    let temp_dir = TempDir::new().unwrap();
+    let recording_dir = record_session(
+        &temp_dir,
+        &[
+            (market_rule_requests, MARKET_RULE),
+            (server_time_requests, SERVER_TIME),
+            (head_timestamp_requests, HEAD_TIMESTAMP),
+        ],
+    );
+
+    let client = Client::from_recording(&recording_dir).expect("recording should load");
+
+    assert_eq!(client.server_version(), server_versions::SIZE_RULES);
+    assert_eq!(client.next_order_id(), 90);
+
+    // Replayed in a different order than recorded.
+    assert_eq!(Some(client.server_time().unwrap()), server_time);
+    assert_eq!(format!("{:?}", client.market_rule(26).unwrap()), format!("{:?}", market_rule.unwrap()));
+    assert_eq!(Some(client.head_timestamp(&contract, WhatToShow::Trades, true).unwrap()), head_timestamp);
+
+    assert!(client.server_time().is_err(), "each recorded request is replayed once");
+}
+
+#[test]
+fn test_replay_drops_order_subscription() {
+    let contract = Contract::stock("TSLA");
+    let order = order_builder::market_order(Action::Buy, 100.0);
+
+    let place_order_requests = stubbed_exchange(&[], |client| {
+        client.place_order(90, &contract, &order).unwrap();
+    });
+
+    let temp_dir = TempDir::new().unwrap();
+    let recording_dir = record_session(&temp_dir, &[(place_order_requests, ORDER_STATUS)]);
+
+    let client = Client::from_recording(&recording_dir).expect("recording should load");
+
+    let subscription = client.place_order(90, &contract, &order).expect("place order failed");
+    assert!(matches!(subscription.next(), Some(PlaceOrder::OrderStatus(_))));
+    drop(subscription);
+
+    assert!(client.server_time().is_err(), "client should still be usable after the drop");
+}
+
+#[test]
+fn test_replay_unrecorded_request() {
+    let temp_dir = TempDir::new().unwrap();
+    let recording_dir = record_session(&temp_dir, &[]);
+
+    let client = Client::from_recording(&recording_dir).expect("recording should load");
+
+    match client.market_rule(26) {
+        Err(Error::Simple(message)) => assert!(message.contains("request not found in recording"), "{message}"),
+        other => panic!("expected missing request error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_replay_requires_handshake() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let result = Client::from_recording(temp_dir.path());
+    assert!(matches!(result, Err(Error::Simple(_))), "{:?}", result.err());
+}
@@ -23,14 +23,279 @@ fn test_parse_connection_time() {
 }
 
 #[test]
-fn test_fibonacci_backoff() {
-    let mut backoff = FibonacciBackoff::new(10);
-
-    assert_eq!(backoff.next_delay(), Duration::from_secs(1));
-    assert_eq!(backoff.next_delay(), Duration::from_secs(2));
-    assert_eq!(backoff.next_delay(), Duration::from_secs(3));
-    assert_eq!(backoff.next_delay(), Duration::from_secs(5));
-    assert_eq!(backoff.next_delay(), Duration::from_secs(8));
-    assert_eq!(backoff.next_delay(), Duration::from_secs(10));
-    assert_eq!(backoff.next_delay(), Duration::from_secs(10));
+fn test_exponential_backoff() {
+    let config = ConnectionConfig {
+        initial_backoff: Duration::from_secs(1),
+        max_backoff: Duration::from_secs(10),
+        jitter: 0.0,
+        ..ConnectionConfig::default()
+    };
+    let mut backoff = ExponentialBackoff::new(&config);
+
+    assert_eq!(backoff.next_delay(0.0), Duration::from_secs(1));
+    assert_eq!(backoff.next_delay(0.0), Duration::from_secs(2));
+    assert_eq!(backoff.next_delay(0.0), Duration::from_secs(4));
+    assert_eq!(backoff.next_delay(0.0), Duration::from_secs(8));
+    assert_eq!(backoff.next_delay(0.0), Duration::from_secs(10));
+    assert_eq!(backoff.next_delay(0.0), Duration::from_secs(10));
+
+    for _ in 0..100 {
+        assert_eq!(backoff.next_delay(0.0), Duration::from_secs(10), "should not overflow");
+    }
+}
+
+#[test]
+fn test_exponential_backoff_jitter() {
+    let config = ConnectionConfig {
+        initial_backoff: Duration::from_millis(500),
+        max_backoff: Duration::from_secs(30),
+        jitter: 0.2,
+        ..ConnectionConfig::default()
+    };
+
+    let mut backoff = ExponentialBackoff::new(&config);
+    assert_eq!(backoff.next_delay(0.5), Duration::from_millis(500), "midpoint sample is unjittered");
+    assert_eq!(backoff.next_delay(0.0), Duration::from_millis(800), "lowest sample removes 20%");
+    assert_eq!(backoff.next_delay(1.0), Duration::from_millis(2400), "highest sample adds 20%");
+
+    // jitter never exceeds the configured maximum
+    let mut backoff = ExponentialBackoff::new(&ConnectionConfig {
+        max_backoff: Duration::from_millis(500),
+        ..config
+    });
+    assert_eq!(backoff.next_delay(1.0), Duration::from_millis(500));
+}
+
+#[test]
+fn test_default_connection_config() {
+    let config = ConnectionConfig::default();
+
+    assert_eq!(config.max_retries, 20);
+    assert_eq!(config.initial_backoff, Duration::from_secs(1));
+    assert_eq!(config.max_backoff, Duration::from_secs(30));
+}
+
+#[test]
+fn test_subscription_buffer_drop_oldest() {
+    let buffer = SubscriptionBuffer {
+        capacity: 3,
+        overflow: OverflowPolicy::DropOldest,
+    };
+    let (sender, receiver) = response_channel(Some(buffer));
+
+    for i in 0..100 {
+        sender.send(Ok(ResponseMessage::from_simple(&format!("1|2|{i}|")))).unwrap();
+    }
+
+    let received: Vec<String> = receiver.try_iter().map(|response| response.unwrap().peek_string(2)).collect();
+    assert_eq!(received, vec!["97", "98", "99"], "only the newest responses should be kept");
+}
+
+#[test]
+fn test_subscription_buffer_block() {
+    let buffer = SubscriptionBuffer {
+        capacity: 2,
+        overflow: OverflowPolicy::Block,
+    };
+    let (sender, receiver) = response_channel(Some(buffer));
+
+    let producer = thread::spawn(move || {
+        for i in 0..10 {
+            sender.send(Ok(ResponseMessage::from_simple(&format!("1|2|{i}|")))).unwrap();
+        }
+    });
+
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(receiver.len(), 2, "producer should block once the buffer is full");
+    assert!(!producer.is_finished(), "producer should be waiting for room");
+
+    let received: Vec<String> = receiver.iter().take(10).map(|response| response.unwrap().peek_string(2)).collect();
+    let expected: Vec<String> = (0..10).map(|i| i.to_string()).collect();
+    assert_eq!(received, expected, "no responses should be lost");
+
+    producer.join().unwrap();
+}
+
+#[test]
+fn test_subscription_buffer_default_unbounded() {
+    assert_eq!(ConnectionConfig::default().subscription_buffer, None);
+
+    let (sender, receiver) = response_channel(None);
+    for i in 0..1000 {
+        sender.send(Ok(ResponseMessage::from_simple(&format!("1|2|{i}|")))).unwrap();
+    }
+    assert_eq!(receiver.len(), 1000);
+}
+
+fn blocking_buffer(capacity: usize) -> SubscriptionBuffer {
+    SubscriptionBuffer {
+        capacity,
+        overflow: OverflowPolicy::Block,
+    }
+}
+
+#[test]
+fn test_blocked_send_does_not_hold_senders_lock() {
+    let senders = Arc::new(SenderHash::<i32>::new());
+    let (sender, receiver) = response_channel(Some(blocking_buffer(1)));
+    senders.insert(1, sender);
+    senders.send(&1, Ok(ResponseMessage::from_simple("1|2|0|"))).unwrap();
+
+    let dispatcher = {
+        let senders = Arc::clone(&senders);
+        thread::spawn(move || senders.send(&1, Ok(ResponseMessage::from_simple("1|2|1|"))).unwrap())
+    };
+    thread::sleep(Duration::from_millis(50));
+    assert!(!dispatcher.is_finished(), "dispatcher should be waiting for room");
+
+    // A new request from the consumer registers its sender while the dispatcher is blocked.
+    let (other, _other_receiver) = response_channel(None);
+    senders.insert(2, other);
+    assert_eq!(senders.len(), 2);
+
+    let received: Vec<String> = receiver.iter().take(2).map(|response| response.unwrap().peek_string(2)).collect();
+    assert_eq!(received, vec!["0", "1"]);
+    dispatcher.join().unwrap();
+}
+
+#[test]
+fn test_notices_delivered_to_full_buffers() {
+    let senders = SenderHash::<i32>::new();
+    let (sender, receiver) = response_channel(Some(blocking_buffer(2)));
+    senders.insert(1, sender);
+    for i in 0..2 {
+        senders.send(&1, Ok(ResponseMessage::from_simple(&format!("1|2|{i}|")))).unwrap();
+    }
+
+    senders.send_notice(&1, Err(Error::Cancelled)).unwrap();
+    senders.notify_all(&Err(Error::Shutdown));
+
+    let received: Vec<Response> = receiver.try_iter().collect();
+    assert_eq!(received.len(), 2, "oldest responses should make room for the notices");
+    assert!(matches!(received[0], Err(Error::Cancelled)), "{:?}", received[0]);
+    assert!(matches!(received[1], Err(Error::Shutdown)), "{:?}", received[1]);
+}
+
+#[test]
+fn test_cancel_delivered_to_full_buffer() {
+    let (sender, receiver) = response_channel(Some(blocking_buffer(1)));
+    sender.send(Ok(ResponseMessage::from_simple("1|2|0|"))).unwrap();
+
+    let (signaler, _signals) = channel::unbounded();
+    let subscription = SubscriptionBuilder::new()
+        .receiver(receiver)
+        .sender(sender.sender.clone())
+        .signaler(signaler)
+        .build();
+
+    subscription.cancel();
+    assert!(matches!(subscription.try_next(), Some(Err(Error::Cancelled))));
+}
+
+#[test]
+fn test_subscription_buffer_override() {
+    let default = Some(blocking_buffer(100));
+    let buffer = SubscriptionBuffer {
+        capacity: 5,
+        overflow: OverflowPolicy::DropOldest,
+    };
+
+    assert_eq!(subscription_buffer(default), default);
+    with_subscription_buffer(Some(buffer), || {
+        assert_eq!(subscription_buffer(default), Some(buffer));
+        with_subscription_buffer(None, || assert_eq!(subscription_buffer(default), None, "unbounded override"));
+        assert_eq!(subscription_buffer(default), Some(buffer), "inner override should be restored");
+
+        let other_thread = thread::spawn(move || subscription_buffer(default)).join().unwrap();
+        assert_eq!(other_thread, default, "other threads should not see the override");
+    });
+    assert_eq!(subscription_buffer(default), default);
+}
+
+#[test]
+fn test_resync_after_reconnect() {
+    let resync = Resync::default();
+
+    let mut positions = RequestMessage::new();
+    positions.push_field(&OutgoingMessages::RequestPositions);
+    positions.push_field(&1);
+
+    let mut open_orders = RequestMessage::new();
+    open_orders.push_field(&OutgoingMessages::RequestAutoOpenOrders);
+    open_orders.push_field(&1);
+    open_orders.push_field(&true);
+
+    let mut market_data_type = RequestMessage::new();
+    market_data_type.push_field(&OutgoingMessages::RequestMarketDataType);
+    market_data_type.push_field(&1);
+    market_data_type.push_field(&3);
+
+    resync.track(OutgoingMessages::RequestPositions, &positions);
+    resync.track(OutgoingMessages::RequestAutoOpenOrders, &open_orders);
+    resync.track(OutgoingMessages::RequestMarketDataType, &market_data_type);
+
+    let hook_calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let calls = Arc::clone(&hook_calls);
+    resync.add_hook(Arc::new(move || {
+        calls.fetch_add(1, Ordering::SeqCst);
+    }));
+
+    // Simulate a reconnect.
+    let sent = Mutex::new(Vec::new());
+    resync.replay(|packet| {
+        sent.lock().unwrap().push(packet.encode_simple());
+        Ok(())
+    });
+
+    let mut sent = sent.into_inner().unwrap();
+    sent.sort();
+    assert_eq!(sent, vec!["15|1|1|", "61|1|"], "positions and auto open orders should be re-sent");
+    assert_eq!(hook_calls.load(Ordering::SeqCst), 1, "reconnect hook should run");
+}
+
+#[test]
+fn test_resync_skips_released_subscriptions() {
+    let resync = Resync::default();
+
+    let mut positions = RequestMessage::new();
+    positions.push_field(&OutgoingMessages::RequestPositions);
+    positions.push_field(&1);
+
+    resync.track(OutgoingMessages::RequestPositions, &positions);
+    resync.track(OutgoingMessages::RequestPositions, &positions);
+
+    let replayed = || {
+        let sent = Mutex::new(Vec::new());
+        resync.replay(|packet| {
+            sent.lock().unwrap().push(packet.encode_simple());
+            Ok(())
+        });
+        sent.into_inner().unwrap()
+    };
+
+    resync.release(OutgoingMessages::RequestPositions);
+    assert_eq!(replayed(), vec!["61|1|"], "request should be re-sent while a subscription is active");
+
+    resync.release(OutgoingMessages::RequestPositions);
+    assert!(replayed().is_empty(), "released subscriptions should not be re-sent");
+}
+
+#[test]
+fn test_connection_status_transitions() {
+    let status = ConnectionStatus::default();
+    assert_eq!(status.get(), ConnectionState::Connected, "bus is created after connecting");
+
+    // Simulate a dropped connection that is restored.
+    status.set(ConnectionState::Reconnecting);
+    assert_eq!(status.get(), ConnectionState::Reconnecting);
+    status.set(ConnectionState::Connected);
+    assert_eq!(status.get(), ConnectionState::Connected);
+
+    // Simulate a dropped connection that is not restored.
+    status.set(ConnectionState::Reconnecting);
+    status.set(ConnectionState::Disconnected);
+    assert_eq!(status.get(), ConnectionState::Disconnected);
+
+    status.set(ConnectionState::Connected);
+    assert_eq!(status.get(), ConnectionState::Disconnected, "disconnected is final");
 }
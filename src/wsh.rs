@@ -33,6 +33,114 @@ impl DataStream<WshMetadata> for WshMetadata {
     }
 }
 
+impl WshMetadata {
+    /// Event date window advertised in [data_json](WshMetadata::data_json), if any.
+    ///
+    /// Reads `start_date`, `end_date` (YYYYMMDD) and `max_days` from the top level or the `data` envelope.
+    /// Returns `None` when the metadata does not describe a window.
+    pub fn date_range(&self) -> Option<WshDateRange> {
+        let value: serde_json::Value = serde_json::from_str(&self.data_json).ok()?;
+        let bounds = match value.get("data") {
+            Some(data) if data.get("start_date").is_some() || data.get("end_date").is_some() || data.get("max_days").is_some() => data,
+            _ => &value,
+        };
+
+        let range = WshDateRange {
+            start: bounds.get("start_date").and_then(parse_wsh_date),
+            end: bounds.get("end_date").and_then(parse_wsh_date),
+            max_days: bounds
+                .get("max_days")
+                .and_then(|days| days.as_i64().or_else(|| days.as_str()?.parse().ok())),
+        };
+
+        if range == WshDateRange::default() {
+            None
+        } else {
+            Some(range)
+        }
+    }
+
+    /// Checks a requested event date range against the window advertised in the metadata.
+    ///
+    /// Always rejects a start date after the end date. Other checks are skipped when the metadata
+    /// has no [date_range](WshMetadata::date_range).
+    pub fn validate_date_range(&self, start_date: Option<Date>, end_date: Option<Date>) -> Result<(), Error> {
+        validate_date_order(start_date, end_date)?;
+
+        match self.date_range() {
+            Some(range) => range.validate(start_date, end_date),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Event dates the WSH calendar will serve, as advertised by [WshMetadata::date_range].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct WshDateRange {
+    /// Earliest event date available.
+    pub start: Option<Date>,
+    /// Latest event date available.
+    pub end: Option<Date>,
+    /// Widest span, in days, allowed between start and end dates.
+    pub max_days: Option<i64>,
+}
+
+impl WshDateRange {
+    /// Rejects requested dates that fall outside this range.
+    pub fn validate(&self, start_date: Option<Date>, end_date: Option<Date>) -> Result<(), Error> {
+        validate_date_order(start_date, end_date)?;
+
+        for date in [start_date, end_date].into_iter().flatten() {
+            if let Some(start) = self.start {
+                if date < start {
+                    return Err(Error::InvalidArgument(format!(
+                        "WSH event date {date} is before the earliest available date {start}"
+                    )));
+                }
+            }
+            if let Some(end) = self.end {
+                if date > end {
+                    return Err(Error::InvalidArgument(format!(
+                        "WSH event date {date} is after the latest available date {end}"
+                    )));
+                }
+            }
+        }
+
+        if let (Some(max_days), Some(start_date), Some(end_date)) = (self.max_days, start_date, end_date) {
+            let days = (end_date - start_date).whole_days();
+            if days > max_days {
+                return Err(Error::InvalidArgument(format!(
+                    "WSH event date range {start_date} to {end_date} spans {days} days, the maximum is {max_days}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn validate_date_order(start_date: Option<Date>, end_date: Option<Date>) -> Result<(), Error> {
+    if let (Some(start_date), Some(end_date)) = (start_date, end_date) {
+        if start_date > end_date {
+            return Err(Error::InvalidArgument(format!(
+                "WSH event start date {start_date} is after end date {end_date}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn parse_wsh_date(value: &serde_json::Value) -> Option<Date> {
+    let text = match value {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Number(number) => number.to_string(),
+        _ => return None,
+    };
+    let format = time::macros::format_description!("[year][month][day]");
+    Date::parse(text.trim(), format).ok()
+}
+
 pub(super) fn wsh_metadata(client: &Client) -> Result<WshMetadata, Error> {
     client.check_server_version(server_versions::WSHE_CALENDAR, "It does not support WSHE Calendar API.")?;
 
@@ -53,6 +161,77 @@ pub struct WshEventData {
     pub data_json: String,
 }
 
+impl WshEventData {
+    /// Deserializes the events in [data_json](WshEventData::data_json).
+    ///
+    /// Accepts a JSON array of events, a single event object, or the `{"data": {"events": [...]}}` envelope.
+    /// Fields not modeled by [WshEvent] are kept in [WshEvent::other].
+    pub fn parse(&self) -> Result<Vec<WshEvent>, Error> {
+        let value: serde_json::Value = serde_json::from_str(&self.data_json).map_err(|e| Error::Simple(format!("invalid WSH event data: {e}")))?;
+
+        let events = match value {
+            serde_json::Value::Object(mut object) if object.contains_key("data") => match object.remove("data") {
+                Some(serde_json::Value::Object(mut data)) => data.remove("events").unwrap_or(serde_json::Value::Array(vec![])),
+                Some(other) => other,
+                None => serde_json::Value::Array(vec![]),
+            },
+            other => other,
+        };
+
+        let events = match events {
+            serde_json::Value::Array(events) => events,
+            event => vec![event],
+        };
+
+        events
+            .into_iter()
+            .map(|event| serde_json::from_value(event).map_err(|e| Error::Simple(format!("invalid WSH event: {e}"))))
+            .collect()
+    }
+}
+
+/// Event from the Wall Street Horizon calendar, such as an earnings date or dividend.
+///
+/// The WSH schema varies by event type, so all fields are optional.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WshEvent {
+    /// Event type, e.g. `wshe_ed` for earnings dates.
+    pub event_type: Option<String>,
+    /// Ticker symbol of the company.
+    pub ticker: Option<String>,
+    /// IB contract identifier.
+    #[serde(default, deserialize_with = "lenient_i64")]
+    pub conid: Option<i64>,
+    /// Event date, as sent by the feed (YYYYMMDD).
+    #[serde(default, deserialize_with = "lenient_string")]
+    pub date: Option<String>,
+    /// Event time of day, when known.
+    #[serde(default, deserialize_with = "lenient_string")]
+    pub time: Option<String>,
+    /// Human readable description of the event.
+    pub description: Option<String>,
+    /// Remaining fields of the event.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+// The feed is not consistent about quoting numbers, so accept either representation.
+fn lenient_string<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::String(value)) => Ok(Some(value)),
+        Some(serde_json::Value::Number(value)) => Ok(Some(value.to_string())),
+        _ => Ok(None),
+    }
+}
+
+fn lenient_i64<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+    match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::Number(value)) => Ok(value.as_i64()),
+        Some(serde_json::Value::String(value)) => Ok(value.parse().ok()),
+        _ => Ok(None),
+    }
+}
+
 fn decode_event_data_message(message: crate::messages::ResponseMessage) -> Result<WshEventData, Error> {
     match message.message_type() {
         IncomingMessages::WshEventData => decoders::decode_wsh_event_data(message),
@@ -97,6 +276,7 @@ pub(super) fn wsh_event_data_by_contract(
     auto_fill: Option<AutoFill>,
 ) -> Result<WshEventData, Error> {
     client.check_server_version(server_versions::WSHE_CALENDAR, "It does not support WSHE Calendar API.")?;
+    validate_date_order(start_date, end_date)?;
 
     if client.server_version < server_versions::WSH_EVENT_DATA_FILTERS && auto_fill.is_some() {
         let message = "It does not support WSH event data filters.".to_string();
@@ -140,12 +320,15 @@ pub(super) fn wsh_event_data_by_contract(
 pub(super) fn wsh_event_data_by_filter<'a>(
     client: &'a Client,
     filter: &str,
+    start_date: Option<Date>,
+    end_date: Option<Date>,
     limit: Option<i32>,
     auto_fill: Option<AutoFill>,
 ) -> Result<Subscription<'a, WshEventData>, Error> {
     client.check_server_version(server_versions::WSH_EVENT_DATA_FILTERS, "It does not support WSH event data filters.")?;
+    validate_date_order(start_date, end_date)?;
 
-    if client.server_version < server_versions::WSH_EVENT_DATA_FILTERS_DATE && limit.is_some() {
+    if client.server_version < server_versions::WSH_EVENT_DATA_FILTERS_DATE && (start_date.is_some() || end_date.is_some() || limit.is_some()) {
         let message = "It does not support WSH event data date filters.".to_string();
         return Err(Error::ServerVersion(
             server_versions::WSH_EVENT_DATA_FILTERS_DATE,
@@ -155,7 +338,16 @@ pub(super) fn wsh_event_data_by_filter<'a>(
     }
 
     let request_id = client.next_request_id();
-    let request = encoders::encode_request_wsh_event_data(client.server_version, request_id, None, Some(filter), None, None, limit, auto_fill)?;
+    let request = encoders::encode_request_wsh_event_data(
+        client.server_version,
+        request_id,
+        None,
+        Some(filter),
+        start_date,
+        end_date,
+        limit,
+        auto_fill,
+    )?;
     let subscription = client.send_request(request_id, request)?;
 
     Ok(Subscription::new(client, subscription, ResponseContext::default()))
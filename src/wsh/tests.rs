@@ -94,6 +94,8 @@ fn test_wsh_event_data_by_filter() {
     let result = wsh_event_data_by_filter(
         &client,
         filter,
+        None,
+        None,
         Some(100),
         Some(AutoFill {
             competitors: true,
@@ -117,7 +119,7 @@ fn test_wsh_event_data_by_filter_no_autofill() {
 
     let client = Client::stubbed(message_bus, server_versions::WSH_EVENT_DATA_FILTERS);
     let filter = "filter=value";
-    let result = wsh_event_data_by_filter(&client, filter, None, None);
+    let result = wsh_event_data_by_filter(&client, filter, None, None, None, None);
 
     let request_messages = client.message_bus.request_messages();
     assert_eq!(request_messages[0].encode_simple(), "102|9000||filter=value|0|0|0|");
@@ -125,6 +127,47 @@ fn test_wsh_event_data_by_filter_no_autofill() {
     assert!(result.is_ok(), "failed to request wsh event data by filter: {}", result.err().unwrap());
 }
 
+#[test]
+fn test_wsh_event_data_by_filter_dates() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec!["105|9000|{\"validated\":true,\"data\":{\"events\":[]}}|".to_owned()],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::WSH_EVENT_DATA_FILTERS_DATE);
+    let start_date = Some(date!(2024 - 01 - 01));
+    let end_date = Some(date!(2024 - 01 - 31));
+    let auto_fill = Some(AutoFill {
+        competitors: false,
+        portfolio: true,
+        watchlist: false,
+    });
+
+    let result = wsh_event_data_by_filter(&client, "filter=value", start_date, end_date, Some(50), auto_fill);
+    assert!(result.is_ok(), "failed to request wsh event data by filter: {}", result.err().unwrap());
+
+    let expected = encoders::encode_request_wsh_event_data(
+        server_versions::WSH_EVENT_DATA_FILTERS_DATE,
+        9000,
+        None,
+        Some("filter=value"),
+        start_date,
+        end_date,
+        Some(50),
+        auto_fill,
+    )
+    .unwrap();
+    let request_messages = client.message_bus.request_messages();
+    assert_eq!(request_messages[0].encode_simple(), expected.encode_simple());
+    assert_eq!(request_messages[0].encode_simple(), "102|9000||filter=value|0|1|0|20240101|20240131|50|");
+
+    let result = wsh_event_data_by_filter(&client, "filter=value", end_date, start_date, None, None);
+    assert!(
+        matches!(result, Err(Error::InvalidArgument(_))),
+        "start date after end date should be rejected"
+    );
+}
+
 #[test]
 fn test_invalid_server_version_wsh_metadata() {
     let message_bus = Arc::new(MessageBusStub {
@@ -146,7 +189,7 @@ fn test_invalid_server_version_wsh_event_data_filters() {
     });
 
     let client = Client::stubbed(message_bus, server_versions::WSHE_CALENDAR);
-    let result = wsh_event_data_by_filter(&client, "filter", None, None);
+    let result = wsh_event_data_by_filter(&client, "filter", None, None, None, None);
 
     assert!(matches!(result, Err(Error::ServerVersion(_, _, _))));
 }
@@ -162,6 +205,10 @@ fn test_invalid_server_version_wsh_event_data_date_filters() {
     let result = wsh_event_data_by_contract(&client, 12345, Some(date!(2024 - 01 - 01)), Some(date!(2024 - 12 - 31)), Some(100), None);
 
     assert!(matches!(result, Err(Error::ServerVersion(_, _, _))));
+
+    let result = wsh_event_data_by_filter(&client, "filter", Some(date!(2024 - 01 - 01)), None, None, None);
+
+    assert!(matches!(result, Err(Error::ServerVersion(_, _, _))));
 }
 
 #[test]
@@ -266,3 +313,130 @@ fn test_encode_cancel_wsh_event_data() {
     assert!(result.is_ok());
     assert_eq!(result.unwrap().encode_simple(), "103|9000|");
 }
+
+#[test]
+fn test_parse_wsh_event_data() {
+    let event_data = WshEventData {
+        data_json: r#"[
+            {"event_type":"wshe_ed","ticker":"IBM","conid":8314,"date":"20240424","time":"AMC","description":"Q1 2024 Earnings","fiscal_period":"Q1","confirmed":true},
+            {"event_type":"wshe_div","ticker":"IBM","conid":"8314","date":20240509,"amount":1.67}
+        ]"#
+        .to_owned(),
+    };
+
+    let events = event_data.parse().expect("failed to parse wsh event data");
+
+    assert_eq!(events.len(), 2);
+
+    assert_eq!(events[0].event_type.as_deref(), Some("wshe_ed"));
+    assert_eq!(events[0].ticker.as_deref(), Some("IBM"));
+    assert_eq!(events[0].conid, Some(8314));
+    assert_eq!(events[0].date.as_deref(), Some("20240424"));
+    assert_eq!(events[0].time.as_deref(), Some("AMC"));
+    assert_eq!(events[0].description.as_deref(), Some("Q1 2024 Earnings"));
+    assert_eq!(events[0].other["fiscal_period"], "Q1");
+    assert_eq!(events[0].other["confirmed"], true);
+
+    assert_eq!(events[1].event_type.as_deref(), Some("wshe_div"));
+    assert_eq!(events[1].conid, Some(8314), "string conid");
+    assert_eq!(events[1].date.as_deref(), Some("20240509"), "numeric date");
+    assert_eq!(events[1].time, None);
+    assert_eq!(events[1].other["amount"], 1.67);
+}
+
+#[test]
+fn test_parse_wsh_event_data_envelope() {
+    let event_data = WshEventData {
+        data_json: "{\"validated\":true,\"data\":{\"events\":[]}}".to_owned(),
+    };
+    assert_eq!(event_data.parse().unwrap(), vec![]);
+
+    let event_data = WshEventData {
+        data_json: r#"{"validated":true,"data":{"events":[{"event_type":"wshe_ed","conid":8314}]}}"#.to_owned(),
+    };
+    let events = event_data.parse().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].conid, Some(8314));
+
+    let event_data = WshEventData {
+        data_json: r#"{"event_type":"wshe_ed","ticker":"AAPL"}"#.to_owned(),
+    };
+    let events = event_data.parse().unwrap();
+    assert_eq!(events[0].ticker.as_deref(), Some("AAPL"));
+
+    let event_data = WshEventData {
+        data_json: "not json".to_owned(),
+    };
+    assert!(event_data.parse().is_err());
+}
+
+#[test]
+fn test_wsh_metadata_date_range() {
+    let metadata = WshMetadata {
+        data_json: r#"{"validated":true,"data":{"start_date":"20240101","end_date":20241231,"max_days":"90"}}"#.to_owned(),
+    };
+
+    assert_eq!(
+        metadata.date_range(),
+        Some(WshDateRange {
+            start: Some(date!(2024 - 01 - 01)),
+            end: Some(date!(2024 - 12 - 31)),
+            max_days: Some(90),
+        })
+    );
+
+    let metadata = WshMetadata {
+        data_json: "{\"validated\":true,\"data\":{\"metadata\":\"test\"}}".to_owned(),
+    };
+    assert_eq!(metadata.date_range(), None);
+    assert!(metadata
+        .validate_date_range(Some(date!(2020 - 01 - 01)), Some(date!(2030 - 01 - 01)))
+        .is_ok());
+}
+
+#[test]
+fn test_wsh_metadata_rejects_out_of_range_dates() {
+    let metadata = WshMetadata {
+        data_json: r#"{"data":{"start_date":"20240101","end_date":"20241231","max_days":90}}"#.to_owned(),
+    };
+
+    assert!(metadata
+        .validate_date_range(Some(date!(2024 - 03 - 01)), Some(date!(2024 - 04 - 01)))
+        .is_ok());
+    assert!(metadata.validate_date_range(None, None).is_ok());
+
+    let too_wide = metadata.validate_date_range(Some(date!(2024 - 01 - 01)), Some(date!(2024 - 06 - 30)));
+    assert!(
+        matches!(too_wide, Err(Error::InvalidArgument(ref message)) if message.contains("maximum is 90")),
+        "{too_wide:?}"
+    );
+
+    let too_early = metadata.validate_date_range(Some(date!(2023 - 12 - 01)), None);
+    assert!(
+        matches!(too_early, Err(Error::InvalidArgument(ref message)) if message.contains("earliest")),
+        "{too_early:?}"
+    );
+
+    let too_late = metadata.validate_date_range(None, Some(date!(2025 - 01 - 15)));
+    assert!(
+        matches!(too_late, Err(Error::InvalidArgument(ref message)) if message.contains("latest")),
+        "{too_late:?}"
+    );
+
+    let reversed = metadata.validate_date_range(Some(date!(2024 - 04 - 01)), Some(date!(2024 - 03 - 01)));
+    assert!(matches!(reversed, Err(Error::InvalidArgument(_))), "{reversed:?}");
+}
+
+#[test]
+fn test_wsh_event_data_by_contract_rejects_reversed_dates() {
+    let message_bus = Arc::new(MessageBusStub {
+        request_messages: RwLock::new(vec![]),
+        response_messages: vec![],
+    });
+
+    let client = Client::stubbed(message_bus, server_versions::WSH_EVENT_DATA_FILTERS_DATE);
+    let result = wsh_event_data_by_contract(&client, 76792991, Some(date!(2024 - 04 - 01)), Some(date!(2024 - 03 - 01)), None, None);
+
+    assert!(matches!(result, Err(Error::InvalidArgument(_))), "{result:?}");
+    assert!(client.message_bus.request_messages().is_empty());
+}